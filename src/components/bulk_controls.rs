@@ -0,0 +1,57 @@
+use dioxus::prelude::*;
+
+use crate::utils::{BulkReport, BulkStatus};
+
+/// Bar shown while rows are checked. The bulk action buttons are passed as
+/// children.
+#[component]
+pub fn BulkBar(count: usize, busy: bool, onclear: EventHandler<()>, children: Element) -> Element {
+    rsx! {
+        div { class: "bulk-bar",
+            span { class: "bulk-count", "{count} selected" }
+            if busy {
+                span { class: "card-hint", "Waiting for the previous bulk action…" }
+            } else {
+                {children}
+            }
+            button { class: "button secondary", onclick: move |_| onclear.call(()), "Clear selection" }
+        }
+    }
+}
+
+/// Outcome of each item of a bulk action, updated as the calls finish.
+#[component]
+pub fn BulkReportPanel(report: BulkReport, ondismiss: EventHandler<()>) -> Element {
+    let finished = report.pending() == 0;
+
+    rsx! {
+        div { class: "card bulk-report",
+            div { class: "bulk-report-header",
+                strong { {report.summary()} }
+                if finished {
+                    button { class: "button secondary", onclick: move |_| ondismiss.call(()), "Dismiss" }
+                }
+            }
+            for item in report.items.iter() {
+                div { class: "bulk-report-item",
+                    match &item.status {
+                        BulkStatus::Pending => rsx! {
+                            span { class: "pill pending", "…" }
+                        },
+                        BulkStatus::Done => rsx! {
+                            span { class: "pill running", "done" }
+                        },
+                        BulkStatus::Failed(_) => rsx! {
+                            span { class: "pill failed", "failed" }
+                        },
+                    }
+                    span { class: "bulk-report-name", "{item.target.name}" }
+                    span { class: "card-hint", "{item.target.engine}" }
+                    if let BulkStatus::Failed(message) = &item.status {
+                        span { class: "error-message", "{message}" }
+                    }
+                }
+            }
+        }
+    }
+}
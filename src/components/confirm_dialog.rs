@@ -0,0 +1,38 @@
+use dioxus::prelude::*;
+
+/// Modal asking the user to confirm an action. Extra options for the action
+/// can be passed as children and are shown above the buttons.
+#[component]
+pub fn ConfirmDialog(
+    title: String,
+    message: String,
+    confirm_label: String,
+    onconfirm: EventHandler<()>,
+    oncancel: EventHandler<()>,
+    children: Element,
+) -> Element {
+    rsx! {
+        div { class: "modal-backdrop", onclick: move |_| oncancel.call(()),
+            div {
+                class: "modal card",
+                // Clicks inside the dialog must not reach the backdrop
+                onclick: move |event| event.stop_propagation(),
+                h3 { "{title}" }
+                p { class: "modal-message", "{message}" }
+                {children}
+                div { class: "button-row",
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| oncancel.call(()),
+                        "Cancel"
+                    }
+                    button {
+                        class: "button danger",
+                        onclick: move |_| onconfirm.call(()),
+                        "{confirm_label}"
+                    }
+                }
+            }
+        }
+    }
+}
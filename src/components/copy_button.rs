@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+
+/// Answers whether the write went through; it fails without clipboard
+/// permission or while the window is not focused.
+const WRITE_CLIPBOARD: &str = r#"
+    const text = await dioxus.recv();
+    try {
+        await navigator.clipboard.writeText(text);
+        dioxus.send(true);
+    } catch (error) {
+        dioxus.send(false);
+    }
+"#;
+
+/// Small button that copies `value` to the clipboard.
+#[component]
+pub fn CopyButton(value: String) -> Element {
+    // Outcome of the latest copy, shown for a moment
+    let mut copied = use_signal(|| None::<bool>);
+    let label = match copied() {
+        Some(true) => "Copied",
+        Some(false) => "Copy failed",
+        None => "Copy",
+    };
+
+    rsx! {
+        button {
+            class: "copy-button",
+            title: "Copy to clipboard",
+            onclick: move |_| {
+                let value = value.clone();
+                spawn(async move {
+                    let mut clipboard = document::eval(WRITE_CLIPBOARD);
+                    let written = clipboard.send(value).is_ok()
+                        && clipboard.recv::<bool>().await.unwrap_or(false);
+                    copied.set(Some(written));
+                    tokio::time::sleep(Duration::from_millis(1500)).await;
+                    copied.set(None);
+                });
+            },
+            {label}
+        }
+    }
+}
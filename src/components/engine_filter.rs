@@ -0,0 +1,27 @@
+use dioxus::prelude::*;
+
+/// Drop-down that narrows a resource table to one engine. `None` means all.
+#[component]
+pub fn EngineFilter(
+    engines: Vec<String>,
+    selected: Option<String>,
+    onchange: EventHandler<Option<String>>,
+) -> Element {
+    rsx! {
+        select {
+            class: "engine-filter",
+            onchange: move |event| {
+                let value = event.value();
+                onchange.call((!value.is_empty()).then_some(value));
+            },
+            option { value: "", selected: selected.is_none(), "All engines" }
+            for engine in engines {
+                option {
+                    value: "{engine}",
+                    selected: selected.as_deref() == Some(engine.as_str()),
+                    "{engine}"
+                }
+            }
+        }
+    }
+}
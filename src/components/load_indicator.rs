@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+
+use crate::utils::{AppState, Listing, LoadState, format_age, unix_now};
+
+/// Spinner while `list` is loading, otherwise how long ago it was loaded or
+/// that the latest load failed.
+#[component]
+pub fn LoadIndicator(list: Listing) -> Element {
+    let app_state = use_context::<AppState>();
+    let state = app_state.loads.read().of(list);
+    // Ticks so the age keeps counting up between loads
+    let mut now = use_signal(unix_now);
+    use_hook(move || {
+        spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                now.set(unix_now());
+            }
+        });
+    });
+
+    rsx! {
+        match state {
+            LoadState::Idle => rsx! {},
+            LoadState::Loading => rsx! {
+                span { class: "load-indicator",
+                    span { class: "spinner" }
+                    "Loading…"
+                }
+            },
+            LoadState::Loaded(at) => {
+                let age = format_age(at, now());
+                rsx! {
+                    span { class: "load-indicator", "Updated {age}" }
+                }
+            }
+            LoadState::Failed { at, message } => {
+                let age = format_age(at, now());
+                rsx! {
+                    span { class: "load-indicator failed", title: "{message}", "Update failed {age}" }
+                }
+            }
+        }
+    }
+}
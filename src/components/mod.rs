@@ -1,10 +1,31 @@
 //! Shared UI building blocks for the app shell and pages.
 
+mod bulk_controls;
+pub use bulk_controls::{BulkBar, BulkReportPanel};
+
+mod confirm_dialog;
+pub use confirm_dialog::ConfirmDialog;
+
+mod copy_button;
+pub use copy_button::CopyButton;
+
+mod engine_filter;
+pub use engine_filter::EngineFilter;
+
+mod load_indicator;
+pub use load_indicator::LoadIndicator;
+
 mod metric_card;
 pub use metric_card::MetricCard;
 
+mod notification_center;
+pub use notification_center::NotificationCenter;
+
 mod section_header;
 pub use section_header::SectionHeader;
 
 mod status_pill;
 pub use status_pill::StatusPill;
+
+mod table_controls;
+pub use table_controls::{SortHeader, TableToolbar};
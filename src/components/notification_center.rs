@@ -0,0 +1,99 @@
+use dioxus::prelude::*;
+
+use crate::utils::{AppState, Notification, format_age, unix_now};
+
+/// Bell button with the notification count, the panel it opens, and the
+/// toasts of new notifications.
+#[component]
+pub fn NotificationCenter() -> Element {
+    let app_state = use_context::<AppState>();
+    let mut notifications = app_state.notifications;
+    let mut open = use_signal(|| false);
+    let listed: Vec<Notification> = notifications.read().iter().cloned().collect();
+    let toasts: Vec<Notification> = notifications.read().toasts().take(3).cloned().collect();
+    let badge = notifications
+        .read()
+        .highest()
+        .map(|severity| severity.css_class())
+        .unwrap_or("info");
+    let count = notifications.read().len();
+    let now = unix_now();
+
+    rsx! {
+        div { class: "notification-center",
+            button {
+                class: "button secondary notification-button",
+                title: "Notifications",
+                onclick: move |_| open.toggle(),
+                "🔔"
+                if count > 0 {
+                    span { class: "notification-badge {badge}", "{count}" }
+                }
+            }
+
+            if open() {
+                div { class: "notification-panel",
+                    div { class: "notification-panel-header",
+                        strong { "Notifications" }
+                        if count > 0 {
+                            button {
+                                class: "button secondary",
+                                onclick: move |_| notifications.write().clear(),
+                                "Clear all"
+                            }
+                        }
+                    }
+                    if notifications.read().is_empty() {
+                        p { class: "card-hint", "Nothing to report." }
+                    }
+                    for notification in listed {
+                        NotificationItem {
+                            key: "{notification.id}",
+                            age: format_age(notification.at, now),
+                            notification,
+                        }
+                    }
+                }
+            }
+        }
+
+        div { class: "toast-stack",
+            for notification in toasts {
+                NotificationItem {
+                    key: "{notification.id}",
+                    age: format_age(notification.at, now),
+                    notification,
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn NotificationItem(notification: Notification, age: String) -> Element {
+    let mut notifications = use_context::<AppState>().notifications;
+    let id = notification.id;
+    let severity = notification.severity.css_class();
+
+    rsx! {
+        div { class: "notification {severity}",
+            div { class: "notification-header",
+                strong { "{notification.title}" }
+                if notification.count > 1 {
+                    span { class: "pill {severity}", "×{notification.count}" }
+                }
+                span { class: "card-hint", "{age}" }
+                button {
+                    class: "notification-dismiss",
+                    title: "Dismiss",
+                    onclick: move |_| notifications.write().dismiss(id),
+                    "✕"
+                }
+            }
+            p { class: "notification-message", "{notification.message}" }
+            if let Some(hint) = &notification.hint {
+                p { class: "card-hint", "{hint}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use dioxus::prelude::*;
+
+use crate::utils::{SortColumn, SortOrder, TableView};
+
+use super::EngineFilter;
+
+/// Filter bar of a resource table: engine, free text, state and label
+/// facets. Extra controls such as "Refresh" are passed as children.
+#[component]
+pub fn TableToolbar(
+    view: TableView,
+    engines: Vec<String>,
+    states: Vec<String>,
+    labels: Vec<String>,
+    shown: usize,
+    total: usize,
+    onchange: EventHandler<TableView>,
+    children: Element,
+) -> Element {
+    let filtered = view.is_filtered();
+    let selected_state = view.state.clone().unwrap_or_default();
+    let selected_label = view.label.clone().unwrap_or_default();
+    let for_engine = view.clone();
+    let for_query = view.clone();
+    let for_state = view.clone();
+    let for_label = view.clone();
+    let for_clear = view.clone();
+
+    rsx! {
+        div { class: "action-bar table-toolbar",
+            EngineFilter {
+                engines,
+                selected: view.engine.clone(),
+                onchange: move |engine| onchange.call(TableView { engine, ..for_engine.clone() })
+            }
+            input {
+                class: "text-input table-search",
+                r#type: "search",
+                placeholder: "Filter…",
+                value: "{view.query}",
+                oninput: move |event| onchange.call(TableView { query: event.value(), ..for_query.clone() })
+            }
+            if !states.is_empty() {
+                select {
+                    class: "engine-filter",
+                    onchange: move |event| {
+                        let value = event.value();
+                        let state = (!value.is_empty()).then_some(value);
+                        onchange.call(TableView { state, ..for_state.clone() });
+                    },
+                    option { value: "", selected: selected_state.is_empty(), "All states" }
+                    for state in states {
+                        option { value: "{state}", selected: state == selected_state, "{state}" }
+                    }
+                }
+            }
+            if !labels.is_empty() {
+                select {
+                    class: "engine-filter",
+                    onchange: move |event| {
+                        let value = event.value();
+                        let label = (!value.is_empty()).then_some(value);
+                        onchange.call(TableView { label, ..for_label.clone() });
+                    },
+                    option { value: "", selected: selected_label.is_empty(), "All labels" }
+                    for label in labels {
+                        option { value: "{label}", selected: label == selected_label, "{label}" }
+                    }
+                }
+            }
+            if filtered {
+                button {
+                    class: "button secondary",
+                    onclick: move |_| onchange.call(TableView { sort: for_clear.sort, ..Default::default() }),
+                    "Clear filters"
+                }
+            }
+            {children}
+            span { class: "table-count", "{shown} of {total}" }
+        }
+    }
+}
+
+/// Header cell that sorts the table by `column` when clicked.
+#[component]
+pub fn SortHeader(
+    label: String,
+    column: SortColumn,
+    sort: Option<(SortColumn, SortOrder)>,
+    onsort: EventHandler<SortColumn>,
+) -> Element {
+    let arrow = match sort {
+        Some((current, SortOrder::Ascending)) if current == column => " ▲",
+        Some((current, SortOrder::Descending)) if current == column => " ▼",
+        _ => "",
+    };
+
+    rsx! {
+        button { class: "sort-header", onclick: move |_| onsort.call(column), "{label}{arrow}" }
+    }
+}
@@ -3,7 +3,9 @@
 use dioxus::prelude::*;
 
 use utils::AppState;
-use views::{AppShell, Containers, Dashboard, Images, Settings, Volumes};
+use views::{
+    AppShell, ContainerDetail, Containers, Dashboard, Images, RunContainer, Settings, Volumes,
+};
 
 /// Define a components module that contains all shared components for our app.
 mod components;
@@ -29,6 +31,10 @@ enum Route {
         Dashboard {},
         #[route("/containers")]
         Containers {},
+        #[route("/containers/run")]
+        RunContainer {},
+        #[route("/containers/:engine/:id")]
+        ContainerDetail { engine: String, id: String },
         #[route("/images")]
         Images {},
         #[route("/volumes")]
@@ -44,6 +50,8 @@ const FAVICON: Asset = asset!("/assets/icon.svg");
 const MAIN_CSS: Asset = asset!("/assets/styling/main.css");
 
 fn main() {
+    services::install_crypto_provider();
+
     // The `launch` function is the main entry point for a dioxus app. It takes a component and renders it with the platform feature
     // you have enabled
     dioxus::launch(App);
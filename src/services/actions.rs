@@ -0,0 +1,116 @@
+//! Lifecycle actions that can be run on a container, with their options.
+
+/// Seconds the engine waits for a container to exit before killing it on
+/// stop and restart, matching `docker stop`.
+pub const DEFAULT_STOP_TIMEOUT_SECS: u32 = 10;
+
+/// Signals offered when killing a container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KillSignal {
+    Kill,
+    Term,
+    Int,
+    Hup,
+    Quit,
+    Usr1,
+    Usr2,
+}
+
+impl KillSignal {
+    pub const ALL: [KillSignal; 7] = [
+        KillSignal::Kill,
+        KillSignal::Term,
+        KillSignal::Int,
+        KillSignal::Hup,
+        KillSignal::Quit,
+        KillSignal::Usr1,
+        KillSignal::Usr2,
+    ];
+
+    /// Name as sent to the engine, e.g. `SIGKILL`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            KillSignal::Kill => "SIGKILL",
+            KillSignal::Term => "SIGTERM",
+            KillSignal::Int => "SIGINT",
+            KillSignal::Hup => "SIGHUP",
+            KillSignal::Quit => "SIGQUIT",
+            KillSignal::Usr1 => "SIGUSR1",
+            KillSignal::Usr2 => "SIGUSR2",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|signal| signal.name() == name)
+    }
+
+    /// Linux signal number, used for the exit code (128 + n) of the fake engine.
+    #[cfg(test)]
+    pub fn number(&self) -> i64 {
+        match self {
+            KillSignal::Hup => 1,
+            KillSignal::Int => 2,
+            KillSignal::Quit => 3,
+            KillSignal::Kill => 9,
+            KillSignal::Usr1 => 10,
+            KillSignal::Usr2 => 12,
+            KillSignal::Term => 15,
+        }
+    }
+}
+
+/// Options of `docker rm`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemoveOptions {
+    /// Kill the container first if it is still running.
+    pub force: bool,
+    /// Also remove the anonymous volumes attached to the container.
+    pub volumes: bool,
+}
+
+/// Lifecycle calls a container row can trigger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainerAction {
+    Start,
+    Stop,
+    Restart { timeout_secs: u32 },
+    Pause,
+    Unpause,
+    Kill(KillSignal),
+    Remove(RemoveOptions),
+}
+
+impl ContainerAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerAction::Start => "Start",
+            ContainerAction::Stop => "Stop",
+            ContainerAction::Restart { .. } => "Restart",
+            ContainerAction::Pause => "Pause",
+            ContainerAction::Unpause => "Unpause",
+            ContainerAction::Kill(_) => "Kill",
+            ContainerAction::Remove(_) => "Remove",
+        }
+    }
+
+    /// Past tense, for the last-action message.
+    pub fn done_label(&self) -> &'static str {
+        match self {
+            ContainerAction::Start => "Started",
+            ContainerAction::Stop => "Stopped",
+            ContainerAction::Restart { .. } => "Restarted",
+            ContainerAction::Pause => "Paused",
+            ContainerAction::Unpause => "Unpaused",
+            ContainerAction::Kill(_) => "Killed",
+            ContainerAction::Remove(_) => "Removed",
+        }
+    }
+
+    /// Actions that interrupt or destroy a container are confirmed first.
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(
+            self,
+            ContainerAction::Restart { .. } | ContainerAction::Kill(_) | ContainerAction::Remove(_)
+        )
+    }
+}
@@ -0,0 +1,274 @@
+//! Docker CLI contexts, read from `~/.docker` (or `DOCKER_CONFIG`) the same
+//! way `docker context ls` does.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::host::Endpoint;
+use super::tls::TlsOptions;
+
+/// Name of the built-in context that follows `DOCKER_HOST`.
+pub const DEFAULT_CONTEXT: &str = "default";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DockerContext {
+    pub name: String,
+    pub description: String,
+    pub endpoint: Endpoint,
+}
+
+/// Contexts known to the CLI and the name of the one it currently uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DockerContexts {
+    pub contexts: Vec<DockerContext>,
+    pub current: String,
+    /// Contexts that could not be read, each with the reason. The others
+    /// are still listed.
+    pub skipped: Vec<String>,
+}
+
+impl Default for DockerContexts {
+    /// Only the `default` context, used when the CLI config cannot be read.
+    fn default() -> Self {
+        Self::load_from(None).expect("the default context needs no I/O")
+    }
+}
+
+impl DockerContexts {
+    /// Reads contexts from the CLI config directory. The `default` context is
+    /// always present, even when the directory does not exist.
+    pub fn load() -> Result<Self> {
+        Self::load_from(docker_config_dir().as_deref())
+    }
+
+    pub fn find(&self, name: &str) -> Option<&DockerContext> {
+        self.contexts.iter().find(|context| context.name == name)
+    }
+
+    pub fn current_context(&self) -> Option<&DockerContext> {
+        self.find(&self.current)
+    }
+
+    fn load_from(dir: Option<&Path>) -> Result<Self> {
+        let mut contexts = vec![DockerContext {
+            name: DEFAULT_CONTEXT.to_string(),
+            description: "Current DOCKER_HOST based configuration".to_string(),
+            endpoint: Endpoint::from_env(),
+        }];
+
+        let mut skipped = Vec::new();
+        if let Some(dir) = dir {
+            contexts.extend(read_contexts(dir, &mut skipped)?);
+        }
+        contexts[1..].sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Self {
+            contexts,
+            current: current_context_name(dir),
+            skipped,
+        })
+    }
+}
+
+/// Reads every context under `contexts/meta`. One that cannot be read is
+/// described in `skipped` and left out, so it does not hide the others.
+fn read_contexts(dir: &Path, skipped: &mut Vec<String>) -> Result<Vec<DockerContext>> {
+    let mut contexts = Vec::new();
+    let meta_dir = dir.join("contexts").join("meta");
+    if meta_dir.is_dir() {
+        let entries = fs::read_dir(&meta_dir)
+            .with_context(|| format!("Failed to read {}", meta_dir.display()))?;
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    skipped.push(format!("Failed to read {}: {e}", meta_dir.display()));
+                    continue;
+                }
+            };
+            let meta_file = entry.path().join("meta.json");
+            if !meta_file.is_file() {
+                continue;
+            }
+            match read_context(dir, &entry.file_name().to_string_lossy(), &meta_file) {
+                Ok(Some(context)) => contexts.push(context),
+                Ok(None) => {}
+                Err(e) => skipped.push(format!(
+                    "Skipped Docker context in {}: {e:#}",
+                    meta_file.display()
+                )),
+            }
+        }
+    }
+    Ok(contexts)
+}
+
+/// `DOCKER_CONFIG` when set, otherwise `~/.docker`.
+pub fn docker_config_dir() -> Option<PathBuf> {
+    std::env::var_os("DOCKER_CONFIG")
+        .map(PathBuf::from)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".docker")))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContextMeta {
+    name: String,
+    #[serde(default)]
+    metadata: Value,
+    #[serde(default)]
+    endpoints: HashMap<String, EndpointMeta>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EndpointMeta {
+    host: Option<String>,
+    #[serde(default, rename = "SkipTLSVerify")]
+    skip_tls_verify: bool,
+}
+
+/// Parses one `contexts/meta/<id>/meta.json`. Contexts without a Docker
+/// endpoint (e.g. Kubernetes-only ones) are skipped.
+fn read_context(dir: &Path, id: &str, meta_file: &Path) -> Result<Option<DockerContext>> {
+    let meta: ContextMeta = serde_json::from_str(&fs::read_to_string(meta_file)?)?;
+    let Some(endpoint) = meta.endpoints.get("docker") else {
+        return Ok(None);
+    };
+    let Some(host) = endpoint.host.clone() else {
+        return Ok(None);
+    };
+    if endpoint.skip_tls_verify {
+        bail!(
+            "\"{}\" sets SkipTLSVerify, which is not supported; engine certificates are always verified",
+            meta.name
+        );
+    }
+
+    // TLS material lives next to the metadata, under the same directory id.
+    // Contexts created with only `ca=` have no client certificate.
+    let tls_dir = dir.join("contexts").join("tls").join(id).join("docker");
+    let tls = tls_dir.is_dir().then(|| {
+        let mut tls = TlsOptions::from_cert_dir(&tls_dir);
+        if !tls.cert.exists() && !tls.key.exists() {
+            tls.cert = PathBuf::new();
+            tls.key = PathBuf::new();
+        }
+        tls
+    });
+
+    let description = meta
+        .metadata
+        .get("Description")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    Ok(Some(DockerContext {
+        name: meta.name,
+        description,
+        endpoint: Endpoint { host, tls },
+    }))
+}
+
+/// Mirrors the CLI's precedence: `DOCKER_HOST` forces the default context,
+/// then `DOCKER_CONTEXT`, then `currentContext` from `config.json`.
+fn current_context_name(dir: Option<&Path>) -> String {
+    if std::env::var_os("DOCKER_HOST").is_some_and(|host| !host.is_empty()) {
+        return DEFAULT_CONTEXT.to_string();
+    }
+    if let Ok(name) = std::env::var("DOCKER_CONTEXT")
+        && !name.is_empty()
+    {
+        return name;
+    }
+
+    dir.and_then(|dir| fs::read_to_string(dir.join("config.json")).ok())
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .and_then(|config| config.get("currentContext")?.as_str().map(str::to_string))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_CONTEXT.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: PathBuf, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn reads_contexts_with_tls_material() {
+        let dir = std::env::temp_dir().join(format!("doctainr-ctx-{}", uuid::Uuid::new_v4()));
+        write(
+            dir.join("contexts/meta/abc123/meta.json"),
+            r#"{"Name":"staging","Metadata":{"Description":"Staging VM"},
+                "Endpoints":{"docker":{"Host":"tcp://staging:2376","SkipTLSVerify":false}}}"#,
+        );
+        write(dir.join("contexts/tls/abc123/docker/ca.pem"), "ca");
+        write(
+            dir.join("contexts/meta/def456/meta.json"),
+            r#"{"Name":"lab","Metadata":null,
+                "Endpoints":{"docker":{"Host":"ssh://deploy@lab-01"}}}"#,
+        );
+        write(
+            dir.join("contexts/meta/k8s/meta.json"),
+            r#"{"Name":"k8s-only","Endpoints":{"kubernetes":{}}}"#,
+        );
+        write(dir.join("contexts/meta/broken/meta.json"), "{\"Name\":");
+
+        let contexts = DockerContexts::load_from(Some(&dir)).unwrap();
+        let names: Vec<_> = contexts.contexts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["default", "lab", "staging"]);
+        assert_eq!(contexts.skipped.len(), 1);
+        assert!(contexts.skipped[0].contains("broken"));
+
+        let staging = contexts.find("staging").unwrap();
+        assert_eq!(staging.description, "Staging VM");
+        assert_eq!(staging.endpoint.host, "tcp://staging:2376");
+        // Only a CA: the engine is verified without a client certificate
+        let tls = staging.endpoint.tls.as_ref().unwrap();
+        assert_eq!(tls.ca, dir.join("contexts/tls/abc123/docker/ca.pem"));
+        assert!(!tls.has_client_cert());
+        tls.check().unwrap();
+        assert_eq!(contexts.find("lab").unwrap().endpoint.tls, None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn skips_contexts_that_skip_tls_verification() {
+        let dir = std::env::temp_dir().join(format!("doctainr-ctx-{}", uuid::Uuid::new_v4()));
+        write(
+            dir.join("contexts/meta/abc123/meta.json"),
+            r#"{"Name":"insecure","Endpoints":{"docker":{"Host":"tcp://lab:2376","SkipTLSVerify":true}}}"#,
+        );
+
+        let contexts = DockerContexts::load_from(Some(&dir)).unwrap();
+        assert!(contexts.find("insecure").is_none());
+        assert_eq!(contexts.skipped.len(), 1);
+        assert!(
+            contexts.skipped[0].contains("\"insecure\" sets SkipTLSVerify"),
+            "{}",
+            contexts.skipped[0]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_directory_only_has_default() {
+        let dir = std::env::temp_dir().join(format!("doctainr-ctx-{}", uuid::Uuid::new_v4()));
+        let contexts = DockerContexts::load_from(Some(&dir)).unwrap();
+        assert_eq!(contexts.contexts.len(), 1);
+        assert_eq!(contexts.contexts[0].name, DEFAULT_CONTEXT);
+    }
+}
@@ -1,177 +1,524 @@
-use anyhow::Result;
-use bollard::Docker;
-use bollard::container::{ListContainersOptions, StartContainerOptions, StopContainerOptions};
-use bollard::image::ListImagesOptions;
-use bollard::volume::ListVolumesOptions;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use bollard::container::{
+    Config, CreateContainerOptions, InspectContainerOptions, KillContainerOptions,
+    ListContainersOptions, LogOutput, LogsOptions, MemoryStatsStats, RemoveContainerOptions,
+    RestartContainerOptions, StartContainerOptions, Stats, StatsOptions, StopContainerOptions,
+};
+use bollard::errors::Error as BollardError;
+use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions, StartExecResults};
+use bollard::image::{
+    CreateImageOptions, ListImagesOptions, PruneImagesOptions, RemoveImageOptions, TagImageOptions,
+};
+use bollard::models::{
+    ContainerSummary, CreateImageInfo, EndpointSettings, HostConfig, PortBinding,
+    RestartPolicy as EngineRestartPolicy, RestartPolicyNameEnum,
+};
+use bollard::network::ConnectNetworkOptions;
+use bollard::system::{EventsOptions, Version};
+use bollard::volume::{ListVolumesOptions, RemoveVolumeOptions};
+use bollard::{API_DEFAULT_VERSION, Docker};
+use futures_util::StreamExt;
+use tokio::sync::OnceCell;
+
+use super::actions::{ContainerAction, DEFAULT_STOP_TIMEOUT_SECS, KillSignal, RemoveOptions};
+use super::engine::ContainerEngine;
+use super::events::{EngineEvent, EventStream};
+use super::exec::{ExecOptions, ExecSession};
+use super::host::{DockerHost, Endpoint};
+use super::images::{ImageRemoveOptions, PruneReport, PruneScope};
+use super::inspect::{
+    ContainerDetails, EnvVar, HealthCheck, MountInfo, NetworkAttachment, ResourceLimits,
+};
+use super::logs::{LogChunk, LogChunks, LogOptions, LogStream};
+use super::pull::{PullOptions, PullProgress, PullStream, with_default_tag};
+use super::reference::ImageReference;
+use super::run::{PortMapping, Protocol, RestartPolicy, RunSpec};
+use super::ssh::SshTunnel;
+use super::stats::{ContainerStats, IoCounters, StatsStream, cpu_percent, with_deltas};
+
+/// Request timeout, in seconds, applied to every engine connection.
+const CONNECT_TIMEOUT_SECS: u64 = 120;
+
+/// Where a container is in Docker's lifecycle, as reported in its `State`.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ContainerState {
+    Created,
     Running,
-    Stopped,
+    Paused,
+    Restarting,
+    Removing,
+    /// Exit code, when the engine reported one.
+    Exited(Option<i64>),
+    Dead,
+    /// A state this app does not know, or none at all, as reported.
+    Unknown(String),
 }
 
 impl ContainerState {
-    pub fn label(&self) -> &'static str {
+    /// Parses the `State` of a container (`running`, `exited`, ...). The exit
+    /// code is only kept for exited containers. Anything else is kept as is
+    /// rather than guessed at.
+    pub fn parse(state: &str, exit_code: Option<i64>) -> Self {
+        match state.to_ascii_lowercase().as_str() {
+            "created" => ContainerState::Created,
+            "running" => ContainerState::Running,
+            "paused" => ContainerState::Paused,
+            "restarting" => ContainerState::Restarting,
+            "removing" => ContainerState::Removing,
+            "exited" => ContainerState::Exited(exit_code),
+            "dead" => ContainerState::Dead,
+            _ => ContainerState::Unknown(state.trim().to_string()),
+        }
+    }
+
+    /// The engine's name for the state, without the exit code.
+    pub fn name(&self) -> &str {
+        match self {
+            ContainerState::Created => "created",
+            ContainerState::Running => "running",
+            ContainerState::Paused => "paused",
+            ContainerState::Restarting => "restarting",
+            ContainerState::Removing => "removing",
+            ContainerState::Exited(_) => "exited",
+            ContainerState::Dead => "dead",
+            ContainerState::Unknown(state) if state.is_empty() => "unknown",
+            ContainerState::Unknown(state) => state,
+        }
+    }
+
+    pub fn label(&self) -> String {
         match self {
-            ContainerState::Running => "Running",
-            ContainerState::Stopped => "Stopped",
+            ContainerState::Created => "Created".to_string(),
+            ContainerState::Running => "Running".to_string(),
+            ContainerState::Paused => "Paused".to_string(),
+            ContainerState::Restarting => "Restarting".to_string(),
+            ContainerState::Removing => "Removing".to_string(),
+            ContainerState::Exited(Some(code)) => format!("Exited ({code})"),
+            ContainerState::Exited(None) => "Exited".to_string(),
+            ContainerState::Dead => "Dead".to_string(),
+            ContainerState::Unknown(state) if state.is_empty() => "Unknown".to_string(),
+            ContainerState::Unknown(state) => format!("Unknown ({state})"),
         }
     }
 
     pub fn css_class(&self) -> &'static str {
         match self {
             ContainerState::Running => "running",
-            ContainerState::Stopped => "stopped",
+            ContainerState::Created => "created",
+            ContainerState::Paused => "paused",
+            ContainerState::Restarting | ContainerState::Removing => "pending",
+            ContainerState::Exited(Some(0) | None) => "stopped",
+            ContainerState::Exited(Some(_)) | ContainerState::Dead => "failed",
+            ContainerState::Unknown(_) => "unknown",
+        }
+    }
+
+    /// The action the row button offers. Dead containers, containers being
+    /// removed and containers in an unknown state cannot be acted on.
+    pub fn primary_action(&self) -> Option<ContainerAction> {
+        match self {
+            ContainerState::Created | ContainerState::Exited(_) => Some(ContainerAction::Start),
+            ContainerState::Running | ContainerState::Restarting => Some(ContainerAction::Stop),
+            ContainerState::Paused => Some(ContainerAction::Unpause),
+            ContainerState::Removing | ContainerState::Dead | ContainerState::Unknown(_) => None,
+        }
+    }
+
+    /// Every action the engine accepts in this state, primary action first.
+    /// Removal is forced for containers that are still up.
+    pub fn available_actions(&self) -> Vec<ContainerAction> {
+        let restart = ContainerAction::Restart {
+            timeout_secs: DEFAULT_STOP_TIMEOUT_SECS,
+        };
+        let kill = ContainerAction::Kill(KillSignal::Kill);
+        let remove = |force| {
+            ContainerAction::Remove(RemoveOptions {
+                force,
+                volumes: false,
+            })
+        };
+        match self {
+            ContainerState::Created => vec![ContainerAction::Start, remove(false)],
+            ContainerState::Running => vec![
+                ContainerAction::Stop,
+                restart,
+                ContainerAction::Pause,
+                kill,
+                remove(true),
+            ],
+            ContainerState::Paused => vec![ContainerAction::Unpause, remove(true)],
+            ContainerState::Restarting => vec![ContainerAction::Stop, kill, remove(true)],
+            ContainerState::Exited(_) => vec![ContainerAction::Start, restart, remove(false)],
+            ContainerState::Dead => vec![remove(false)],
+            ContainerState::Removing | ContainerState::Unknown(_) => Vec::new(),
+        }
+    }
+}
+
+/// Result of a container's health check, when it defines one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthStatus {
+    Starting,
+    Healthy,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    /// Parses a health status as reported by inspect (`none` means no check).
+    pub fn parse(health: &str) -> Option<Self> {
+        match health.to_ascii_lowercase().as_str() {
+            "starting" => Some(HealthStatus::Starting),
+            "healthy" => Some(HealthStatus::Healthy),
+            "unhealthy" => Some(HealthStatus::Unhealthy),
+            _ => None,
+        }
+    }
+
+    /// Reads the health suffix of a list `Status` such as
+    /// `Up 5 minutes (healthy)` or `Up 3 seconds (health: starting)`.
+    fn from_status(status: &str) -> Option<Self> {
+        let (_, suffix) = status.rsplit_once('(')?;
+        let health = suffix.trim_end_matches(')').trim();
+        HealthStatus::parse(health.strip_prefix("health:").unwrap_or(health).trim())
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthStatus::Starting => "Starting",
+            HealthStatus::Healthy => "Healthy",
+            HealthStatus::Unhealthy => "Unhealthy",
         }
     }
 
-    pub fn action_label(&self) -> &'static str {
+    pub fn css_class(&self) -> &'static str {
         match self {
-            ContainerState::Running => "Stop",
-            ContainerState::Stopped => "Start",
+            HealthStatus::Starting => "pending",
+            HealthStatus::Healthy => "running",
+            HealthStatus::Unhealthy => "failed",
         }
     }
 }
 
+/// Reads the code out of an `Exited (137) 2 hours ago` status.
+fn exit_code_from_status(status: &str) -> Option<i64> {
+    let rest = status.strip_prefix("Exited (")?;
+    rest.split_once(')')?.0.parse().ok()
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ContainerInfo {
+    /// Name of the engine the container was listed from, set by `AppState`.
+    pub engine: String,
     pub id: String,
     pub name: String,
     pub image: String,
+    /// Id of the image the container was created from, `sha256:…`.
+    pub image_id: String,
     pub status: String,
+    /// Summary such as `8080:80, 443`, for display.
     pub ports: String,
+    /// Ports bound on the host, with their address and protocol.
+    pub published: Vec<PortMapping>,
     pub state: ContainerState,
+    pub health: Option<HealthStatus>,
+    /// Unix timestamp, or 0 when unknown.
+    pub created: i64,
+    pub labels: BTreeMap<String, String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ImageInfo {
+    /// Name of the engine the image was listed from, set by `AppState`.
+    pub engine: String,
     pub id: String,
-    pub repository: String,
-    pub tag: String,
+    /// Every tag of the image, none when it is dangling.
+    pub tags: Vec<ImageReference>,
+    /// `repository@sha256:…` for each registry the image was pulled from.
+    pub digests: Vec<String>,
     pub size: String,
+    pub size_bytes: i64,
+    /// Unix timestamp.
+    pub created: i64,
+    pub labels: BTreeMap<String, String>,
+}
+
+impl ImageInfo {
+    pub fn is_dangling(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// The first tag, or the short id of a dangling image.
+    pub fn name(&self) -> String {
+        match self.tags.first() {
+            Some(tag) => tag.to_string(),
+            None => self.short_id().to_string(),
+        }
+    }
+
+    /// The first 12 hex digits of the id, as `docker images` shows it.
+    pub fn short_id(&self) -> &str {
+        let hex = self.id.trim_start_matches("sha256:");
+        hex.get(..12).unwrap_or(hex)
+    }
+
+    /// Whether `container`, on the same engine, was created from this image.
+    /// Falls back to the image reference when the container's image id is
+    /// not known.
+    pub fn is_used_by(&self, container: &ContainerInfo) -> bool {
+        if container.engine != self.engine {
+            return false;
+        }
+        if !container.image_id.is_empty() {
+            return container.image_id == self.id;
+        }
+        let reference = with_default_tag(&container.image);
+        self.tags.iter().any(|tag| tag.to_string() == reference)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct VolumeInfo {
+    /// Name of the engine the volume was listed from, set by `AppState`.
+    pub engine: String,
     pub name: String,
     pub driver: String,
     pub mountpoint: String,
     pub size: String,
+    /// RFC 3339 timestamp, when the driver reports one.
+    pub created: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Which runtime answers on the other end of the Docker API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngineKind {
+    Docker,
+    /// Podman's Docker-compatible API, which lacks a few endpoints.
+    Podman,
+}
+
+impl EngineKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EngineKind::Docker => "Docker",
+            EngineKind::Podman => "Podman",
+        }
+    }
+
+    /// Podman names itself in the version components (`Podman Engine`) and
+    /// in the platform name; Docker reports `Engine` and `Docker Engine - …`.
+    fn detect(version: &Version) -> Self {
+        let is_podman = |name: &str| name.to_ascii_lowercase().contains("podman");
+        let in_components = version
+            .components
+            .iter()
+            .flatten()
+            .any(|component| is_podman(&component.name));
+        let in_platform = version
+            .platform
+            .as_ref()
+            .is_some_and(|platform| is_podman(&platform.name));
+        if in_components || in_platform {
+            EngineKind::Podman
+        } else {
+            EngineKind::Docker
+        }
+    }
+}
+
+/// Result of a successful round trip to an engine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EngineReport {
+    pub kind: EngineKind,
+    pub api_version: String,
+    pub engine_version: String,
+    pub os: String,
+    pub arch: String,
+    pub latency: Duration,
 }
 
 #[derive(Clone)]
 pub struct DockerService {
     docker: Docker,
+    /// Keeps the SSH forward of `ssh://` hosts open for as long as any clone
+    /// of the service is alive.
+    _tunnel: Option<Arc<SshTunnel>>,
+    /// Detected on first use and shared by all clones.
+    kind: Arc<OnceCell<EngineKind>>,
 }
 
 impl DockerService {
-    pub fn new() -> Result<Self> {
-        let docker = Docker::connect_with_local_defaults()?;
-        Ok(Self { docker })
+    /// Builds a client for the engine at `endpoint` (e.g. `unix:///var/run/docker.sock`,
+    /// `tcp://build.internal:2376` with TLS or `ssh://deploy@lab-01`). Certificate
+    /// files are read and SSH tunnels opened here; no engine request is made until
+    /// the first call.
+    pub async fn connect(endpoint: &Endpoint) -> Result<Self> {
+        let host = DockerHost::parse(&endpoint.host)?;
+        let mut tunnel = None;
+        let docker = match (&host, &endpoint.tls) {
+            (DockerHost::Unix(path) | DockerHost::NamedPipe(path), _) => {
+                Docker::connect_with_socket(path, CONNECT_TIMEOUT_SECS, API_DEFAULT_VERSION)?
+            }
+            (DockerHost::Tcp(addr), Some(tls)) => {
+                tls.check()?;
+                Docker::connect_with_ssl(
+                    addr,
+                    &tls.key,
+                    &tls.cert,
+                    &tls.ca,
+                    CONNECT_TIMEOUT_SECS,
+                    API_DEFAULT_VERSION,
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to load TLS certificates: {e}"))?
+            }
+            (DockerHost::Tcp(addr), None) => {
+                Docker::connect_with_http(addr, CONNECT_TIMEOUT_SECS, API_DEFAULT_VERSION)?
+            }
+            (DockerHost::Ssh(target), _) => {
+                let opened = SshTunnel::open(target).await?;
+                let socket = opened
+                    .socket_path()
+                    .to_str()
+                    .context("SSH tunnel socket path is not valid UTF-8")?
+                    .to_string();
+                tunnel = Some(Arc::new(opened));
+                Docker::connect_with_socket(&socket, CONNECT_TIMEOUT_SECS, API_DEFAULT_VERSION)?
+            }
+        };
+        Ok(Self {
+            docker,
+            _tunnel: tunnel,
+            kind: Arc::default(),
+        })
+    }
+
+    /// The runtime behind the API, asked for once per connection.
+    async fn engine_kind(&self) -> Result<EngineKind> {
+        let kind = self
+            .kind
+            .get_or_try_init(|| async {
+                let version = self.docker.version().await?;
+                Ok::<_, BollardError>(EngineKind::detect(&version))
+            })
+            .await?;
+        Ok(*kind)
     }
 
-    pub async fn list_containers(&self) -> Result<Vec<ContainerInfo>> {
-        let options = Some(ListContainersOptions::<String> {
+    async fn list_containers_matching(
+        &self,
+        filters: HashMap<String, Vec<String>>,
+    ) -> Result<Vec<ContainerInfo>> {
+        let options = Some(ListContainersOptions {
             all: true,
+            filters,
             ..Default::default()
         });
+        let containers = self
+            .compat(
+                "Listing containers",
+                self.docker.list_containers(options).await,
+            )
+            .await?;
+        Ok(containers.into_iter().map(container_info).collect())
+    }
 
-        let containers = self.docker.list_containers(options).await?;
+    /// Turns Podman's "endpoint not implemented" replies into a readable
+    /// message naming the missing `feature`. Other errors, including 404s for
+    /// objects that do not exist, pass through unchanged.
+    async fn compat<T>(&self, feature: &str, result: Result<T, BollardError>) -> Result<T> {
+        let unsupported = matches!(
+            &result,
+            Err(BollardError::DockerResponseServerError { status_code, message })
+                if is_unsupported_endpoint(*status_code, message)
+        );
+        if unsupported && matches!(self.engine_kind().await, Ok(EngineKind::Podman)) {
+            bail!("{feature} is not supported by Podman's Docker-compatible API");
+        }
+        Ok(result?)
+    }
+}
 
-        let container_infos = containers
-            .into_iter()
-            .map(|container| {
-                let id = container
-                    .id
-                    .as_ref()
-                    .map(|s| s.chars().take(12).collect())
-                    .unwrap_or_else(|| "unknown".to_string());
-
-                let name = container
-                    .names
-                    .as_ref()
-                    .and_then(|names| names.first())
-                    .map(|n| n.trim_start_matches('/').to_string())
-                    .unwrap_or_else(|| "unnamed".to_string());
-
-                let image = container.image.unwrap_or_else(|| "unknown".to_string());
-
-                let status = container.status.unwrap_or_else(|| "unknown".to_string());
-
-                let ports = if let Some(ports) = container.ports {
-                    if ports.is_empty() {
-                        "--".to_string()
-                    } else {
-                        ports
-                            .iter()
-                            .filter_map(|p| match (p.public_port, p.private_port) {
-                                (Some(pub_port), priv_port) => {
-                                    Some(format!("{}:{}", pub_port, priv_port))
-                                }
-                                (None, priv_port) => Some(format!("{}", priv_port)),
-                            })
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    }
-                } else {
-                    "--".to_string()
-                };
+#[async_trait]
+impl ContainerEngine for DockerService {
+    /// Pings the engine and collects its version details. The latency is the
+    /// round trip of the ping alone.
+    async fn probe(&self) -> Result<EngineReport> {
+        let started = Instant::now();
+        self.docker.ping().await?;
+        let latency = started.elapsed();
 
-                let state = if let Some(st) = container.state {
-                    if st == "running" {
-                        ContainerState::Running
-                    } else {
-                        ContainerState::Stopped
-                    }
-                } else {
-                    ContainerState::Stopped
-                };
+        let version = self.docker.version().await?;
+        let kind = EngineKind::detect(&version);
+        let _ = self.kind.set(kind);
+        let info = self.docker.info().await?;
 
-                ContainerInfo {
-                    id,
-                    name,
-                    image,
-                    status,
-                    ports,
-                    state,
-                }
-            })
-            .collect();
+        Ok(EngineReport {
+            kind,
+            api_version: version.api_version.unwrap_or_else(|| "unknown".to_string()),
+            engine_version: version.version.unwrap_or_else(|| "unknown".to_string()),
+            os: info
+                .operating_system
+                .or(version.os)
+                .unwrap_or_else(|| "unknown".to_string()),
+            arch: version.arch.unwrap_or_else(|| "unknown".to_string()),
+            latency,
+        })
+    }
 
-        Ok(container_infos)
+    async fn list_containers(&self) -> Result<Vec<ContainerInfo>> {
+        self.list_containers_matching(HashMap::new()).await
     }
 
-    pub async fn list_images(&self) -> Result<Vec<ImageInfo>> {
+    async fn find_container(&self, id: &str) -> Result<Option<ContainerInfo>> {
+        let filters = HashMap::from([("id".to_string(), vec![id.to_string()])]);
+        let containers = self.list_containers_matching(filters).await?;
+        Ok(containers.into_iter().next())
+    }
+
+    async fn list_images(&self) -> Result<Vec<ImageInfo>> {
         let options = Some(ListImagesOptions::<String> {
             all: false,
             ..Default::default()
         });
 
-        let images = self.docker.list_images(options).await?;
+        let images = self
+            .compat("Listing images", self.docker.list_images(options).await)
+            .await?;
 
         let image_infos = images
             .into_iter()
             .map(|image| {
                 let id = image.id;
 
-                // Parse repository and tag from repo_tags (Vec<String>)
-                let (repository, tag) = if let Some(first) = image.repo_tags.first() {
-                    let parts: Vec<&str> = first.split(':').collect();
-                    let repo = parts.first().unwrap_or(&"<none>").to_string();
-                    let tag_part = parts.get(1).unwrap_or(&"<none>").to_string();
-                    (repo, tag_part)
-                } else {
-                    ("<none>".to_string(), "<none>".to_string())
-                };
+                // Dangling images are listed with a `<none>:<none>` tag
+                let tags = image
+                    .repo_tags
+                    .iter()
+                    .filter_map(|tag| ImageReference::parse(tag).ok())
+                    .collect();
+                let digests = image
+                    .repo_digests
+                    .into_iter()
+                    .filter(|digest| !digest.starts_with("<none>"))
+                    .collect();
 
                 // Format size directly (it's i64, not Option<i64>)
                 let size = format_size(image.size);
 
                 ImageInfo {
+                    engine: String::new(),
                     id,
-                    repository,
-                    tag,
+                    tags,
+                    digests,
                     size,
+                    size_bytes: image.size,
+                    created: image.created,
+                    labels: image.labels.into_iter().collect(),
                 }
             })
             .collect();
@@ -179,12 +526,17 @@ impl DockerService {
         Ok(image_infos)
     }
 
-    pub async fn list_volumes(&self) -> Result<Vec<VolumeInfo>> {
+    async fn list_volumes(&self) -> Result<Vec<VolumeInfo>> {
         let options = ListVolumesOptions::<String> {
             ..Default::default()
         };
 
-        let volumes_response = self.docker.list_volumes(Some(options)).await?;
+        let volumes_response = self
+            .compat(
+                "Listing volumes",
+                self.docker.list_volumes(Some(options)).await,
+            )
+            .await?;
 
         let volume_infos = volumes_response
             .volumes
@@ -198,10 +550,13 @@ impl DockerService {
                 let size = "--".to_string();
 
                 VolumeInfo {
+                    engine: String::new(),
                     name,
                     driver,
                     mountpoint,
                     size,
+                    created: volume.created_at,
+                    labels: volume.labels.into_iter().collect(),
                 }
             })
             .collect();
@@ -209,19 +564,707 @@ impl DockerService {
         Ok(volume_infos)
     }
 
-    pub async fn start_container(&self, id: &str) -> Result<()> {
-        self.docker
+    async fn inspect_container(&self, id: &str) -> Result<ContainerDetails> {
+        let result = self
+            .docker
+            .inspect_container(id, None::<InspectContainerOptions>)
+            .await;
+        let container = self.compat("Inspecting containers", result).await?;
+        let raw = serde_json::to_value(&container).unwrap_or_default();
+
+        let inspected = container.state.unwrap_or_default();
+        let status = inspected
+            .status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let state = ContainerState::parse(&status, inspected.exit_code);
+        let health = inspected.health.unwrap_or_default();
+        let health_log = health
+            .log
+            .unwrap_or_default()
+            .into_iter()
+            .map(|check| HealthCheck {
+                start: check.start.unwrap_or_default(),
+                exit_code: check.exit_code.unwrap_or_default(),
+                output: check.output.unwrap_or_default(),
+            })
+            .collect();
+        let health = health
+            .status
+            .and_then(|status| HealthStatus::parse(status.as_ref()));
+
+        let network_settings = container.network_settings.unwrap_or_default();
+        // Bindings are keyed by "<port>/<proto>"; unpublished ports map to None
+        let port_map = network_settings.ports.unwrap_or_default();
+        let published = port_map
+            .iter()
+            .flat_map(|(port, bindings)| {
+                let (private, protocol) = port.split_once('/').unwrap_or((port, "tcp"));
+                let protocol = Protocol::parse(protocol);
+                let private = private.parse::<u16>().ok();
+                bindings.iter().flatten().filter_map(move |binding| {
+                    Some(PortMapping {
+                        host_ip: binding.host_ip.clone().filter(|ip| !ip.is_empty()),
+                        host_port: binding.host_port.as_deref()?.parse().ok()?,
+                        container_port: private?,
+                        protocol: protocol?,
+                    })
+                })
+            })
+            .collect();
+        let mut ports: Vec<String> = port_map
+            .into_iter()
+            .flat_map(|(port, bindings)| {
+                let private = port.split('/').next().unwrap_or_default().to_string();
+                match bindings.filter(|b| !b.is_empty()) {
+                    Some(bindings) => bindings
+                        .into_iter()
+                        .map(|b| match b.host_port {
+                            Some(public) => format!("{public}:{private}"),
+                            None => private.clone(),
+                        })
+                        .collect(),
+                    None => vec![private],
+                }
+            })
+            .collect();
+        ports.sort();
+        ports.dedup();
+
+        let mut networks: Vec<NetworkAttachment> = network_settings
+            .networks
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(network, endpoint)| NetworkAttachment {
+                network,
+                ip_address: endpoint.ip_address.filter(|ip| !ip.is_empty()),
+                ipv6_address: endpoint.global_ipv6_address.filter(|ip| !ip.is_empty()),
+                gateway: endpoint.gateway.filter(|ip| !ip.is_empty()),
+                mac_address: endpoint.mac_address.filter(|mac| !mac.is_empty()),
+                aliases: endpoint.aliases.unwrap_or_default(),
+            })
+            .collect();
+        networks.sort_by(|a, b| a.network.cmp(&b.network));
+
+        let mounts = container
+            .mounts
+            .unwrap_or_default()
+            .into_iter()
+            .map(|mount| MountInfo {
+                kind: mount.typ.map(|kind| kind.to_string()).unwrap_or_default(),
+                source: mount.name.or(mount.source).unwrap_or_default(),
+                destination: mount.destination.unwrap_or_default(),
+                read_only: mount.rw == Some(false),
+            })
+            .collect();
+
+        let config = container.config.unwrap_or_default();
+        let labels: BTreeMap<String, String> =
+            config.labels.unwrap_or_default().into_iter().collect();
+
+        let host_config = container.host_config.unwrap_or_default();
+        let restart = host_config.restart_policy.unwrap_or_default();
+        // Zero means "not set" for every limit
+        let limit = |value: Option<i64>| value.filter(|value| *value > 0);
+        let limits = ResourceLimits {
+            cpus: limit(host_config.nano_cpus).map(|nanos| nanos as f64 / 1e9),
+            cpu_shares: limit(host_config.cpu_shares),
+            cpuset: host_config.cpuset_cpus.filter(|cpus| !cpus.is_empty()),
+            memory: limit(host_config.memory),
+            memory_swap: host_config.memory_swap.filter(|swap| *swap != 0),
+            pids: limit(host_config.pids_limit),
+        };
+
+        let info = ContainerInfo {
+            engine: String::new(),
+            id: container
+                .id
+                .map(|id| id.chars().take(12).collect())
+                .unwrap_or_else(|| "unknown".to_string()),
+            name: container
+                .name
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_else(|| "unnamed".to_string()),
+            image: config.image.unwrap_or_else(|| "unknown".to_string()),
+            image_id: container.image.unwrap_or_default(),
+            status,
+            ports: if ports.is_empty() {
+                "--".to_string()
+            } else {
+                ports.join(", ")
+            },
+            published,
+            state,
+            health,
+            created: container
+                .created
+                .as_deref()
+                .and_then(parse_timestamp)
+                .unwrap_or_default(),
+            labels: labels.clone(),
+        };
+
+        Ok(ContainerDetails {
+            info,
+            created: container.created,
+            entrypoint: config.entrypoint.unwrap_or_default(),
+            command: config.cmd.unwrap_or_default(),
+            working_dir: config.working_dir.filter(|dir| !dir.is_empty()),
+            user: config.user.filter(|user| !user.is_empty()),
+            env: config
+                .env
+                .unwrap_or_default()
+                .iter()
+                .map(|entry| EnvVar::parse(entry))
+                .collect(),
+            mounts,
+            networks,
+            labels: labels.into_iter().collect(),
+            restart_policy: restart
+                .name
+                .map(|name| name.to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "no".to_string()),
+            restart_max_retries: restart.maximum_retry_count.unwrap_or_default(),
+            restart_count: container.restart_count.unwrap_or_default(),
+            limits,
+            health_log,
+            raw,
+        })
+    }
+
+    async fn start_container(&self, id: &str) -> Result<()> {
+        let result = self
+            .docker
             .start_container(id, None::<StartContainerOptions<String>>)
-            .await?;
-        Ok(())
+            .await;
+        self.compat("Starting containers", result).await
     }
 
-    pub async fn stop_container(&self, id: &str) -> Result<()> {
-        self.docker
+    async fn stop_container(&self, id: &str) -> Result<()> {
+        let result = self
+            .docker
             .stop_container(id, None::<StopContainerOptions>)
-            .await?;
+            .await;
+        self.compat("Stopping containers", result).await
+    }
+
+    async fn restart_container(&self, id: &str, timeout_secs: u32) -> Result<()> {
+        let options = RestartContainerOptions {
+            t: timeout_secs as isize,
+        };
+        let result = self.docker.restart_container(id, Some(options)).await;
+        self.compat("Restarting containers", result).await
+    }
+
+    async fn pause_container(&self, id: &str) -> Result<()> {
+        let result = self.docker.pause_container(id).await;
+        self.compat("Pausing containers", result).await
+    }
+
+    async fn unpause_container(&self, id: &str) -> Result<()> {
+        let result = self.docker.unpause_container(id).await;
+        self.compat("Unpausing containers", result).await
+    }
+
+    async fn kill_container(&self, id: &str, signal: KillSignal) -> Result<()> {
+        let options = KillContainerOptions {
+            signal: signal.name(),
+        };
+        let result = self.docker.kill_container(id, Some(options)).await;
+        self.compat("Killing containers", result).await
+    }
+
+    async fn remove_container(&self, id: &str, options: RemoveOptions) -> Result<()> {
+        let options = RemoveContainerOptions {
+            force: options.force,
+            v: options.volumes,
+            ..Default::default()
+        };
+        let result = self.docker.remove_container(id, Some(options)).await;
+        self.compat("Removing containers", result).await
+    }
+
+    async fn remove_image(&self, id: &str, options: ImageRemoveOptions) -> Result<()> {
+        let options = RemoveImageOptions {
+            force: options.force,
+            noprune: options.no_prune,
+        };
+        let result = self.docker.remove_image(id, Some(options), None).await;
+        self.compat("Removing images", result).await?;
         Ok(())
     }
+
+    async fn tag_image(&self, id: &str, repository: &str, tag: &str) -> Result<()> {
+        let options = TagImageOptions {
+            repo: repository,
+            tag,
+        };
+        let result = self.docker.tag_image(id, Some(options)).await;
+        self.compat("Tagging images", result).await
+    }
+
+    async fn prune_images(&self, scope: PruneScope) -> Result<PruneReport> {
+        let dangling = match scope {
+            PruneScope::Dangling => "true",
+            PruneScope::Unused => "false",
+        };
+        let options = PruneImagesOptions {
+            filters: HashMap::from([("dangling", vec![dangling])]),
+        };
+        let result = self.docker.prune_images(Some(options)).await;
+        let response = self.compat("Pruning images", result).await?;
+        // Entries either name a removed tag or a deleted image or layer
+        let deleted = response
+            .images_deleted
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|item| item.deleted)
+            .collect();
+        Ok(PruneReport {
+            deleted,
+            reclaimed_bytes: response.space_reclaimed.unwrap_or_default().max(0) as u64,
+        })
+    }
+
+    async fn remove_volume(&self, name: &str) -> Result<()> {
+        let result = self
+            .docker
+            .remove_volume(name, None::<RemoveVolumeOptions>)
+            .await;
+        self.compat("Removing volumes", result).await
+    }
+
+    async fn run_container(&self, spec: RunSpec) -> Result<String> {
+        let mut exposed_ports = HashMap::new();
+        let mut bindings: HashMap<String, Vec<PortBinding>> = HashMap::new();
+        for port in &spec.ports {
+            let key = format!("{}/{}", port.container_port, port.protocol.name());
+            exposed_ports.insert(key.clone(), HashMap::new());
+            bindings.entry(key).or_default().push(PortBinding {
+                host_ip: port.host_ip.clone(),
+                host_port: Some(port.host_port.to_string()),
+            });
+        }
+        let (restart_name, max_retries) = match spec.restart {
+            RestartPolicy::No => (RestartPolicyNameEnum::NO, None),
+            RestartPolicy::Always => (RestartPolicyNameEnum::ALWAYS, None),
+            RestartPolicy::UnlessStopped => (RestartPolicyNameEnum::UNLESS_STOPPED, None),
+            RestartPolicy::OnFailure { max_retries } => {
+                (RestartPolicyNameEnum::ON_FAILURE, Some(max_retries as i64))
+            }
+        };
+        let mut networks = spec.networks.into_iter();
+
+        let host_config = HostConfig {
+            port_bindings: Some(
+                bindings
+                    .into_iter()
+                    .map(|(port, bindings)| (port, Some(bindings)))
+                    .collect(),
+            ),
+            binds: Some(spec.mounts.iter().map(|mount| mount.bind()).collect()),
+            restart_policy: Some(EngineRestartPolicy {
+                name: Some(restart_name),
+                maximum_retry_count: max_retries,
+            }),
+            nano_cpus: spec.cpus.map(|cpus| (cpus * 1e9) as i64),
+            memory: spec.memory,
+            network_mode: networks.next(),
+            ..Default::default()
+        };
+        let config = Config {
+            image: Some(spec.image),
+            cmd: (!spec.command.is_empty()).then_some(spec.command),
+            env: Some(spec.env),
+            labels: Some(spec.labels.into_iter().collect()),
+            exposed_ports: Some(exposed_ports),
+            host_config: Some(host_config),
+            ..Default::default()
+        };
+        let options = spec.name.map(|name| CreateContainerOptions {
+            name,
+            platform: None,
+        });
+        let result = self.docker.create_container(options, config).await;
+        let id = self.compat("Creating containers", result).await?.id;
+
+        for network in networks {
+            let options = ConnectNetworkOptions {
+                container: id.clone(),
+                endpoint_config: EndpointSettings::default(),
+            };
+            if let Err(err) = self.docker.connect_network(&network, options).await {
+                // Don't leave a half-configured container behind
+                let remove = RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                };
+                let _ = self.docker.remove_container(&id, Some(remove)).await;
+                return Err(anyhow::Error::new(err)
+                    .context(format!("Failed to connect the container to {network}")));
+            }
+        }
+        self.start_container(&id).await?;
+        Ok(id.chars().take(12).collect())
+    }
+
+    async fn exec(&self, id: &str, options: ExecOptions) -> Result<ExecSession> {
+        let create = CreateExecOptions {
+            attach_stdin: Some(true),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            tty: Some(true),
+            cmd: Some(options.cmd),
+            user: options.user,
+            working_dir: options.working_dir,
+            env: Some(options.env),
+            ..Default::default()
+        };
+        let result = self.docker.create_exec(id, create).await;
+        let exec_id = self.compat("Exec", result).await?.id;
+
+        let start = StartExecOptions {
+            detach: false,
+            tty: true,
+            output_capacity: None,
+        };
+        let result = self.docker.start_exec(&exec_id, Some(start)).await;
+        let StartExecResults::Attached { output, input } = self.compat("Exec", result).await?
+        else {
+            bail!("The engine started the exec session detached");
+        };
+
+        // The size can only be set once the process is running
+        self.resize_exec(&exec_id, options.cols, options.rows)
+            .await
+            .ok();
+
+        Ok(ExecSession {
+            exec_id,
+            output: output
+                .map(|chunk| -> Result<Vec<u8>> { Ok(chunk?.into_bytes().to_vec()) })
+                .boxed(),
+            input,
+        })
+    }
+
+    async fn resize_exec(&self, exec_id: &str, cols: u16, rows: u16) -> Result<()> {
+        let options = ResizeExecOptions {
+            width: cols,
+            height: rows,
+        };
+        let result = self.docker.resize_exec(exec_id, options).await;
+        self.compat("Resizing exec sessions", result).await
+    }
+
+    fn logs(&self, id: &str, options: LogOptions) -> LogChunks {
+        let options = LogsOptions::<String> {
+            follow: options.follow,
+            stdout: true,
+            stderr: true,
+            since: options.since.unwrap_or_default(),
+            until: options.until.unwrap_or_default(),
+            timestamps: options.timestamps,
+            tail: options
+                .tail
+                .map(|tail| tail.to_string())
+                .unwrap_or_else(|| "all".to_string()),
+        };
+        self.docker
+            .logs(id, Some(options))
+            .map(|output| -> Result<LogChunk> {
+                let (stream, message) = match output? {
+                    LogOutput::StdErr { message } => (LogStream::Stderr, message),
+                    // TTY containers have a single combined console stream
+                    LogOutput::StdOut { message }
+                    | LogOutput::Console { message }
+                    | LogOutput::StdIn { message } => (LogStream::Stdout, message),
+                };
+                Ok(LogChunk {
+                    stream,
+                    text: String::from_utf8_lossy(&message).into_owned(),
+                })
+            })
+            .boxed()
+    }
+
+    fn pull_image(&self, options: PullOptions) -> PullStream {
+        self.docker
+            .create_image(Some(pull_request(options)), None, None)
+            .map(|info| match info {
+                Ok(info) => pull_progress(info),
+                // bollard lifts errors out of the stream, and its own message
+                // for them does not say what went wrong
+                Err(BollardError::DockerStreamError { error }) => Err(anyhow::anyhow!(error)),
+                Err(e) => Err(e.into()),
+            })
+            .boxed()
+    }
+
+    fn stats(&self, id: &str) -> StatsStream {
+        let options = StatsOptions {
+            stream: true,
+            one_shot: false,
+        };
+        let samples = self
+            .docker
+            .stats(id, Some(options))
+            .map(|stats| -> Result<ContainerStats> { Ok(container_stats(stats?)) });
+        with_deltas(samples.boxed())
+    }
+
+    fn events(&self) -> EventStream {
+        let filters = HashMap::from([(
+            "type".to_string(),
+            vec![
+                "container".to_string(),
+                "image".to_string(),
+                "volume".to_string(),
+            ],
+        )]);
+        let options = EventsOptions {
+            filters,
+            ..Default::default()
+        };
+        self.docker
+            .events(Some(options))
+            .filter_map(|message| async move {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(e) => return Some(Err(e.into())),
+                };
+                let kind = message.typ.map(|kind| kind.to_string()).unwrap_or_default();
+                let action = message.action.unwrap_or_default();
+                let id = message.actor.and_then(|actor| actor.id).unwrap_or_default();
+                EngineEvent::from_message(&kind, &action, &id).map(Ok)
+            })
+            .boxed()
+    }
+}
+
+/// A row of the container list, from the engine's summary of it.
+fn container_info(container: ContainerSummary) -> ContainerInfo {
+    let id = container
+        .id
+        .as_ref()
+        .map(|s| s.chars().take(12).collect())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let name = container
+        .names
+        .as_ref()
+        .and_then(|names| names.first())
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| "unnamed".to_string());
+
+    let image = container.image.unwrap_or_else(|| "unknown".to_string());
+    let image_id = container.image_id.unwrap_or_default();
+
+    let status = container.status.unwrap_or_else(|| "unknown".to_string());
+
+    let published = container
+        .ports
+        .iter()
+        .flatten()
+        .filter_map(|port| {
+            Some(PortMapping {
+                host_ip: port.ip.clone().filter(|ip| !ip.is_empty()),
+                host_port: port.public_port?,
+                container_port: port.private_port,
+                protocol: Protocol::parse(port.typ?.as_ref())?,
+            })
+        })
+        .collect();
+    let ports = if let Some(ports) = container.ports {
+        if ports.is_empty() {
+            "--".to_string()
+        } else {
+            ports
+                .iter()
+                .map(|p| match (p.public_port, p.private_port) {
+                    (Some(pub_port), priv_port) => format!("{}:{}", pub_port, priv_port),
+                    (None, priv_port) => format!("{}", priv_port),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    } else {
+        "--".to_string()
+    };
+
+    let state = ContainerState::parse(
+        container.state.as_deref().unwrap_or_default(),
+        exit_code_from_status(&status),
+    );
+    let health = HealthStatus::from_status(&status);
+
+    ContainerInfo {
+        engine: String::new(),
+        id,
+        name,
+        image,
+        image_id,
+        status,
+        ports,
+        published,
+        state,
+        health,
+        created: container.created.unwrap_or_default(),
+        labels: container.labels.unwrap_or_default().into_iter().collect(),
+    }
+}
+
+/// Reduces a raw stats sample to the figures `docker stats` shows. Memory
+/// excludes inactive page cache, as the CLI does.
+fn container_stats(stats: Stats) -> ContainerStats {
+    let cpu = &stats.cpu_stats;
+    let precpu = &stats.precpu_stats;
+    let cpu_delta = cpu
+        .cpu_usage
+        .total_usage
+        .saturating_sub(precpu.cpu_usage.total_usage);
+    let system_delta = cpu
+        .system_cpu_usage
+        .unwrap_or_default()
+        .saturating_sub(precpu.system_cpu_usage.unwrap_or_default());
+    let online_cpus = cpu
+        .online_cpus
+        .or_else(|| {
+            cpu.cpu_usage
+                .percpu_usage
+                .as_ref()
+                .map(|cpus| cpus.len() as u64)
+        })
+        .unwrap_or(1);
+
+    let cache = match &stats.memory_stats.stats {
+        Some(MemoryStatsStats::V1(v1)) => v1.total_inactive_file,
+        Some(MemoryStatsStats::V2(v2)) => v2.inactive_file,
+        None => 0,
+    };
+    let memory_usage = stats
+        .memory_stats
+        .usage
+        .unwrap_or_default()
+        .saturating_sub(cache);
+
+    let mut totals = IoCounters::default();
+    for network in stats.networks.iter().flat_map(|networks| networks.values()) {
+        totals.net_rx += network.rx_bytes;
+        totals.net_tx += network.tx_bytes;
+    }
+    // cgroup v1 reports "Read"/"Write", v2 "read"/"write"
+    let entries = stats
+        .blkio_stats
+        .io_service_bytes_recursive
+        .iter()
+        .flatten();
+    for entry in entries {
+        if entry.op.eq_ignore_ascii_case("read") {
+            totals.block_read += entry.value;
+        } else if entry.op.eq_ignore_ascii_case("write") {
+            totals.block_write += entry.value;
+        }
+    }
+
+    ContainerStats {
+        cpu_percent: cpu_percent(cpu_delta, system_delta, online_cpus),
+        memory_usage,
+        memory_limit: stats.memory_stats.limit.unwrap_or_default(),
+        totals,
+        delta: IoCounters::default(),
+    }
+}
+
+/// The repository and the tag or digest to pull, passed apart as the Docker
+/// CLI does. A reference naming neither gets `latest`, as the engine would
+/// otherwise pull every tag of the repository.
+fn pull_request(options: PullOptions) -> CreateImageOptions<'static, String> {
+    let reference = with_default_tag(&options.reference);
+    let (from_image, tag) = match ImageReference::parse(&reference) {
+        Ok(parsed) => {
+            let tag = parsed.digest.clone().or(parsed.tag.clone());
+            (parsed.repository(), tag.unwrap_or_default())
+        }
+        // Left for the engine to reject with its own message
+        Err(_) => (reference, String::new()),
+    };
+    CreateImageOptions {
+        from_image,
+        tag,
+        platform: options.platform.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// One progress message of a pull. A message carrying an error ends the
+/// pull with it, since the engine reports failures such as a missing tag
+/// inside the stream.
+fn pull_progress(info: CreateImageInfo) -> Result<PullProgress> {
+    if let Some(error) = info.error {
+        bail!("{error}");
+    }
+    let detail = info.progress_detail.unwrap_or_default();
+    Ok(PullProgress {
+        id: info.id,
+        status: info.status.unwrap_or_default(),
+        current: detail.current.map(|bytes| bytes.max(0) as u64),
+        total: detail.total.map(|bytes| bytes.max(0) as u64),
+    })
+}
+
+/// 405 and 501 are what Podman answers for Docker endpoints it does not
+/// implement. A 404 only counts when the route itself is missing (Go's
+/// `404 page not found`); any other 404 is about a missing object, worded
+/// differently by each engine (`no such image`, `image not known`, ...).
+fn is_unsupported_endpoint(status_code: u16, message: &str) -> bool {
+    match status_code {
+        405 | 501 => true,
+        404 => message.to_ascii_lowercase().contains("page not found"),
+        _ => false,
+    }
+}
+
+/// Unix seconds of an RFC 3339 timestamp such as inspect's `Created`
+/// (`2024-05-01T12:34:56.789012345Z`, or with a `+02:00` offset from Podman).
+/// Docker's zero time and anything else before 1970 give `None`.
+fn parse_timestamp(text: &str) -> Option<i64> {
+    let (date, time) = text.split_once(['T', 't', ' '])?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+
+    let (clock, offset) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let at = time.rfind(['+', '-'])?;
+        let (hours, minutes) = time[at + 1..].split_once(':')?;
+        let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+        let sign = if time[at..].starts_with('-') { -1 } else { 1 };
+        (&time[..at], sign * offset)
+    };
+    // Fractions of a second are dropped
+    let clock = clock.split('.').next()?;
+    let mut clock = clock.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    (seconds > 0).then_some(seconds)
 }
 
 fn format_size(size: i64) -> String {
@@ -242,12 +1285,386 @@ fn format_size(size: i64) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::super::pull::{LayerPhase, PullState};
     use super::*;
 
     #[test]
     fn container_state_labels_match() {
         assert_eq!(ContainerState::Running.label(), "Running");
-        assert_eq!(ContainerState::Stopped.label(), "Stopped");
+        assert_eq!(ContainerState::Exited(Some(137)).label(), "Exited (137)");
+        assert_eq!(ContainerState::Exited(None).label(), "Exited");
+    }
+
+    #[test]
+    fn parses_states_and_exit_codes() {
+        let status = "Exited (137) 2 hours ago";
+        assert_eq!(
+            ContainerState::parse("exited", exit_code_from_status(status)),
+            ContainerState::Exited(Some(137))
+        );
+        assert_eq!(
+            ContainerState::parse("paused", None),
+            ContainerState::Paused
+        );
+        assert_eq!(ContainerState::parse("dead", None), ContainerState::Dead);
+        assert_eq!(exit_code_from_status("Up 2 hours"), None);
+    }
+
+    #[test]
+    fn unknown_states_are_shown_as_reported() {
+        let stopping = ContainerState::parse("stopping", Some(0));
+        assert_eq!(stopping, ContainerState::Unknown("stopping".to_string()));
+        assert_eq!(stopping.label(), "Unknown (stopping)");
+        assert_eq!(stopping.name(), "stopping");
+        assert_eq!(stopping.primary_action(), None);
+        assert!(stopping.available_actions().is_empty());
+
+        let missing = ContainerState::parse("", None);
+        assert_eq!(missing.label(), "Unknown");
+        assert_eq!(missing.name(), "unknown");
+    }
+
+    #[test]
+    fn parses_health_from_status() {
+        assert_eq!(
+            HealthStatus::from_status("Up 5 minutes (healthy)"),
+            Some(HealthStatus::Healthy)
+        );
+        assert_eq!(
+            HealthStatus::from_status("Up 3 seconds (health: starting)"),
+            Some(HealthStatus::Starting)
+        );
+        assert_eq!(
+            HealthStatus::from_status("Up 1 hour (unhealthy)"),
+            Some(HealthStatus::Unhealthy)
+        );
+        assert_eq!(HealthStatus::from_status("Up 1 hour (Paused)"), None);
+        assert_eq!(HealthStatus::from_status("Exited (0) 1 hour ago"), None);
+    }
+
+    #[test]
+    fn actions_follow_the_state() {
+        assert_eq!(
+            ContainerState::Paused.primary_action(),
+            Some(ContainerAction::Unpause)
+        );
+        assert_eq!(
+            ContainerState::Exited(Some(1)).primary_action(),
+            Some(ContainerAction::Start)
+        );
+        assert_eq!(ContainerState::Removing.primary_action(), None);
+
+        let running = ContainerState::Running.available_actions();
+        assert_eq!(running[0], ContainerAction::Stop);
+        assert!(running.contains(&ContainerAction::Pause));
+        assert!(
+            !ContainerState::Paused
+                .available_actions()
+                .contains(&ContainerAction::Start)
+        );
+    }
+
+    #[test]
+    fn detects_podman_from_version_components() {
+        let podman: Version = serde_json::from_str(
+            r#"{"Platform":{"Name":"linux/amd64/fedora-40"},
+                "Components":[{"Name":"Podman Engine","Version":"5.2.1"}],
+                "Version":"5.2.1","ApiVersion":"1.41"}"#,
+        )
+        .unwrap();
+        assert_eq!(EngineKind::detect(&podman), EngineKind::Podman);
+
+        let docker: Version = serde_json::from_str(
+            r#"{"Platform":{"Name":"Docker Engine - Community"},
+                "Components":[{"Name":"Engine","Version":"27.3.1"}],
+                "Version":"27.3.1","ApiVersion":"1.47"}"#,
+        )
+        .unwrap();
+        assert_eq!(EngineKind::detect(&docker), EngineKind::Docker);
+    }
+
+    #[test]
+    fn missing_objects_are_not_unsupported_endpoints() {
+        assert!(is_unsupported_endpoint(501, "not implemented"));
+        assert!(is_unsupported_endpoint(404, "page not found"));
+        assert!(is_unsupported_endpoint(404, "404 page not found\n"));
+        assert!(is_unsupported_endpoint(405, ""));
+        assert!(!is_unsupported_endpoint(404, "no such container: web"));
+        // Podman's missing-object messages
+        assert!(!is_unsupported_endpoint(
+            404,
+            "docker.io/library/nope:latest: image not known"
+        ));
+        assert!(!is_unsupported_endpoint(
+            404,
+            "no container with name or ID \"web\" found: no such container"
+        ));
+        assert!(!is_unsupported_endpoint(
+            404,
+            "no container with name or ID web found"
+        ));
+        assert!(!is_unsupported_endpoint(404, "no such volume data"));
+        assert!(!is_unsupported_endpoint(500, "boom"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_removes_the_container_when_a_network_connect_fails() {
+        use super::super::stand_in::StandIn;
+
+        let engine = StandIn::start(|request| match request.path.as_str() {
+            "/containers/create" => (201, r#"{"Id":"0123456789abcdef","Warnings":[]}"#.into()),
+            "/networks/backend/connect" => {
+                (404, r#"{"message":"network backend not found"}"#.into())
+            }
+            _ => (204, String::new()),
+        });
+        let service = DockerService::connect(&engine.endpoint()).await.unwrap();
+        let spec = RunSpec {
+            image: "nginx:latest".to_string(),
+            networks: vec!["frontend".to_string(), "backend".to_string()],
+            ports: vec![PortMapping::parse("8080:80").unwrap()],
+            ..Default::default()
+        };
+
+        let err = service.run_container(spec).await.unwrap_err();
+        assert!(err.to_string().contains("backend"), "{err}");
+
+        let requests = engine.requests();
+        let create = &requests[0];
+        assert_eq!(create.path, "/containers/create");
+        let config: serde_json::Value = serde_json::from_str(&create.body).unwrap();
+        assert_eq!(config["HostConfig"]["NetworkMode"], "frontend");
+        assert_eq!(
+            config["HostConfig"]["PortBindings"]["80/tcp"][0]["HostPort"],
+            "8080"
+        );
+        let remove = requests.last().unwrap();
+        assert_eq!(remove.method, "DELETE");
+        assert_eq!(remove.path, "/containers/0123456789abcdef");
+        assert_eq!(remove.query.get("force").map(String::as_str), Some("true"));
+        assert!(!requests.iter().any(|r| r.path.ends_with("/start")));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn image_removal_and_prune_options_reach_the_engine() {
+        use super::super::stand_in::StandIn;
+
+        let engine = StandIn::start(|request| {
+            match request.path.as_str() {
+            "/images/prune" => (
+                200,
+                r#"{"ImagesDeleted":[{"Untagged":"app:1"},{"Deleted":"sha256:aa"},{"Deleted":"sha256:bb"}],"SpaceReclaimed":2048}"#.into(),
+            ),
+            _ => (200, r#"[{"Untagged":"app:1"}]"#.into()),
+        }
+        });
+        let service = DockerService::connect(&engine.endpoint()).await.unwrap();
+
+        let options = ImageRemoveOptions {
+            force: true,
+            no_prune: true,
+        };
+        service.remove_image("sha256:aa", options).await.unwrap();
+        service
+            .remove_image("app:1", ImageRemoveOptions::default())
+            .await
+            .unwrap();
+        let report = service.prune_images(PruneScope::Dangling).await.unwrap();
+        assert_eq!(report.deleted, ["sha256:aa", "sha256:bb"]);
+        assert_eq!(report.reclaimed_bytes, 2048);
+        service.prune_images(PruneScope::Unused).await.unwrap();
+
+        let requests = engine.requests();
+        let query = |index: usize, name: &str| requests[index].query.get(name).cloned();
+        assert_eq!(requests[0].method, "DELETE");
+        assert_eq!(requests[0].path, "/images/sha256:aa");
+        assert_eq!(query(0, "force").as_deref(), Some("true"));
+        assert_eq!(query(0, "noprune").as_deref(), Some("true"));
+        assert_eq!(requests[1].path, "/images/app:1");
+        assert_eq!(query(1, "force").as_deref(), Some("false"));
+        assert_eq!(query(1, "noprune").as_deref(), Some("false"));
+
+        let filters = |index: usize| -> serde_json::Value {
+            serde_json::from_str(&query(index, "filters").unwrap()).unwrap()
+        };
+        assert_eq!(requests[2].method, "POST");
+        assert_eq!(requests[2].path, "/images/prune");
+        assert_eq!(filters(2), serde_json::json!({"dangling": ["true"]}));
+        assert_eq!(filters(3), serde_json::json!({"dangling": ["false"]}));
+    }
+
+    #[test]
+    fn pull_requests_split_the_tag_from_the_repository() {
+        let request = |reference: &str, platform: Option<&str>| {
+            let options = pull_request(PullOptions {
+                reference: reference.to_string(),
+                platform: platform.map(str::to_string),
+            });
+            (options.from_image, options.tag, options.platform)
+        };
+        assert_eq!(
+            request("nginx", None),
+            ("nginx".into(), "latest".into(), String::new())
+        );
+        assert_eq!(
+            request("localhost:5000/team/app:1.2", Some("linux/arm64")),
+            (
+                "localhost:5000/team/app".into(),
+                "1.2".into(),
+                "linux/arm64".into()
+            )
+        );
+        assert_eq!(
+            request("ghcr.io/org/app@sha256:0a1b", None),
+            (
+                "ghcr.io/org/app".into(),
+                "sha256:0a1b".into(),
+                String::new()
+            )
+        );
+        assert_eq!(
+            request("Not Valid", None),
+            ("Not Valid".into(), String::new(), String::new())
+        );
+    }
+
+    /// Messages as the engine streams them while pulling `nginx:1.27`.
+    const PULL_MESSAGES: &str = r#"
+        {"status":"Pulling from library/nginx","id":"1.27"}
+        {"status":"Already exists","progressDetail":{},"id":"a1"}
+        {"status":"Pulling fs layer","progressDetail":{},"id":"b2"}
+        {"status":"Downloading","progressDetail":{"current":512,"total":2048},"progress":"[=>  ]","id":"b2"}
+        {"status":"Verifying Checksum","progressDetail":{},"id":"b2"}
+        {"status":"Download complete","progressDetail":{},"id":"b2"}
+        {"status":"Extracting","progressDetail":{"current":1024,"total":2048},"id":"b2"}
+        {"status":"Pull complete","progressDetail":{},"id":"b2"}
+        {"status":"Digest: sha256:0a1b"}
+        {"status":"Status: Downloaded newer image for nginx:1.27"}
+    "#;
+
+    fn recorded(messages: &str) -> Vec<CreateImageInfo> {
+        messages
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn pull_messages_fold_into_layer_progress() {
+        let mut state = PullState::default();
+        let mut downloading = None;
+        for info in recorded(PULL_MESSAGES) {
+            state.apply(pull_progress(info).unwrap());
+            if downloading.is_none() && state.layers.len() == 2 && state.layers[1].current > 0 {
+                downloading = Some(state.layers[1].clone());
+            }
+        }
+        let downloading = downloading.unwrap();
+        assert_eq!(downloading.phase, LayerPhase::Downloading);
+        assert_eq!(downloading.percent(), 25);
+
+        assert_eq!(state.layers.len(), 2);
+        assert_eq!(state.layers[0].phase, LayerPhase::Exists);
+        assert_eq!(state.layers[1].phase, LayerPhase::Complete);
+        assert_eq!(state.layers_done(), 2);
+        assert_eq!(
+            state.status.as_deref(),
+            Some("Status: Downloaded newer image for nginx:1.27")
+        );
+    }
+
+    /// Pulls through a stand-in engine that relays a registry's progress as
+    /// the daemon does, so bollard's stream decoding is exercised too.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pulls_stream_progress_from_a_stand_in_engine() {
+        use super::super::stand_in::StandIn;
+
+        // One JSON message per line, as the daemon streams them
+        let progress: Vec<&str> = PULL_MESSAGES
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let progress = progress.join("\n");
+        let engine = StandIn::start(move |request| {
+            match request.query.get("tag").map(String::as_str) {
+            Some("1.27") => (200, progress.clone()),
+            _ => (
+                200,
+                r#"{"errorDetail":{"message":"manifest for nginx:9.99 not found"},"error":"manifest for nginx:9.99 not found"}"#.into(),
+            ),
+        }
+        });
+        let service = DockerService::connect(&engine.endpoint()).await.unwrap();
+        let pull = |reference: &str| {
+            service.pull_image(PullOptions {
+                reference: reference.to_string(),
+                platform: None,
+            })
+        };
+
+        let mut state = PullState::default();
+        let mut stream = pull("nginx:1.27");
+        while let Some(progress) = stream.next().await {
+            state.apply(progress.unwrap());
+        }
+        assert_eq!(state.layers.len(), 2);
+        assert_eq!(state.layers_done(), 2);
+        assert_eq!(
+            state.status.as_deref(),
+            Some("Status: Downloaded newer image for nginx:1.27")
+        );
+
+        let mut stream = pull("nginx:9.99");
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "manifest for nginx:9.99 not found");
+
+        let requests = engine.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/images/create");
+        assert_eq!(
+            requests[0].query.get("fromImage").map(String::as_str),
+            Some("nginx")
+        );
+        assert_eq!(
+            requests[1].query.get("tag").map(String::as_str),
+            Some("9.99")
+        );
+    }
+
+    #[test]
+    fn pull_errors_end_the_pull() {
+        let messages = recorded(
+            r#"
+            {"status":"Pulling from library/nginx","id":"9.99"}
+            {"errorDetail":{"message":"manifest for nginx:9.99 not found"},"error":"manifest for nginx:9.99 not found"}
+        "#,
+        );
+        let mut progress = messages.into_iter().map(pull_progress);
+        assert!(progress.next().unwrap().is_ok());
+        let err = progress.next().unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "manifest for nginx:9.99 not found");
+    }
+
+    #[test]
+    fn parses_inspect_timestamps() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:01Z"), Some(1));
+        assert_eq!(
+            parse_timestamp("2024-05-01T12:34:56.123456789Z"),
+            Some(1_714_566_896)
+        );
+        assert_eq!(
+            parse_timestamp("2024-05-01T14:34:56.5+02:00"),
+            Some(1_714_566_896)
+        );
+        assert_eq!(parse_timestamp("2000-02-29T00:00:00Z"), Some(951_782_400));
+        assert_eq!(parse_timestamp("0001-01-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
     }
 
     #[test]
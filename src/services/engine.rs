@@ -0,0 +1,108 @@
+//! The operations `AppState` needs from an engine, so the Docker API client
+//! can be swapped for an in-memory fake in tests.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::actions::{ContainerAction, KillSignal, RemoveOptions};
+use super::docker::{ContainerInfo, EngineReport, ImageInfo, VolumeInfo};
+use super::events::EventStream;
+use super::exec::{ExecOptions, ExecSession};
+use super::images::{ImageRemoveOptions, PruneReport, PruneScope};
+use super::inspect::ContainerDetails;
+use super::logs::{LogChunks, LogOptions};
+use super::pull::{PullOptions, PullStream};
+use super::run::RunSpec;
+use super::stats::StatsStream;
+
+/// An engine handle that can be cloned cheaply and shared between tasks.
+pub type SharedEngine = Arc<dyn ContainerEngine>;
+
+#[async_trait]
+pub trait ContainerEngine: Send + Sync {
+    /// Pings the engine and collects its runtime and version details.
+    async fn probe(&self) -> Result<EngineReport>;
+
+    async fn list_containers(&self) -> Result<Vec<ContainerInfo>>;
+
+    /// Lists one container by id, as `list_containers` shows it. `None` once
+    /// the container is gone.
+    async fn find_container(&self, id: &str) -> Result<Option<ContainerInfo>>;
+
+    async fn list_images(&self) -> Result<Vec<ImageInfo>>;
+
+    async fn list_volumes(&self) -> Result<Vec<VolumeInfo>>;
+
+    /// Looks up a single container by id or name, with its full configuration.
+    async fn inspect_container(&self, id: &str) -> Result<ContainerDetails>;
+
+    async fn start_container(&self, id: &str) -> Result<()>;
+
+    async fn stop_container(&self, id: &str) -> Result<()>;
+
+    /// Stops the container, waiting up to `timeout_secs` before killing it,
+    /// and starts it again.
+    async fn restart_container(&self, id: &str, timeout_secs: u32) -> Result<()>;
+
+    async fn pause_container(&self, id: &str) -> Result<()>;
+
+    async fn unpause_container(&self, id: &str) -> Result<()>;
+
+    async fn kill_container(&self, id: &str, signal: KillSignal) -> Result<()>;
+
+    async fn remove_container(&self, id: &str, options: RemoveOptions) -> Result<()>;
+
+    /// Removes image `id`, or just the tag when given a `repository:tag`
+    /// and the image has others.
+    async fn remove_image(&self, id: &str, options: ImageRemoveOptions) -> Result<()>;
+
+    /// Adds the tag `repository:tag` to image `id`.
+    async fn tag_image(&self, id: &str, repository: &str, tag: &str) -> Result<()>;
+
+    /// Removes the images in `scope` that no container uses.
+    async fn prune_images(&self, scope: PruneScope) -> Result<PruneReport>;
+
+    async fn remove_volume(&self, name: &str) -> Result<()>;
+
+    /// Creates a container from `spec` and starts it, returning its id.
+    async fn run_container(&self, spec: RunSpec) -> Result<String>;
+
+    /// Streams the output of container `id`. With `options.follow` the stream
+    /// stays open until the container stops or the stream is dropped.
+    fn logs(&self, id: &str, options: LogOptions) -> LogChunks;
+
+    /// Pulls an image into the local cache, streaming progress per layer.
+    /// Dropping the stream cancels the pull.
+    fn pull_image(&self, options: PullOptions) -> PullStream;
+
+    /// Streams resource usage samples of running container `id` until it
+    /// stops or the stream is dropped.
+    fn stats(&self, id: &str) -> StatsStream;
+
+    /// Subscribes to container, image and volume events. The stream ends when
+    /// the connection to the engine drops.
+    fn events(&self) -> EventStream;
+
+    /// Starts an interactive process with a TTY in running container `id`.
+    async fn exec(&self, id: &str, options: ExecOptions) -> Result<ExecSession>;
+
+    /// Resizes the TTY of a running exec session.
+    async fn resize_exec(&self, exec_id: &str, cols: u16, rows: u16) -> Result<()>;
+
+    /// Runs one lifecycle `action` on container `id`.
+    async fn run_action(&self, id: &str, action: ContainerAction) -> Result<()> {
+        match action {
+            ContainerAction::Start => self.start_container(id).await,
+            ContainerAction::Stop => self.stop_container(id).await,
+            ContainerAction::Restart { timeout_secs } => {
+                self.restart_container(id, timeout_secs).await
+            }
+            ContainerAction::Pause => self.pause_container(id).await,
+            ContainerAction::Unpause => self.unpause_container(id).await,
+            ContainerAction::Kill(signal) => self.kill_container(id, signal).await,
+            ContainerAction::Remove(options) => self.remove_container(id, options).await,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Engine failures sorted into the kinds the UI explains differently.
+
+use std::fmt;
+use std::io::ErrorKind;
+
+use bollard::errors::Error as BollardError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// Nothing listens at the engine's address, or its socket is missing.
+    ConnectionRefused(String),
+    /// The socket exists but this user may not open it.
+    PermissionDenied(String),
+    NotFound(String),
+    /// The object is in use, or its name is taken.
+    Conflict(String),
+    /// Client and engine do not agree on an API version.
+    ApiVersionMismatch(String),
+    Timeout(String),
+    /// Certificate validation failed during the TLS handshake.
+    Tls(String),
+    Other(String),
+}
+
+impl EngineError {
+    /// Sorts `error` by the engine's status code or the underlying I/O error
+    /// when there is one, and by its message otherwise. Only failures to
+    /// reach the engine are taken for TLS errors, since a reply may quote
+    /// names such as `tls-proxy`.
+    pub fn classify(error: &anyhow::Error) -> Self {
+        let message = format!("{error:#}");
+        let lower = message.to_lowercase();
+
+        let engine = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<BollardError>());
+        if let Some(engine) = engine {
+            match engine {
+                BollardError::DockerResponseServerError { status_code, .. } => match status_code {
+                    404 => return EngineError::NotFound(message),
+                    409 => return EngineError::Conflict(message),
+                    _ => {}
+                },
+                BollardError::RequestTimeoutError => return EngineError::Timeout(message),
+                BollardError::APIVersionParseError { .. } => {
+                    return EngineError::ApiVersionMismatch(message);
+                }
+                BollardError::SocketNotFoundError(_) => {
+                    return EngineError::ConnectionRefused(message);
+                }
+                _ => {}
+            }
+        }
+
+        let io = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<std::io::Error>());
+        match io.map(|io| io.kind()) {
+            Some(ErrorKind::ConnectionRefused | ErrorKind::NotFound) => {
+                return EngineError::ConnectionRefused(message);
+            }
+            Some(ErrorKind::PermissionDenied) => return EngineError::PermissionDenied(message),
+            Some(ErrorKind::TimedOut) => return EngineError::Timeout(message),
+            _ => {}
+        }
+
+        let replied = matches!(engine, Some(BollardError::DockerResponseServerError { .. }));
+        let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+        if has(&[
+            "connection refused",
+            "socket not found",
+            "no such file or directory",
+        ]) {
+            EngineError::ConnectionRefused(message)
+        } else if has(&["permission denied"]) {
+            EngineError::PermissionDenied(message)
+        } else if has(&["client version", "api version", "is too new", "is too old"]) {
+            EngineError::ApiVersionMismatch(message)
+        } else if has(&["timed out", "timeout"]) {
+            EngineError::Timeout(message)
+        } else if has(&["no such ", "not found"]) {
+            EngineError::NotFound(message)
+        } else if has(&["conflict", "already in use", "is using"]) {
+            EngineError::Conflict(message)
+        } else if !replied && has(&["certificate", "unknownissuer", "handshake"]) {
+            EngineError::Tls(message)
+        } else {
+            EngineError::Other(message)
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            EngineError::ConnectionRefused(_) => "Engine unreachable",
+            EngineError::PermissionDenied(_) => "Permission denied",
+            EngineError::NotFound(_) => "Not found",
+            EngineError::Conflict(_) => "Conflict",
+            EngineError::ApiVersionMismatch(_) => "API version mismatch",
+            EngineError::Timeout(_) => "Timed out",
+            EngineError::Tls(_) => "TLS error",
+            EngineError::Other(_) => "Engine error",
+        }
+    }
+
+    /// What the user can do about it, when there is an obvious fix.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            EngineError::ConnectionRefused(_) => {
+                Some("Check that the engine is running and the host in Settings is right.")
+            }
+            EngineError::PermissionDenied(_) => {
+                Some("Add your user to the docker group, or use a rootless engine's socket.")
+            }
+            EngineError::ApiVersionMismatch(_) => {
+                Some("The engine is older or newer than this app supports; update one of them.")
+            }
+            EngineError::Timeout(_) => Some("The engine is busy or the network is slow."),
+            EngineError::Tls(_) => {
+                Some("Check the CA, client certificate and key configured for this host.")
+            }
+            EngineError::NotFound(_) | EngineError::Conflict(_) | EngineError::Other(_) => None,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            EngineError::ConnectionRefused(message)
+            | EngineError::PermissionDenied(message)
+            | EngineError::NotFound(message)
+            | EngineError::Conflict(message)
+            | EngineError::ApiVersionMismatch(message)
+            | EngineError::Timeout(message)
+            | EngineError::Tls(message)
+            | EngineError::Other(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Tls(message) => write!(f, "TLS certificate error: {message}"),
+            other => f.write_str(other.message()),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// The error's message for display, with a label for TLS handshake failures,
+/// which otherwise surface as generic connection failures.
+pub fn describe_error(error: &anyhow::Error) -> String {
+    EngineError::classify(error).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn handshake_failures_are_labelled() {
+        let error = anyhow!("invalid peer certificate: UnknownIssuer");
+        assert!(describe_error(&error).starts_with("TLS certificate error"));
+        let error = anyhow!("connection refused");
+        assert_eq!(describe_error(&error), "connection refused");
+    }
+
+    #[test]
+    fn classifies_by_io_error_and_message() {
+        let denied = std::io::Error::from(ErrorKind::PermissionDenied);
+        let error = anyhow::Error::new(denied).context("Connecting to unix:///var/run/docker.sock");
+        assert!(matches!(
+            EngineError::classify(&error),
+            EngineError::PermissionDenied(_)
+        ));
+
+        let classify = |message: &str| EngineError::classify(&anyhow!("{message}"));
+        assert_eq!(
+            classify("No such container: web"),
+            EngineError::NotFound("No such container: web".to_string())
+        );
+        assert!(matches!(
+            classify("Conflict. The container name \"/web\" is already in use"),
+            EngineError::Conflict(_)
+        ));
+        assert!(matches!(
+            classify("client version 1.47 is too new. Maximum supported API version is 1.41"),
+            EngineError::ApiVersionMismatch(_)
+        ));
+        assert!(matches!(
+            classify("operation timed out"),
+            EngineError::Timeout(_)
+        ));
+        assert_eq!(classify("boom").title(), "Engine error");
+    }
+
+    #[test]
+    fn names_mentioning_tls_are_not_tls_errors() {
+        let classify = |message: &str| EngineError::classify(&anyhow!("{message}"));
+        assert!(matches!(
+            classify("No such container: tls-proxy"),
+            EngineError::NotFound(_)
+        ));
+        assert!(matches!(
+            classify("Cannot connect to the tls-gateway"),
+            EngineError::Other(_)
+        ));
+
+        let reply = BollardError::DockerResponseServerError {
+            status_code: 409,
+            message: "Conflict. The container name \"/tls-gateway\" is already in use".into(),
+        };
+        assert!(matches!(
+            EngineError::classify(&anyhow::Error::new(reply)),
+            EngineError::Conflict(_)
+        ));
+        let reply = BollardError::DockerResponseServerError {
+            status_code: 500,
+            message: "certificate-renewer exited".into(),
+        };
+        assert!(matches!(
+            EngineError::classify(&anyhow::Error::new(reply)),
+            EngineError::Other(_)
+        ));
+    }
+}
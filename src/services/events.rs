@@ -0,0 +1,88 @@
+//! Changes announced on the engine's events stream, reduced to what the
+//! resource lists need to stay current.
+
+use anyhow::Result;
+use futures_util::stream::BoxStream;
+
+/// A change to the containers, images or volumes of one engine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineEvent {
+    /// Container `id` was created or changed state; its row is re-read.
+    ContainerChanged(String),
+    ContainerRemoved(String),
+    /// An image was pulled, tagged, untagged, imported or loaded. Events do
+    /// not carry enough to build rows, so the image list is reloaded.
+    ImagesChanged,
+    /// Image `id` was deleted.
+    ImageRemoved(String),
+    /// Volume `name` was created; the volume list is reloaded.
+    VolumeCreated(String),
+    VolumeRemoved(String),
+}
+
+impl EngineEvent {
+    /// Maps an event message's `Type`, `Action` and actor id. Events that do
+    /// not change a list, such as exec sessions or volume mounts, give `None`.
+    pub fn from_message(kind: &str, action: &str, id: &str) -> Option<Self> {
+        // Health events are reported as "health_status: healthy"
+        let action = action.split(':').next().unwrap_or_default().trim();
+        let id = id.to_string();
+        match (kind, action) {
+            ("container", "destroy") => Some(EngineEvent::ContainerRemoved(id)),
+            (
+                "container",
+                "create" | "start" | "restart" | "stop" | "die" | "kill" | "oom" | "pause"
+                | "unpause" | "rename" | "update" | "health_status",
+            ) => Some(EngineEvent::ContainerChanged(id)),
+            ("image", "delete") => Some(EngineEvent::ImageRemoved(id)),
+            ("image", "pull" | "tag" | "untag" | "import" | "load") => {
+                Some(EngineEvent::ImagesChanged)
+            }
+            ("volume", "create") => Some(EngineEvent::VolumeCreated(id)),
+            ("volume", "destroy") => Some(EngineEvent::VolumeRemoved(id)),
+            _ => None,
+        }
+    }
+}
+
+/// Events of one engine, until the connection drops or the stream is
+/// dropped.
+pub type EventStream = BoxStream<'static, Result<EngineEvent>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_event_messages() {
+        assert_eq!(
+            EngineEvent::from_message("container", "die", "abc"),
+            Some(EngineEvent::ContainerChanged("abc".to_string()))
+        );
+        assert_eq!(
+            EngineEvent::from_message("container", "health_status: unhealthy", "abc"),
+            Some(EngineEvent::ContainerChanged("abc".to_string()))
+        );
+        assert_eq!(
+            EngineEvent::from_message("container", "destroy", "abc"),
+            Some(EngineEvent::ContainerRemoved("abc".to_string()))
+        );
+        assert_eq!(
+            EngineEvent::from_message("image", "pull", "nginx:latest"),
+            Some(EngineEvent::ImagesChanged)
+        );
+        assert_eq!(
+            EngineEvent::from_message("volume", "destroy", "data"),
+            Some(EngineEvent::VolumeRemoved("data".to_string()))
+        );
+        assert_eq!(
+            EngineEvent::from_message("container", "exec_start: sh", "abc"),
+            None
+        );
+        assert_eq!(EngineEvent::from_message("volume", "mount", "data"), None);
+        assert_eq!(
+            EngineEvent::from_message("network", "connect", "bridge"),
+            None
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Interactive exec sessions: a process started inside a running container
+//! with a TTY attached.
+
+use std::pin::Pin;
+
+use anyhow::{Result, bail};
+use futures_util::stream::BoxStream;
+use tokio::io::AsyncWrite;
+
+/// Options of `docker exec -it`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecOptions {
+    pub cmd: Vec<String>,
+    pub user: Option<String>,
+    pub working_dir: Option<String>,
+    /// `KEY=VALUE` entries.
+    pub env: Vec<String>,
+    /// Initial terminal size.
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl ExecOptions {
+    /// Reads one `KEY=VALUE` entry per line, skipping blank lines.
+    pub fn parse_env(text: &str) -> Result<Vec<String>> {
+        let mut env = Vec::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match line.split_once('=') {
+                Some((key, _)) if !key.is_empty() && !key.contains(char::is_whitespace) => {
+                    env.push(line.to_string())
+                }
+                _ => bail!("\"{line}\" is not a KEY=VALUE environment entry"),
+            }
+        }
+        Ok(env)
+    }
+}
+
+/// A running exec attached over the engine's hijacked connection. Output
+/// ends when the process exits; dropping the session detaches from it.
+pub struct ExecSession {
+    /// Engine-side id, needed to resize the TTY.
+    pub exec_id: String,
+    pub output: BoxStream<'static, Result<Vec<u8>>>,
+    pub input: Pin<Box<dyn AsyncWrite + Send>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_env_lines() {
+        let env = ExecOptions::parse_env("TERM=xterm\n\n  DEBUG=1 \nEMPTY=").unwrap();
+        assert_eq!(env, ["TERM=xterm", "DEBUG=1", "EMPTY="]);
+        assert!(ExecOptions::parse_env("=value").is_err());
+        assert!(ExecOptions::parse_env("NO VALUE").is_err());
+    }
+}
@@ -0,0 +1,898 @@
+//! An in-memory `ContainerEngine` for tests. It holds containers, images and
+//! volumes, applies lifecycle calls to them, and can be told to fail or to
+//! answer slowly.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
+use futures_util::{StreamExt, stream};
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+
+use super::actions::{KillSignal, RemoveOptions};
+use super::docker::{
+    ContainerInfo, ContainerState, EngineKind, EngineReport, ImageInfo, VolumeInfo,
+};
+use super::engine::ContainerEngine;
+use super::events::{EngineEvent, EventStream};
+use super::exec::{ExecOptions, ExecSession};
+use super::images::{ImageRemoveOptions, PruneReport, PruneScope};
+use super::inspect::ContainerDetails;
+use super::logs::{LogChunk, LogChunks, LogOptions, LogStream};
+use super::pull::{PullOptions, PullProgress, PullStream, with_default_tag};
+use super::reference::ImageReference;
+use super::run::RunSpec;
+use super::stats::{ContainerStats, StatsStream, with_deltas};
+
+/// The engine calls a `FakeEngine` can be scripted to fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Probe,
+    ListContainers,
+    FindContainer,
+    ListImages,
+    ListVolumes,
+    InspectContainer,
+    StartContainer,
+    StopContainer,
+    RestartContainer,
+    PauseContainer,
+    UnpauseContainer,
+    KillContainer,
+    RemoveContainer,
+    RemoveImage,
+    TagImage,
+    PruneImages,
+    RemoveVolume,
+    RunContainer,
+    PullImage,
+    Logs,
+    Stats,
+    Events,
+    Exec,
+    ResizeExec,
+}
+
+#[derive(Default)]
+struct FakeState {
+    containers: Vec<ContainerInfo>,
+    images: Vec<ImageInfo>,
+    volumes: Vec<VolumeInfo>,
+    /// Images a pull can fetch, by reference, standing in for a registry.
+    registry: HashMap<String, ImageInfo>,
+    logs: Vec<LogChunk>,
+    stats: Vec<ContainerStats>,
+    failures: HashMap<Operation, String>,
+    latency: Duration,
+    calls: Vec<Operation>,
+    subscribers: Vec<mpsc::UnboundedSender<Result<EngineEvent>>>,
+}
+
+/// Clones share the same state, so a test can keep one handle to script the
+/// engine after handing another to `AppState`.
+#[derive(Clone, Default)]
+pub struct FakeEngine {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl FakeEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_container(self, container: ContainerInfo) -> Self {
+        self.lock().containers.push(container);
+        self
+    }
+
+    pub fn with_image(self, image: ImageInfo) -> Self {
+        self.lock().images.push(image);
+        self
+    }
+
+    pub fn with_volume(self, volume: VolumeInfo) -> Self {
+        self.lock().volumes.push(volume);
+        self
+    }
+
+    /// Makes `image` available to `pull_image` as `reference`.
+    pub fn with_registry_image(self, reference: &str, image: ImageInfo) -> Self {
+        self.lock().registry.insert(reference.to_string(), image);
+        self
+    }
+
+    /// Adds a chunk of output returned by `logs`, for every container.
+    pub fn with_log(self, stream: LogStream, text: &str) -> Self {
+        self.lock().logs.push(LogChunk {
+            stream,
+            text: text.to_string(),
+        });
+        self
+    }
+
+    /// Adds a sample returned by `stats`, for every container. Only `totals`
+    /// matter for I/O; deltas are derived from them.
+    pub fn with_stats(self, sample: ContainerStats) -> Self {
+        self.lock().stats.push(sample);
+        self
+    }
+
+    /// Delays every call by `latency` before it is answered.
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.lock().latency = latency;
+        self
+    }
+
+    /// Makes `operation` fail with `message` until `recover` is called.
+    pub fn fail(&self, operation: Operation, message: &str) {
+        self.lock().failures.insert(operation, message.to_string());
+    }
+
+    pub fn recover(&self, operation: Operation) {
+        self.lock().failures.remove(&operation);
+    }
+
+    /// Sends `event` to every open events stream, as the engine does after a
+    /// change. Changes made through the fake's own calls are announced
+    /// automatically.
+    pub fn emit(&self, event: EngineEvent) {
+        self.lock()
+            .subscribers
+            .retain(|subscriber| subscriber.send(Ok(event.clone())).is_ok());
+    }
+
+    /// Ends every open events stream, as a dropped connection would.
+    pub fn drop_events(&self) {
+        self.lock().subscribers.clear();
+    }
+
+    /// Events streams that are still being read.
+    pub fn open_streams(&self) -> usize {
+        let mut state = self.lock();
+        state
+            .subscribers
+            .retain(|subscriber| !subscriber.is_closed());
+        state.subscribers.len()
+    }
+
+    /// Every call made so far, in order, including failed ones.
+    pub fn calls(&self) -> Vec<Operation> {
+        self.lock().calls.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FakeState> {
+        self.state.lock().expect("fake engine state poisoned")
+    }
+
+    /// Records the call, waits out the latency and returns the scripted
+    /// failure, if any.
+    async fn enter(&self, operation: Operation) -> Result<()> {
+        let latency = {
+            let mut state = self.lock();
+            state.calls.push(operation);
+            state.latency
+        };
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        match self.lock().failures.get(&operation) {
+            Some(message) => Err(anyhow!("{message}")),
+            None => Ok(()),
+        }
+    }
+
+    fn set_state(&self, id: &str, state: ContainerState, status: &str) -> Result<()> {
+        let changed = {
+            let mut fake = self.lock();
+            let Some(container) = fake.containers.iter_mut().find(|c| matches(c, id)) else {
+                bail!("No such container: {id}");
+            };
+            container.state = state;
+            container.status = status.to_string();
+            container.id.clone()
+        };
+        self.emit(EngineEvent::ContainerChanged(changed));
+        Ok(())
+    }
+}
+
+fn matches(container: &ContainerInfo, id: &str) -> bool {
+    container.id.starts_with(id) || container.name == id
+}
+
+/// Index of the tag `reference` among the tags of `image`.
+fn tag_index(image: &ImageInfo, reference: &str) -> Option<usize> {
+    let reference = with_default_tag(reference);
+    image
+        .tags
+        .iter()
+        .position(|tag| tag.to_string() == reference)
+}
+
+/// Seeded containers have no engine name, so the image's is ignored.
+fn image_in_use(image: &ImageInfo, container: &ContainerInfo) -> bool {
+    tag_index(image, &container.image).is_some()
+        || (!image.id.is_empty() && container.image_id == image.id)
+}
+
+#[async_trait]
+impl ContainerEngine for FakeEngine {
+    async fn probe(&self) -> Result<EngineReport> {
+        self.enter(Operation::Probe).await?;
+        Ok(EngineReport {
+            kind: EngineKind::Docker,
+            api_version: "1.47".to_string(),
+            engine_version: "fake".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            latency: self.lock().latency,
+        })
+    }
+
+    async fn list_containers(&self) -> Result<Vec<ContainerInfo>> {
+        self.enter(Operation::ListContainers).await?;
+        Ok(self.lock().containers.clone())
+    }
+
+    async fn find_container(&self, id: &str) -> Result<Option<ContainerInfo>> {
+        self.enter(Operation::FindContainer).await?;
+        let fake = self.lock();
+        Ok(fake.containers.iter().find(|c| matches(c, id)).cloned())
+    }
+
+    async fn list_images(&self) -> Result<Vec<ImageInfo>> {
+        self.enter(Operation::ListImages).await?;
+        Ok(self.lock().images.clone())
+    }
+
+    async fn list_volumes(&self) -> Result<Vec<VolumeInfo>> {
+        self.enter(Operation::ListVolumes).await?;
+        Ok(self.lock().volumes.clone())
+    }
+
+    async fn inspect_container(&self, id: &str) -> Result<ContainerDetails> {
+        self.enter(Operation::InspectContainer).await?;
+        self.lock()
+            .containers
+            .iter()
+            .find(|c| matches(c, id))
+            .cloned()
+            .map(ContainerDetails::new)
+            .ok_or_else(|| anyhow!("No such container: {id}"))
+    }
+
+    async fn start_container(&self, id: &str) -> Result<()> {
+        self.enter(Operation::StartContainer).await?;
+        self.set_state(id, ContainerState::Running, "Up Less than a second")
+    }
+
+    async fn stop_container(&self, id: &str) -> Result<()> {
+        self.enter(Operation::StopContainer).await?;
+        self.set_state(
+            id,
+            ContainerState::Exited(Some(0)),
+            "Exited (0) Less than a second ago",
+        )
+    }
+
+    async fn restart_container(&self, id: &str, _timeout_secs: u32) -> Result<()> {
+        self.enter(Operation::RestartContainer).await?;
+        self.set_state(id, ContainerState::Running, "Up Less than a second")
+    }
+
+    async fn pause_container(&self, id: &str) -> Result<()> {
+        self.enter(Operation::PauseContainer).await?;
+        self.set_state(id, ContainerState::Paused, "Up 1 hour (Paused)")
+    }
+
+    async fn unpause_container(&self, id: &str) -> Result<()> {
+        self.enter(Operation::UnpauseContainer).await?;
+        self.set_state(id, ContainerState::Running, "Up 1 hour")
+    }
+
+    async fn kill_container(&self, id: &str, signal: KillSignal) -> Result<()> {
+        self.enter(Operation::KillContainer).await?;
+        let code = 128 + signal.number();
+        self.set_state(
+            id,
+            ContainerState::Exited(Some(code)),
+            &format!("Exited ({code}) Less than a second ago"),
+        )
+    }
+
+    /// An echo session: whatever is written to the input comes back as output.
+    async fn exec(&self, id: &str, _options: ExecOptions) -> Result<ExecSession> {
+        self.enter(Operation::Exec).await?;
+        let running = self
+            .lock()
+            .containers
+            .iter()
+            .any(|c| matches(c, id) && c.state == ContainerState::Running);
+        if !running {
+            bail!("Container {id} is not running");
+        }
+
+        let (input, output) = tokio::io::duplex(4096);
+        let output = stream::unfold(output, |mut output| async move {
+            let mut buf = vec![0; 1024];
+            match output.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(read) => {
+                    buf.truncate(read);
+                    Some((Ok(buf), output))
+                }
+                Err(e) => Some((Err(anyhow::Error::from(e)), output)),
+            }
+        });
+        Ok(ExecSession {
+            exec_id: format!("exec-{id}"),
+            output: output.boxed(),
+            input: Box::pin(input),
+        })
+    }
+
+    async fn resize_exec(&self, _exec_id: &str, _cols: u16, _rows: u16) -> Result<()> {
+        self.enter(Operation::ResizeExec).await
+    }
+
+    /// Replays the seeded output once; the stream ends even when following.
+    fn logs(&self, _id: &str, options: LogOptions) -> LogChunks {
+        let mut state = self.lock();
+        state.calls.push(Operation::Logs);
+        if let Some(message) = state.failures.get(&Operation::Logs) {
+            let error = anyhow!("{message}");
+            return stream::once(async move { Err(error) }).boxed();
+        }
+        let skip = match options.tail {
+            Some(tail) => state.logs.len().saturating_sub(tail as usize),
+            None => 0,
+        };
+        let chunks: Vec<_> = state.logs[skip..].iter().cloned().map(Ok).collect();
+        stream::iter(chunks).boxed()
+    }
+
+    /// Reports two layers downloading and extracting, one message per
+    /// latency tick, and adds the image once the stream is read to the end.
+    fn pull_image(&self, options: PullOptions) -> PullStream {
+        let reference = with_default_tag(&options.reference);
+        let mut state = self.lock();
+        state.calls.push(Operation::PullImage);
+        if let Some(message) = state.failures.get(&Operation::PullImage) {
+            let error = anyhow!("{message}");
+            return stream::once(async move { Err(error) }).boxed();
+        }
+        let Some(image) = state.registry.get(&reference).cloned() else {
+            let error = anyhow!("manifest for {reference} not found: manifest unknown");
+            return stream::once(async move { Err(error) }).boxed();
+        };
+        let latency = state.latency;
+        drop(state);
+
+        let step = |id: &str, status: &str, bytes: Option<(u64, u64)>| PullProgress {
+            id: (!id.is_empty()).then(|| id.to_string()),
+            status: status.to_string(),
+            current: bytes.map(|(current, _)| current),
+            total: bytes.map(|(_, total)| total),
+        };
+        let tag = reference.rsplit(':').next().unwrap_or_default();
+        let mut steps = vec![step(tag, "Pulling from fake", None)];
+        for layer in ["layer1", "layer2"] {
+            steps.extend([
+                step(layer, "Pulling fs layer", None),
+                step(layer, "Downloading", Some((50, 100))),
+                step(layer, "Download complete", None),
+                step(layer, "Extracting", Some((100, 100))),
+                step(layer, "Pull complete", None),
+            ]);
+        }
+        steps.push(step(
+            "",
+            &format!("Status: Downloaded newer image for {reference}"),
+            None,
+        ));
+
+        let fake = self.clone();
+        let finish = stream::once(async move {
+            fake.lock().images.push(image);
+            fake.emit(EngineEvent::ImagesChanged);
+            None
+        });
+        stream::iter(steps.into_iter().map(|step| Some(Ok(step))))
+            .then(move |step| async move {
+                if !latency.is_zero() {
+                    tokio::time::sleep(latency).await;
+                }
+                step
+            })
+            .chain(finish)
+            .filter_map(|step| async move { step })
+            .boxed()
+    }
+
+    /// Replays the seeded samples once.
+    fn stats(&self, _id: &str) -> StatsStream {
+        let mut state = self.lock();
+        state.calls.push(Operation::Stats);
+        if let Some(message) = state.failures.get(&Operation::Stats) {
+            let error = anyhow!("{message}");
+            return stream::once(async move { Err(error) }).boxed();
+        }
+        let samples: Vec<_> = state.stats.iter().copied().map(Ok).collect();
+        with_deltas(stream::iter(samples).boxed())
+    }
+
+    async fn remove_container(&self, id: &str, options: RemoveOptions) -> Result<()> {
+        self.enter(Operation::RemoveContainer).await?;
+        let mut fake = self.lock();
+        let Some(index) = fake.containers.iter().position(|c| matches(c, id)) else {
+            bail!("No such container: {id}");
+        };
+        if fake.containers[index].state == ContainerState::Running && !options.force {
+            bail!(
+                "You cannot remove a running container {id}. Stop the container before attempting removal or force remove"
+            );
+        }
+        let removed = fake.containers.remove(index);
+        drop(fake);
+        self.emit(EngineEvent::ContainerRemoved(removed.id));
+        Ok(())
+    }
+
+    /// Removing by tag only drops that tag while the image has others.
+    /// Removing by id fails while the image has several tags, and either
+    /// fails while a container still uses the image, as the engine does
+    /// without force.
+    async fn remove_image(&self, id: &str, options: ImageRemoveOptions) -> Result<()> {
+        self.enter(Operation::RemoveImage).await?;
+        let mut fake = self.lock();
+        let by_tag = fake
+            .images
+            .iter()
+            .enumerate()
+            .find_map(|(index, image)| Some((index, Some(tag_index(image, id)?))));
+        let Some((index, tag)) = by_tag.or_else(|| {
+            let index = fake.images.iter().position(|i| i.id == id)?;
+            Some((index, None))
+        }) else {
+            bail!("No such image: {id}");
+        };
+        let image = &mut fake.images[index];
+        if let (Some(tag), true) = (tag, image.tags.len() > 1) {
+            image.tags.remove(tag);
+            drop(fake);
+            self.emit(EngineEvent::ImagesChanged);
+            return Ok(());
+        }
+        if image.tags.len() > 1 && !options.force {
+            bail!(
+                "conflict: unable to delete {} (must be forced) - image is referenced in multiple repositories",
+                image.short_id()
+            );
+        }
+        let image = &fake.images[index];
+        let user = fake.containers.iter().find(|c| image_in_use(image, c));
+        if let (Some(user), false) = (user, options.force) {
+            bail!(
+                "conflict: unable to remove repository reference \"{}\" (must force) - container {} is using its referenced image",
+                image.name(),
+                user.id
+            );
+        }
+        let removed = fake.images.remove(index);
+        drop(fake);
+        self.emit(EngineEvent::ImageRemoved(removed.id));
+        Ok(())
+    }
+
+    /// Moves the tag to image `id`, taking it from any image that had it.
+    async fn tag_image(&self, id: &str, repository: &str, tag: &str) -> Result<()> {
+        self.enter(Operation::TagImage).await?;
+        let reference = ImageReference::parse(&format!("{repository}:{tag}"))?;
+        let mut fake = self.lock();
+        let Some(index) = fake.images.iter().position(|i| i.id == id) else {
+            bail!("No such image: {id}");
+        };
+        for image in fake.images.iter_mut() {
+            image.tags.retain(|existing| *existing != reference);
+        }
+        fake.images[index].tags.push(reference);
+        drop(fake);
+        self.emit(EngineEvent::ImagesChanged);
+        Ok(())
+    }
+
+    async fn prune_images(&self, scope: PruneScope) -> Result<PruneReport> {
+        self.enter(Operation::PruneImages).await?;
+        let mut fake = self.lock();
+        let FakeState {
+            images, containers, ..
+        } = &mut *fake;
+        let mut report = PruneReport::default();
+        images.retain(|image| {
+            let in_scope = scope == PruneScope::Unused || image.is_dangling();
+            let used = containers.iter().any(|c| image_in_use(image, c));
+            if in_scope && !used {
+                report.deleted.push(image.id.clone());
+                report.reclaimed_bytes += image.size_bytes.max(0) as u64;
+                return false;
+            }
+            true
+        });
+        drop(fake);
+        for id in &report.deleted {
+            self.emit(EngineEvent::ImageRemoved(id.clone()));
+        }
+        Ok(report)
+    }
+
+    async fn remove_volume(&self, name: &str) -> Result<()> {
+        self.enter(Operation::RemoveVolume).await?;
+        let mut fake = self.lock();
+        let Some(index) = fake.volumes.iter().position(|v| v.name == name) else {
+            bail!("No such volume: {name}");
+        };
+        fake.volumes.remove(index);
+        drop(fake);
+        self.emit(EngineEvent::VolumeRemoved(name.to_string()));
+        Ok(())
+    }
+
+    async fn run_container(&self, spec: RunSpec) -> Result<String> {
+        self.enter(Operation::RunContainer).await?;
+        let mut fake = self.lock();
+        let id = format!("{:0>12}", fake.containers.len() + 1);
+        let name = spec
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("container_{id}"));
+        if fake.containers.iter().any(|c| c.name == name) {
+            bail!("Conflict. The container name \"/{name}\" is already in use");
+        }
+        fake.containers.push(ContainerInfo {
+            id: id.clone(),
+            name,
+            image: spec.image.clone(),
+            status: "Up Less than a second".to_string(),
+            ports: ports_summary(&spec),
+            published: spec.ports.clone(),
+            state: ContainerState::Running,
+            ..container("", "")
+        });
+        drop(fake);
+        self.emit(EngineEvent::ContainerChanged(id.clone()));
+        Ok(id)
+    }
+
+    /// Stays open until `drop_events` is called or the fake is dropped.
+    /// Like the engine's, the stream subscribes when first polled.
+    fn events(&self) -> EventStream {
+        let fake = self.clone();
+        stream::once(async move {
+            let mut state = fake.lock();
+            state.calls.push(Operation::Events);
+            if let Some(message) = state.failures.get(&Operation::Events) {
+                let error = anyhow!("{message}");
+                return stream::once(async move { Err(error) }).boxed();
+            }
+            let (sender, receiver) = mpsc::unbounded_channel();
+            state.subscribers.push(sender);
+            stream::unfold(receiver, |mut receiver| async move {
+                let event = receiver.recv().await?;
+                Some((event, receiver))
+            })
+            .boxed()
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+/// `8080:80, 9090:90`, like the summaries built from engine listings.
+fn ports_summary(spec: &RunSpec) -> String {
+    if spec.ports.is_empty() {
+        return "--".to_string();
+    }
+    spec.ports
+        .iter()
+        .map(|port| format!("{}:{}", port.host_port, port.container_port))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A stopped container with the given id and name, for seeding a fake.
+pub fn container(id: &str, name: &str) -> ContainerInfo {
+    ContainerInfo {
+        engine: String::new(),
+        id: id.to_string(),
+        name: name.to_string(),
+        image: "nginx:latest".to_string(),
+        image_id: String::new(),
+        status: "Exited (0) 1 hour ago".to_string(),
+        ports: "--".to_string(),
+        published: Vec::new(),
+        state: ContainerState::Exited(Some(0)),
+        health: None,
+        created: 0,
+        labels: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::PullState;
+
+    #[tokio::test]
+    async fn lifecycle_calls_update_containers() {
+        let engine = FakeEngine::new().with_container(container("abc123", "web"));
+
+        engine.start_container("web").await.unwrap();
+        let web = engine.inspect_container("abc").await.unwrap();
+        assert_eq!(web.info.state, ContainerState::Running);
+
+        engine.stop_container("abc123").await.unwrap();
+        assert_eq!(
+            engine.list_containers().await.unwrap()[0].state,
+            ContainerState::Exited(Some(0))
+        );
+        assert!(engine.start_container("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn logs_replay_the_tail() {
+        let engine = FakeEngine::new()
+            .with_log(LogStream::Stdout, "booting\n")
+            .with_log(LogStream::Stderr, "warning: low memory\n")
+            .with_log(LogStream::Stdout, "ready\n");
+        let options = LogOptions {
+            tail: Some(2),
+            ..Default::default()
+        };
+        let chunks: Vec<_> = engine.logs("web", options).collect().await;
+        let streams: Vec<_> = chunks.into_iter().map(|c| c.unwrap().stream).collect();
+        assert_eq!(streams, [LogStream::Stderr, LogStream::Stdout]);
+    }
+
+    #[tokio::test]
+    async fn stats_report_deltas_between_samples() {
+        use super::super::stats::IoCounters;
+
+        let sample = |net_rx| ContainerStats {
+            totals: IoCounters {
+                net_rx,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let engine = FakeEngine::new()
+            .with_stats(sample(100))
+            .with_stats(sample(350));
+        let samples: Vec<_> = engine.stats("web").collect().await;
+        let deltas: Vec<_> = samples
+            .into_iter()
+            .map(|s| s.unwrap().delta.net_rx)
+            .collect();
+        assert_eq!(deltas, [0, 250]);
+    }
+
+    #[tokio::test]
+    async fn exec_echoes_input() {
+        use tokio::io::AsyncWriteExt;
+
+        let engine = FakeEngine::new().with_container(container("abc123", "web"));
+        let options = ExecOptions {
+            cmd: vec!["/bin/sh".to_string()],
+            user: None,
+            working_dir: None,
+            env: Vec::new(),
+            cols: 80,
+            rows: 24,
+        };
+        assert!(engine.exec("web", options.clone()).await.is_err());
+
+        engine.start_container("web").await.unwrap();
+        let mut session = engine.exec("web", options).await.unwrap();
+        session.input.write_all(b"ls\n").await.unwrap();
+        let echoed = session.output.next().await.unwrap().unwrap();
+        assert_eq!(echoed, b"ls\n");
+        engine.resize_exec(&session.exec_id, 120, 40).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn running_containers_need_force_to_remove() {
+        let engine = FakeEngine::new().with_container(container("abc123", "web"));
+        engine.start_container("web").await.unwrap();
+
+        let keep = RemoveOptions::default();
+        assert!(engine.remove_container("web", keep).await.is_err());
+
+        engine
+            .kill_container("web", KillSignal::Kill)
+            .await
+            .unwrap();
+        let web = engine.inspect_container("web").await.unwrap();
+        assert_eq!(web.info.state, ContainerState::Exited(Some(137)));
+
+        engine.remove_container("web", keep).await.unwrap();
+        assert!(engine.list_containers().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn run_starts_a_new_container() {
+        use crate::services::PortMapping;
+
+        let engine = FakeEngine::new().with_container(container("abc123", "web"));
+        let spec = RunSpec {
+            image: "redis:7".to_string(),
+            name: Some("cache".to_string()),
+            ports: vec![PortMapping::parse("6379:6379").unwrap()],
+            ..Default::default()
+        };
+        let id = engine.run_container(spec.clone()).await.unwrap();
+        let cache = engine.inspect_container(&id).await.unwrap().info;
+        assert_eq!(cache.state, ContainerState::Running);
+        assert_eq!(cache.ports, "6379:6379");
+        assert!(engine.run_container(spec).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn changes_are_announced_as_events() {
+        let engine = FakeEngine::new().with_container(container("abc123", "web"));
+        // The stream subscribes when first polled
+        let mut events = engine.events().peekable();
+        let _ = futures_util::poll!(std::pin::Pin::new(&mut events).peek());
+
+        engine.start_container("web").await.unwrap();
+        engine
+            .remove_container(
+                "web",
+                RemoveOptions {
+                    force: true,
+                    volumes: false,
+                },
+            )
+            .await
+            .unwrap();
+        engine.drop_events();
+
+        let received: Vec<_> = events.map(Result::unwrap).collect().await;
+        assert_eq!(
+            received,
+            [
+                EngineEvent::ContainerChanged("abc123".to_string()),
+                EngineEvent::ContainerRemoved("abc123".to_string()),
+            ]
+        );
+        assert!(engine.find_container("abc").await.unwrap().is_none());
+    }
+
+    /// An image with the given tags, dangling when there are none.
+    fn image(id: &str, tags: &[&str], size_bytes: i64) -> ImageInfo {
+        ImageInfo {
+            engine: String::new(),
+            id: id.to_string(),
+            tags: tags
+                .iter()
+                .map(|tag| ImageReference::parse(tag).unwrap())
+                .collect(),
+            digests: Vec::new(),
+            size: String::new(),
+            size_bytes,
+            created: 0,
+            labels: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn pulls_add_the_image_once_finished() {
+        let image = image("sha256:1", &["nginx:latest"], 1_000_000);
+        let engine = FakeEngine::new().with_registry_image("nginx:latest", image);
+        let options = |reference: &str| PullOptions {
+            reference: reference.to_string(),
+            platform: None,
+        };
+
+        // Dropping the stream part way cancels the pull
+        let first: Vec<_> = engine.pull_image(options("nginx")).take(3).collect().await;
+        assert_eq!(first.len(), 3);
+        assert!(engine.list_images().await.unwrap().is_empty());
+
+        let mut pull = PullState::default();
+        let mut progress = engine.pull_image(options("nginx"));
+        while let Some(step) = progress.next().await {
+            pull.apply(step.unwrap());
+        }
+        assert_eq!(pull.layers_done(), 2);
+        assert_eq!(engine.list_images().await.unwrap().len(), 1);
+
+        let mut missing = engine.pull_image(options("nginx:0.1"));
+        let error = missing.next().await.unwrap().unwrap_err();
+        assert!(error.to_string().contains("manifest unknown"));
+    }
+
+    #[tokio::test]
+    async fn images_in_use_survive_pruning_and_plain_removal() {
+        let engine = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_image(image("sha256:1", &["nginx:latest"], 100))
+            .with_image(image("sha256:2", &["redis:latest"], 200))
+            .with_image(image("sha256:3", &[], 50));
+
+        let dangling = engine.prune_images(PruneScope::Dangling).await.unwrap();
+        assert_eq!(dangling.deleted, ["sha256:3"]);
+        assert_eq!(dangling.reclaimed_bytes, 50);
+
+        let unused = engine.prune_images(PruneScope::Unused).await.unwrap();
+        assert_eq!(unused.deleted, ["sha256:2"]);
+
+        let keep = ImageRemoveOptions::default();
+        assert!(engine.remove_image("sha256:1", keep).await.is_err());
+        let force = ImageRemoveOptions {
+            force: true,
+            ..keep
+        };
+        engine.remove_image("sha256:1", force).await.unwrap();
+        assert!(engine.list_images().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn removing_a_tag_keeps_an_image_with_others() {
+        let engine = FakeEngine::new().with_image(image("sha256:1", &["app:1"], 100));
+        engine
+            .tag_image("sha256:1", "localhost:5000/app", "1")
+            .await
+            .unwrap();
+        let tags = |images: Vec<ImageInfo>| -> Vec<String> {
+            images[0].tags.iter().map(|tag| tag.to_string()).collect()
+        };
+        assert_eq!(
+            tags(engine.list_images().await.unwrap()),
+            ["app:1", "localhost:5000/app:1"]
+        );
+
+        let keep = ImageRemoveOptions::default();
+        assert!(engine.remove_image("sha256:1", keep).await.is_err());
+        engine.remove_image("app:1", keep).await.unwrap();
+        assert_eq!(
+            tags(engine.list_images().await.unwrap()),
+            ["localhost:5000/app:1"]
+        );
+        engine
+            .remove_image("localhost:5000/app:1", keep)
+            .await
+            .unwrap();
+        assert!(engine.list_images().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn scripted_failures_last_until_recovered() {
+        let engine = FakeEngine::new();
+        engine.fail(Operation::ListImages, "connection refused");
+
+        let error = engine.list_images().await.unwrap_err();
+        assert_eq!(error.to_string(), "connection refused");
+        assert!(engine.list_volumes().await.is_ok());
+
+        engine.recover(Operation::ListImages);
+        assert!(engine.list_images().await.is_ok());
+        assert_eq!(
+            engine.calls(),
+            [
+                Operation::ListImages,
+                Operation::ListVolumes,
+                Operation::ListImages
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn latency_delays_answers() {
+        let engine = FakeEngine::new().with_latency(Duration::from_millis(50));
+        let started = std::time::Instant::now();
+        engine.probe().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::ssh::SshTarget;
+use super::tls::TlsOptions;
+
+#[cfg(not(windows))]
+pub const DEFAULT_DOCKER_HOST: &str = "unix:///var/run/docker.sock";
+#[cfg(windows)]
+pub const DEFAULT_DOCKER_HOST: &str = "npipe:////./pipe/docker_engine";
+
+/// A parsed Docker engine address, as accepted by `DOCKER_HOST`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DockerHost {
+    /// Path to a local unix socket, e.g. `/var/run/docker.sock`.
+    Unix(String),
+    /// Path to a Windows named pipe, e.g. `//./pipe/docker_engine`.
+    NamedPipe(String),
+    /// Plain HTTP endpoint in `host:port` form.
+    Tcp(String),
+    /// Remote engine reached through an SSH tunnel.
+    Ssh(SshTarget),
+}
+
+impl DockerHost {
+    /// Parses `unix://`, `npipe://`, `tcp://`, `http://` and `ssh://` addresses.
+    /// A bare absolute path is treated as a unix socket.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            bail!("Docker host is empty");
+        }
+
+        if let Some(path) = spec.strip_prefix("unix://") {
+            if path.is_empty() {
+                bail!("Missing socket path in {spec}");
+            }
+            return Ok(DockerHost::Unix(path.to_string()));
+        }
+
+        if let Some(path) = spec.strip_prefix("npipe://") {
+            if path.is_empty() {
+                bail!("Missing pipe path in {spec}");
+            }
+            return Ok(DockerHost::NamedPipe(path.to_string()));
+        }
+
+        for scheme in ["tcp://", "http://"] {
+            if let Some(addr) = spec.strip_prefix(scheme) {
+                return Ok(DockerHost::Tcp(parse_host_port(spec, addr)?));
+            }
+        }
+
+        if let Some(target) = spec.strip_prefix("ssh://") {
+            return Ok(DockerHost::Ssh(SshTarget::parse(target)?));
+        }
+
+        if spec.starts_with('/') {
+            return Ok(DockerHost::Unix(spec.to_string()));
+        }
+
+        match spec.split_once("://") {
+            Some((scheme, _)) => bail!("Unsupported Docker host scheme \"{scheme}\""),
+            None => {
+                bail!("Docker host must start with unix://, npipe://, tcp://, http:// or ssh://")
+            }
+        }
+    }
+}
+
+impl fmt::Display for DockerHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DockerHost::Unix(path) => write!(f, "unix://{path}"),
+            DockerHost::NamedPipe(path) => write!(f, "npipe://{path}"),
+            DockerHost::Tcp(addr) => write!(f, "tcp://{addr}"),
+            DockerHost::Ssh(target) => write!(f, "{target}"),
+        }
+    }
+}
+
+/// Everything needed to reach an engine: its address and, for TCP engines
+/// secured with mutual TLS, the client certificates.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub host: String,
+    #[serde(default)]
+    pub tls: Option<TlsOptions>,
+}
+
+impl Endpoint {
+    /// The endpoint the Docker CLI would use from `DOCKER_HOST`,
+    /// `DOCKER_TLS_VERIFY` and `DOCKER_CERT_PATH`.
+    pub fn from_env() -> Self {
+        Self {
+            host: default_docker_host(),
+            tls: TlsOptions::from_env(),
+        }
+    }
+}
+
+/// Returns `DOCKER_HOST` when set, otherwise the first local engine socket
+/// that exists, falling back to the platform's default socket.
+pub fn default_docker_host() -> String {
+    std::env::var("DOCKER_HOST")
+        .ok()
+        .filter(|host| !host.trim().is_empty())
+        .or_else(detect_local_socket)
+        .unwrap_or_else(|| DEFAULT_DOCKER_HOST.to_string())
+}
+
+/// Looks for the Docker socket, then the rootless and rootful Podman sockets,
+/// so Podman users get a working engine without setting `DOCKER_HOST`.
+fn detect_local_socket() -> Option<String> {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").ok();
+    local_socket_candidates(runtime_dir.as_deref())
+        .into_iter()
+        .find(|path| Path::new(path).exists())
+        .map(|path| format!("unix://{path}"))
+}
+
+fn local_socket_candidates(runtime_dir: Option<&str>) -> Vec<String> {
+    if cfg!(windows) {
+        return Vec::new();
+    }
+    let mut candidates = vec!["/var/run/docker.sock".to_string()];
+    if let Some(dir) = runtime_dir.filter(|dir| !dir.is_empty()) {
+        candidates.push(format!("{}/podman/podman.sock", dir.trim_end_matches('/')));
+    }
+    candidates.push("/run/podman/podman.sock".to_string());
+    candidates
+}
+
+fn parse_host_port(spec: &str, addr: &str) -> Result<String> {
+    let addr = addr.trim_end_matches('/');
+    let Some((host, port)) = addr.rsplit_once(':') else {
+        bail!("Missing port in {spec}");
+    };
+    if host.is_empty() {
+        bail!("Missing host name in {spec}");
+    }
+    if port.parse::<u16>().is_err() {
+        bail!("Invalid port \"{port}\" in {spec}");
+    }
+    Ok(addr.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_supported_schemes() {
+        assert_eq!(
+            DockerHost::parse("unix:///var/run/docker.sock").unwrap(),
+            DockerHost::Unix("/var/run/docker.sock".to_string())
+        );
+        assert_eq!(
+            DockerHost::parse("/run/user/1000/docker.sock").unwrap(),
+            DockerHost::Unix("/run/user/1000/docker.sock".to_string())
+        );
+        assert_eq!(
+            DockerHost::parse("tcp://build.internal:2375").unwrap(),
+            DockerHost::Tcp("build.internal:2375".to_string())
+        );
+        assert_eq!(
+            DockerHost::parse("http://10.0.0.5:2375/").unwrap(),
+            DockerHost::Tcp("10.0.0.5:2375".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_hosts() {
+        assert!(DockerHost::parse("").is_err());
+        assert!(DockerHost::parse("tcp://build.internal").is_err());
+        assert!(DockerHost::parse("tcp://:2375").is_err());
+        assert!(DockerHost::parse("tcp://host:docker").is_err());
+        assert!(DockerHost::parse("ftp://host:21").is_err());
+        assert!(DockerHost::parse("localhost").is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for spec in [
+            "unix:///var/run/docker.sock",
+            "tcp://build.internal:2375",
+            "ssh://deploy@lab-01:2222",
+        ] {
+            assert_eq!(DockerHost::parse(spec).unwrap().to_string(), spec);
+        }
+    }
+
+    #[test]
+    fn prefers_docker_then_rootless_podman() {
+        if cfg!(windows) {
+            return;
+        }
+        assert_eq!(
+            local_socket_candidates(Some("/run/user/1000/")),
+            [
+                "/var/run/docker.sock",
+                "/run/user/1000/podman/podman.sock",
+                "/run/podman/podman.sock",
+            ]
+        );
+        assert_eq!(local_socket_candidates(Some("")).len(), 2);
+    }
+}
@@ -0,0 +1,84 @@
+//! Image maintenance: removal options, tags and pruning.
+
+use anyhow::{Result, bail};
+
+use super::reference::ImageReference;
+
+/// Options of `docker rmi`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImageRemoveOptions {
+    /// Remove the image even when it has other tags or stopped containers
+    /// use it. Running containers still keep it.
+    pub force: bool,
+    /// Keep the untagged parent images the image was built on.
+    pub no_prune: bool,
+}
+
+/// Which images `docker image prune` removes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PruneScope {
+    /// Untagged images no container uses.
+    Dangling,
+    /// Every image no container uses, tagged or not.
+    Unused,
+}
+
+impl PruneScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PruneScope::Dangling => "Prune dangling",
+            PruneScope::Unused => "Prune unused",
+        }
+    }
+}
+
+/// What a prune removed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Ids the engine deleted: the images and the layers only they used.
+    pub deleted: Vec<String>,
+    pub reclaimed_bytes: u64,
+}
+
+impl PruneReport {
+    /// Adds the outcome of pruning another engine.
+    pub fn merge(&mut self, other: PruneReport) {
+        self.deleted.extend(other.deleted);
+        self.reclaimed_bytes += other.reclaimed_bytes;
+    }
+}
+
+/// Splits a new tag `repository[:tag]` into its parts, defaulting the tag to
+/// `latest`.
+pub fn split_tag(reference: &str) -> Result<(String, String)> {
+    let parsed = ImageReference::parse(reference)?;
+    if parsed.digest.is_some() {
+        bail!("A tag cannot name a digest: {}", reference.trim());
+    }
+    let tag = parsed.tag.clone().unwrap_or_else(|| "latest".to_string());
+    Ok((parsed.repository(), tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_new_tags() {
+        let split = |reference| split_tag(reference).unwrap();
+        assert_eq!(split("app"), ("app".to_string(), "latest".to_string()));
+        assert_eq!(split("app:1.0"), ("app".to_string(), "1.0".to_string()));
+        assert_eq!(
+            split("localhost:5000/app"),
+            ("localhost:5000/app".to_string(), "latest".to_string())
+        );
+        assert_eq!(
+            split("localhost:5000/app:2"),
+            ("localhost:5000/app".to_string(), "2".to_string())
+        );
+        assert!(split_tag("").is_err());
+        assert!(split_tag("app:").is_err());
+        assert!(split_tag("App:1").is_err());
+        assert!(split_tag("app@sha256:abc").is_err());
+    }
+}
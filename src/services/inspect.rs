@@ -0,0 +1,190 @@
+//! The full configuration of one container, as returned by inspect.
+
+use serde_json::Value;
+
+use super::docker::ContainerInfo;
+
+/// Shown in place of secret environment values until they are revealed.
+pub const SECRET_MASK: &str = "••••••••";
+
+/// Name fragments that mark an environment variable as a secret.
+const SECRET_MARKERS: [&str; 8] = [
+    "PASSWORD",
+    "PASSWD",
+    "SECRET",
+    "TOKEN",
+    "API_KEY",
+    "PRIVATE_KEY",
+    "ACCESS_KEY",
+    "CREDENTIAL",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainerDetails {
+    pub info: ContainerInfo,
+    pub created: Option<String>,
+    pub entrypoint: Vec<String>,
+    pub command: Vec<String>,
+    pub working_dir: Option<String>,
+    pub user: Option<String>,
+    pub env: Vec<EnvVar>,
+    pub mounts: Vec<MountInfo>,
+    pub networks: Vec<NetworkAttachment>,
+    /// Sorted by key.
+    pub labels: Vec<(String, String)>,
+    /// `no`, `always`, `unless-stopped` or `on-failure`.
+    pub restart_policy: String,
+    /// Retry limit of `on-failure`; zero means unlimited.
+    pub restart_max_retries: i64,
+    pub restart_count: i64,
+    pub limits: ResourceLimits,
+    /// Most recent health check runs, oldest first.
+    pub health_log: Vec<HealthCheck>,
+    /// The inspect response as the engine sent it.
+    pub raw: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+impl EnvVar {
+    /// Splits a `KEY=VALUE` entry; a bare `KEY` has an empty value.
+    pub fn parse(entry: &str) -> Self {
+        let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Whether the name suggests a password, token or key.
+    pub fn is_secret(&self) -> bool {
+        let key = self.key.to_ascii_uppercase();
+        SECRET_MARKERS.iter().any(|marker| key.contains(marker))
+    }
+
+    /// The value, masked when it is a secret and `reveal` is off.
+    pub fn display_value(&self, reveal: bool) -> &str {
+        if self.is_secret() && !reveal && !self.value.is_empty() {
+            SECRET_MASK
+        } else {
+            &self.value
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MountInfo {
+    /// `bind`, `volume`, `tmpfs`, ...
+    pub kind: String,
+    /// Host path, or volume name for volumes.
+    pub source: String,
+    pub destination: String,
+    pub read_only: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkAttachment {
+    pub network: String,
+    pub ip_address: Option<String>,
+    pub ipv6_address: Option<String>,
+    pub gateway: Option<String>,
+    pub mac_address: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+/// Host config limits; `None` means not limited.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResourceLimits {
+    pub cpus: Option<f64>,
+    pub cpu_shares: Option<i64>,
+    pub cpuset: Option<String>,
+    pub memory: Option<i64>,
+    /// Memory plus swap; -1 means unlimited swap.
+    pub memory_swap: Option<i64>,
+    pub pids: Option<i64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthCheck {
+    pub start: String,
+    pub exit_code: i64,
+    pub output: String,
+}
+
+impl ContainerDetails {
+    /// A container with nothing known beyond `info`.
+    #[cfg(test)]
+    pub fn new(info: ContainerInfo) -> Self {
+        Self {
+            info,
+            created: None,
+            entrypoint: Vec::new(),
+            command: Vec::new(),
+            working_dir: None,
+            user: None,
+            env: Vec::new(),
+            mounts: Vec::new(),
+            networks: Vec::new(),
+            labels: Vec::new(),
+            restart_policy: "no".to_string(),
+            restart_max_retries: 0,
+            restart_count: 0,
+            limits: ResourceLimits::default(),
+            health_log: Vec::new(),
+            raw: Value::Null,
+        }
+    }
+
+    /// Pretty-printed inspect JSON. Secret environment values are masked
+    /// unless `reveal` is set.
+    pub fn raw_json(&self, reveal: bool) -> String {
+        let mut raw = self.raw.clone();
+        if !reveal && let Some(Value::Array(env)) = raw.pointer_mut("/Config/Env") {
+            for entry in env.iter_mut() {
+                let Some(text) = entry.as_str() else {
+                    continue;
+                };
+                let var = EnvVar::parse(text);
+                if var.is_secret() && !var.value.is_empty() {
+                    *entry = Value::String(format!("{}={SECRET_MASK}", var.key));
+                }
+            }
+        }
+        serde_json::to_string_pretty(&raw).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::container;
+
+    #[test]
+    fn masks_secret_env_values() {
+        let password = EnvVar::parse("POSTGRES_PASSWORD=hunter2");
+        assert!(password.is_secret());
+        assert_eq!(password.display_value(false), SECRET_MASK);
+        assert_eq!(password.display_value(true), "hunter2");
+
+        let path = EnvVar::parse("PATH=/usr/bin");
+        assert!(!path.is_secret());
+        assert_eq!(path.display_value(false), "/usr/bin");
+        assert!(EnvVar::parse("github_token").is_secret());
+    }
+
+    #[test]
+    fn raw_json_masks_env_unless_revealed() {
+        let mut details = ContainerDetails::new(container("abc", "db"));
+        details.raw = serde_json::json!({
+            "Config": { "Env": ["DB_PASSWORD=hunter2", "PGDATA=/data"] }
+        });
+        let masked = details.raw_json(false);
+        assert!(!masked.contains("hunter2"));
+        assert!(masked.contains("PGDATA=/data"));
+        assert!(details.raw_json(true).contains("DB_PASSWORD=hunter2"));
+    }
+}
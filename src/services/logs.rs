@@ -0,0 +1,35 @@
+//! Container log requests and the chunks streamed back by the engine.
+
+use anyhow::Result;
+use futures_util::stream::BoxStream;
+
+/// Which output a log chunk was written to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// One piece of output as framed by the engine. It usually holds a single
+/// line, but may hold several or end mid-line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogChunk {
+    pub stream: LogStream,
+    pub text: String,
+}
+
+/// Options of `docker logs`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// Keep the stream open and receive new output as it is written.
+    pub follow: bool,
+    /// Only the last `n` lines of existing output.
+    pub tail: Option<u32>,
+    /// Unix timestamps bounding the output.
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// Prefix every line with its RFC 3339 timestamp.
+    pub timestamps: bool,
+}
+
+pub type LogChunks = BoxStream<'static, Result<LogChunk>>;
@@ -1,3 +1,45 @@
+mod actions;
+mod contexts;
 mod docker;
+mod engine;
+mod error;
+mod events;
+mod exec;
+#[cfg(test)]
+mod fake;
+mod host;
+mod images;
+mod inspect;
+mod logs;
+mod pull;
+mod reference;
+mod run;
+mod ssh;
+#[cfg(all(test, unix))]
+mod stand_in;
+mod stats;
+mod tls;
 
-pub use docker::{ContainerInfo, ContainerState, DockerService, ImageInfo, VolumeInfo};
+pub use actions::{ContainerAction, DEFAULT_STOP_TIMEOUT_SECS, KillSignal, RemoveOptions};
+pub use contexts::DockerContexts;
+pub use docker::{
+    ContainerInfo, ContainerState, DockerService, EngineReport, ImageInfo, VolumeInfo,
+};
+pub use engine::{ContainerEngine, SharedEngine};
+pub use error::{EngineError, describe_error};
+pub use events::EngineEvent;
+pub use exec::ExecOptions;
+#[cfg(test)]
+pub use fake::{FakeEngine, Operation, container};
+pub use host::{DockerHost, Endpoint, default_docker_host};
+pub use images::{ImageRemoveOptions, PruneReport, PruneScope, split_tag};
+pub use inspect::ContainerDetails;
+pub use logs::{LogChunk, LogOptions, LogStream};
+pub use pull::{PullOptions, PullState};
+#[cfg(test)]
+pub use reference::ImageReference;
+pub use run::{
+    PortMapping, RestartPolicy, RunSpec, parse_cpus, parse_memory, parse_mount, split_command,
+};
+pub use stats::ContainerStats;
+pub use tls::{TlsOptions, install_crypto_provider};
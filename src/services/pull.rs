@@ -0,0 +1,212 @@
+//! Image pulls and the progress the engine streams while one runs.
+
+use anyhow::Result;
+use futures_util::stream::BoxStream;
+
+use super::reference::ImageReference;
+
+/// Options of `docker pull`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PullOptions {
+    /// Image reference, e.g. `nginx:1.27` or `ghcr.io/org/app@sha256:…`.
+    pub reference: String,
+    /// `os/arch[/variant]` to pull, or the engine's own platform when `None`.
+    pub platform: Option<String>,
+}
+
+/// One progress message of a pull, as framed by the engine.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PullProgress {
+    /// Layer the message is about. Messages about the whole image carry the
+    /// tag, or nothing.
+    pub id: Option<String>,
+    pub status: String,
+    /// Bytes done and expected in the layer's current phase.
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+}
+
+/// Progress of a pull until it finishes. Dropping the stream cancels it.
+pub type PullStream = BoxStream<'static, Result<PullProgress>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerPhase {
+    Waiting,
+    Downloading,
+    Verifying,
+    Downloaded,
+    Extracting,
+    Complete,
+    /// Already in the local cache, so nothing is fetched.
+    Exists,
+}
+
+impl LayerPhase {
+    fn parse(status: &str) -> Option<Self> {
+        Some(match status {
+            "Pulling fs layer" | "Waiting" => LayerPhase::Waiting,
+            "Downloading" => LayerPhase::Downloading,
+            "Verifying Checksum" => LayerPhase::Verifying,
+            "Download complete" => LayerPhase::Downloaded,
+            "Extracting" => LayerPhase::Extracting,
+            "Pull complete" => LayerPhase::Complete,
+            "Already exists" => LayerPhase::Exists,
+            _ => return None,
+        })
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LayerPhase::Waiting => "Waiting",
+            LayerPhase::Downloading => "Downloading",
+            LayerPhase::Verifying => "Verifying",
+            LayerPhase::Downloaded => "Downloaded",
+            LayerPhase::Extracting => "Extracting",
+            LayerPhase::Complete => "Complete",
+            LayerPhase::Exists => "Already exists",
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        matches!(self, LayerPhase::Complete | LayerPhase::Exists)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayerProgress {
+    pub id: String,
+    pub phase: LayerPhase,
+    pub current: u64,
+    pub total: u64,
+}
+
+impl LayerProgress {
+    /// Share of the current phase done, from 0 to 100. Phases without byte
+    /// counts are empty until they finish.
+    pub fn percent(&self) -> u8 {
+        match self.phase {
+            LayerPhase::Waiting => 0,
+            LayerPhase::Downloading | LayerPhase::Extracting if self.total > 0 => {
+                (self.current.min(self.total) * 100 / self.total) as u8
+            }
+            LayerPhase::Downloading | LayerPhase::Extracting => 0,
+            _ => 100,
+        }
+    }
+}
+
+/// Progress messages of one pull folded into a row per layer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PullState {
+    /// Layers in the order the engine first mentioned them.
+    pub layers: Vec<LayerProgress>,
+    /// The latest message about the whole image, e.g. `Digest: sha256:…`.
+    pub status: Option<String>,
+}
+
+impl PullState {
+    pub fn apply(&mut self, progress: PullProgress) {
+        let phase = LayerPhase::parse(&progress.status);
+        let (Some(id), Some(phase)) = (progress.id, phase) else {
+            self.status = Some(progress.status);
+            return;
+        };
+        let index = match self.layers.iter().position(|layer| layer.id == id) {
+            Some(index) => index,
+            None => {
+                self.layers.push(LayerProgress {
+                    id,
+                    phase,
+                    current: 0,
+                    total: 0,
+                });
+                self.layers.len() - 1
+            }
+        };
+        let layer = &mut self.layers[index];
+        if layer.phase != phase {
+            layer.current = 0;
+            layer.total = 0;
+        }
+        layer.phase = phase;
+        layer.current = progress.current.unwrap_or(layer.current);
+        layer.total = progress.total.unwrap_or(layer.total);
+    }
+
+    pub fn layers_done(&self) -> usize {
+        self.layers
+            .iter()
+            .filter(|layer| layer.phase.is_done())
+            .count()
+    }
+}
+
+/// `reference` with the `latest` tag added when it names neither a tag nor a
+/// digest, as the engine would otherwise pull every tag of the repository.
+pub fn with_default_tag(reference: &str) -> String {
+    let reference = reference.trim();
+    match ImageReference::parse(reference) {
+        Ok(parsed) if parsed.tag.is_none() && parsed.digest.is_none() => {
+            format!("{reference}:latest")
+        }
+        _ => reference.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(id: &str, status: &str, current: Option<u64>, total: Option<u64>) -> PullProgress {
+        PullProgress {
+            id: (!id.is_empty()).then(|| id.to_string()),
+            status: status.to_string(),
+            current,
+            total,
+        }
+    }
+
+    #[test]
+    fn folds_messages_per_layer() {
+        let mut pull = PullState::default();
+        pull.apply(progress("1.27", "Pulling from library/nginx", None, None));
+        pull.apply(progress("a1", "Already exists", None, None));
+        pull.apply(progress("b2", "Pulling fs layer", None, None));
+        pull.apply(progress("b2", "Downloading", Some(250), Some(1000)));
+        assert_eq!(pull.status.as_deref(), Some("Pulling from library/nginx"));
+        assert_eq!(pull.layers.len(), 2);
+        assert_eq!(pull.layers[1].phase, LayerPhase::Downloading);
+        assert_eq!(pull.layers[1].percent(), 25);
+        assert_eq!(pull.layers_done(), 1);
+
+        pull.apply(progress("b2", "Download complete", None, None));
+        pull.apply(progress("b2", "Extracting", Some(100), Some(400)));
+        assert_eq!(pull.layers[1].percent(), 25);
+        assert_eq!(pull.layers[1].total, 400);
+
+        pull.apply(progress("b2", "Pull complete", None, None));
+        pull.apply(progress(
+            "",
+            "Status: Downloaded newer image for nginx:1.27",
+            None,
+            None,
+        ));
+        assert_eq!(pull.layers_done(), 2);
+        assert_eq!(pull.layers[1].percent(), 100);
+        assert_eq!(
+            pull.status.as_deref(),
+            Some("Status: Downloaded newer image for nginx:1.27")
+        );
+    }
+
+    #[test]
+    fn adds_the_latest_tag_when_missing() {
+        assert_eq!(with_default_tag("nginx"), "nginx:latest");
+        assert_eq!(with_default_tag("nginx:1.27"), "nginx:1.27");
+        assert_eq!(
+            with_default_tag("localhost:5000/app"),
+            "localhost:5000/app:latest"
+        );
+        assert_eq!(with_default_tag("alpine@sha256:abc"), "alpine@sha256:abc");
+    }
+}
@@ -0,0 +1,168 @@
+//! Image references such as `localhost:5000/team/app:1.2@sha256:…`.
+
+use std::fmt;
+
+use anyhow::{Result, bail};
+
+/// An image reference split into its parts. Parts the reference leaves out
+/// stay `None`, so `nginx` has neither a registry nor a tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageReference {
+    /// Host, with its port, when the reference names one, e.g. `ghcr.io`.
+    pub registry: Option<String>,
+    /// Path between the registry and the name, e.g. `library` or `org/team`.
+    pub namespace: Option<String>,
+    pub name: String,
+    pub tag: Option<String>,
+    /// `algorithm:hex`, e.g. `sha256:…`.
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    pub fn parse(reference: &str) -> Result<Self> {
+        let reference = reference.trim();
+        if reference.is_empty() {
+            bail!("Enter an image, e.g. registry.local/app:1.0");
+        }
+        let invalid = || anyhow::anyhow!("\"{reference}\" is not a valid image reference");
+
+        let (rest, digest) = match reference.split_once('@') {
+            Some((rest, digest)) => {
+                let valid = digest.split_once(':').is_some_and(|(algorithm, hex)| {
+                    !algorithm.is_empty()
+                        && !hex.is_empty()
+                        && hex.chars().all(|c| c.is_ascii_hexdigit())
+                });
+                if !valid {
+                    return Err(invalid());
+                }
+                (rest, Some(digest.to_string()))
+            }
+            None => (reference, None),
+        };
+
+        // A first component with a dot or a port, or `localhost`, is a host;
+        // anything else is part of the path, as in `library/nginx`.
+        let (registry, path) = match rest.split_once('/') {
+            Some((first, path)) if first.contains(['.', ':']) || first == "localhost" => {
+                (Some(first.to_string()), path)
+            }
+            _ => (None, rest),
+        };
+        if registry.as_deref().is_some_and(|host| {
+            !host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'))
+        }) {
+            return Err(invalid());
+        }
+
+        let (path, tag) = match path.rsplit_once(':') {
+            Some((path, tag)) => {
+                let valid = tag.len() <= 128
+                    && tag.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+                    && tag
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+                if !valid {
+                    return Err(invalid());
+                }
+                (path, Some(tag.to_string()))
+            }
+            None => (path, None),
+        };
+
+        for component in path.split('/') {
+            let valid = component.starts_with(|c: char| c.is_ascii_alphanumeric())
+                && component
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-".contains(c));
+            if component.chars().any(|c| c.is_ascii_uppercase()) {
+                bail!("Repository names must be lowercase: {path}");
+            }
+            if !valid {
+                return Err(invalid());
+            }
+        }
+        let (namespace, name) = match path.rsplit_once('/') {
+            Some((namespace, name)) => (Some(namespace.to_string()), name.to_string()),
+            None => (None, path.to_string()),
+        };
+
+        Ok(Self {
+            registry,
+            namespace,
+            name,
+            tag,
+            digest,
+        })
+    }
+
+    /// The reference without its tag and digest, as the engine names
+    /// repositories: `localhost:5000/team/app`.
+    pub fn repository(&self) -> String {
+        [self.registry.as_deref(), self.namespace.as_deref()]
+            .into_iter()
+            .flatten()
+            .chain([self.name.as_str()])
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl fmt::Display for ImageReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.repository())?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{tag}")?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{digest}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_registry_namespace_name_tag_and_digest() {
+        let parsed = ImageReference::parse("localhost:5000/team/app:1.2").unwrap();
+        assert_eq!(parsed.registry.as_deref(), Some("localhost:5000"));
+        assert_eq!(parsed.namespace.as_deref(), Some("team"));
+        assert_eq!(parsed.name, "app");
+        assert_eq!(parsed.tag.as_deref(), Some("1.2"));
+        assert_eq!(parsed.repository(), "localhost:5000/team/app");
+
+        let parsed = ImageReference::parse("library/nginx").unwrap();
+        assert_eq!(parsed.registry, None);
+        assert_eq!(parsed.namespace.as_deref(), Some("library"));
+        assert_eq!(parsed.tag, None);
+
+        let parsed = ImageReference::parse("ghcr.io/org/app@sha256:0a1b").unwrap();
+        assert_eq!(parsed.registry.as_deref(), Some("ghcr.io"));
+        assert_eq!(parsed.digest.as_deref(), Some("sha256:0a1b"));
+        assert_eq!(parsed.to_string(), "ghcr.io/org/app@sha256:0a1b");
+
+        let parsed = ImageReference::parse("localhost:5000/app").unwrap();
+        assert_eq!(parsed.registry.as_deref(), Some("localhost:5000"));
+        assert_eq!(parsed.tag, None);
+    }
+
+    #[test]
+    fn rejects_malformed_references() {
+        for reference in [
+            "",
+            "app:",
+            "App:1",
+            "app@sha256:xyz",
+            "app:-1",
+            "a b",
+            "app//x",
+        ] {
+            assert!(ImageReference::parse(reference).is_err(), "{reference}");
+        }
+    }
+}
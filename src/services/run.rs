@@ -0,0 +1,419 @@
+//! Creating and starting a new container, as `docker run -d` does, and the
+//! parsing of the flags it takes.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use super::docker::ContainerInfo;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    /// `tcp` or `udp`; other protocols, such as `sctp`, are not supported.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "tcp" => Some(Protocol::Tcp),
+            "udp" => Some(Protocol::Udp),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        }
+    }
+}
+
+/// A `-p [host_ip:]host_port:container_port[/protocol]` mapping. IPv6 host
+/// addresses are written in brackets, as in `[::1]:8080:80`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortMapping {
+    pub host_ip: Option<String>,
+    pub host_port: u16,
+    pub container_port: u16,
+    pub protocol: Protocol,
+}
+
+impl PortMapping {
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (ports, protocol) = match text.rsplit_once('/') {
+            Some((ports, name)) => match Protocol::parse(name) {
+                Some(protocol) => (ports, protocol),
+                None => bail!("Unknown protocol \"{name}\" in port mapping \"{text}\""),
+            },
+            None => (text, Protocol::Tcp),
+        };
+        let (host_ip, ports) = match ports.strip_prefix('[') {
+            Some(rest) => match rest.split_once("]:") {
+                Some((ip, ports)) => (Some(ip.to_string()), ports),
+                None => bail!("\"{text}\" is not a [IP]:HOST:CONTAINER port mapping"),
+            },
+            None => (None, ports),
+        };
+        let parts: Vec<&str> = ports.split(':').collect();
+        let (host_ip, host_port, container_port) = match (host_ip, &parts[..]) {
+            (host_ip, [host, container]) => (host_ip, *host, *container),
+            (None, [ip, host, container]) => (Some(ip.to_string()), *host, *container),
+            _ => bail!("\"{text}\" is not a HOST:CONTAINER port mapping"),
+        };
+        let port = |value: &str| {
+            value
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port != 0)
+                .ok_or_else(|| anyhow!("\"{value}\" is not a valid port in \"{text}\""))
+        };
+        Ok(Self {
+            host_ip,
+            host_port: port(host_port)?,
+            container_port: port(container_port)?,
+            protocol,
+        })
+    }
+
+    /// Whether both mappings claim the same host port: same port and
+    /// protocol, on the same address or with either on every address.
+    pub fn clashes_with(&self, other: &PortMapping) -> bool {
+        let any_address = |ip: &Option<String>| {
+            ip.as_deref()
+                .is_none_or(|ip| matches!(ip, "" | "0.0.0.0" | "::"))
+        };
+        self.host_port == other.host_port
+            && self.protocol == other.protocol
+            && (any_address(&self.host_ip)
+                || any_address(&other.host_ip)
+                || self.host_ip == other.host_ip)
+    }
+}
+
+/// A `-v source:target[:ro]` mount. Sources that look like paths are bind
+/// mounts; anything else names a volume, which is created if missing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MountSpec {
+    pub source: String,
+    pub target: String,
+    pub read_only: bool,
+}
+
+impl MountSpec {
+    /// The `-v` form of the mount, as used in `HostConfig.Binds`.
+    pub fn bind(&self) -> String {
+        let mode = if self.read_only { ":ro" } else { "" };
+        format!("{}:{}{mode}", self.source, self.target)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    #[default]
+    No,
+    Always,
+    UnlessStopped,
+    /// Restart after a non-zero exit, at most `max_retries` times (zero means
+    /// no limit).
+    OnFailure {
+        max_retries: u32,
+    },
+}
+
+impl RestartPolicy {
+    pub const ALL: [RestartPolicy; 4] = [
+        RestartPolicy::No,
+        RestartPolicy::Always,
+        RestartPolicy::UnlessStopped,
+        RestartPolicy::OnFailure { max_retries: 0 },
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RestartPolicy::No => "no",
+            RestartPolicy::Always => "always",
+            RestartPolicy::UnlessStopped => "unless-stopped",
+            RestartPolicy::OnFailure { .. } => "on-failure",
+        }
+    }
+}
+
+/// Everything needed to create and start a container.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunSpec {
+    pub image: String,
+    pub name: Option<String>,
+    /// Replaces the image's command when not empty.
+    pub command: Vec<String>,
+    pub ports: Vec<PortMapping>,
+    pub mounts: Vec<MountSpec>,
+    /// `KEY=VALUE` entries.
+    pub env: Vec<String>,
+    /// The first network is joined on creation, the rest right after.
+    pub networks: Vec<String>,
+    pub restart: RestartPolicy,
+    pub cpus: Option<f64>,
+    /// Bytes.
+    pub memory: Option<i64>,
+    pub labels: Vec<(String, String)>,
+}
+
+impl RunSpec {
+    /// Problems that would make the engine reject the request, or that clash
+    /// with `existing` containers on the same engine. Empty when the spec can
+    /// be submitted.
+    pub fn conflicts(&self, existing: &[ContainerInfo]) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.image.trim().is_empty() {
+            problems.push("Choose an image to run".to_string());
+        }
+        if let Some(name) = &self.name {
+            if !is_valid_name(name) {
+                problems.push(format!(
+                    "\"{name}\" is not a valid name: use letters, digits, '_', '.' and '-', starting with a letter or digit"
+                ));
+            } else if existing.iter().any(|c| &c.name == name) {
+                problems.push(format!(
+                    "The name \"{name}\" is already used by another container"
+                ));
+            }
+        }
+
+        for (index, port) in self.ports.iter().enumerate() {
+            let label = format!("Host port {}/{}", port.host_port, port.protocol.name());
+            if self.ports[..index]
+                .iter()
+                .any(|other| other.clashes_with(port))
+            {
+                problems.push(format!("{label} is mapped more than once"));
+            }
+            if let Some(owner) = existing
+                .iter()
+                .find(|c| c.published.iter().any(|other| other.clashes_with(port)))
+            {
+                problems.push(format!("{label} is already published by {}", owner.name));
+            }
+        }
+
+        let mut targets = HashSet::new();
+        for mount in &self.mounts {
+            if !mount.target.starts_with('/') {
+                problems.push(format!(
+                    "Mount target \"{}\" must be an absolute path",
+                    mount.target
+                ));
+            } else if !targets.insert(mount.target.as_str()) {
+                problems.push(format!("{} is mounted more than once", mount.target));
+            }
+        }
+        problems
+    }
+}
+
+/// Parses a `-v source:target[:ro|rw]` mount.
+pub fn parse_mount(text: &str) -> Result<MountSpec> {
+    let text = text.trim();
+    let (rest, read_only) = match text.rsplit_once(':') {
+        Some((rest, "ro")) => (rest, true),
+        Some((rest, "rw")) => (rest, false),
+        _ => (text, false),
+    };
+    match rest.split_once(':') {
+        Some((source, target)) if !source.is_empty() && !target.is_empty() => Ok(MountSpec {
+            source: source.to_string(),
+            target: target.to_string(),
+            read_only,
+        }),
+        _ => bail!("\"{text}\" is not a SOURCE:TARGET mount"),
+    }
+}
+
+/// Parses a `--memory` value such as `512m`, `2g` or a plain byte count.
+pub fn parse_memory(text: &str) -> Result<Option<i64>> {
+    let text = text.trim().to_ascii_lowercase();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let text = text.strip_suffix('b').unwrap_or(&text);
+    let (number, scale) = match text.chars().last() {
+        Some('k') => (&text[..text.len() - 1], 1 << 10),
+        Some('m') => (&text[..text.len() - 1], 1 << 20),
+        Some('g') => (&text[..text.len() - 1], 1 << 30),
+        _ => (text, 1),
+    };
+    let value: f64 = number
+        .parse()
+        .ok()
+        .filter(|value: &f64| *value > 0.0)
+        .with_context(|| format!("\"{text}\" is not a memory size such as 512m or 2g"))?;
+    Ok(Some((value * scale as f64) as i64))
+}
+
+/// Parses a `--cpus` value such as `1.5`.
+pub fn parse_cpus(text: &str) -> Result<Option<f64>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse::<f64>()
+        .ok()
+        .filter(|cpus| *cpus > 0.0)
+        .map(Some)
+        .with_context(|| format!("\"{text}\" is not a number of CPUs such as 0.5 or 2"))
+}
+
+/// Splits a command line into arguments, honouring single and double quotes.
+pub fn split_command(text: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        bail!("Unclosed quote in command");
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// Container names the engine accepts: `[a-zA-Z0-9][a-zA-Z0-9_.-]*`.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::container;
+
+    #[test]
+    fn parses_port_mappings() {
+        let port = PortMapping::parse("127.0.0.1:8080:80/udp").unwrap();
+        assert_eq!(port.host_ip.as_deref(), Some("127.0.0.1"));
+        assert_eq!((port.host_port, port.container_port), (8080, 80));
+        assert_eq!(port.protocol, Protocol::Udp);
+        assert_eq!(PortMapping::parse("80:80").unwrap().protocol, Protocol::Tcp);
+        assert!(PortMapping::parse("80").is_err());
+        assert!(PortMapping::parse("0:80").is_err());
+        assert!(PortMapping::parse("80:80/sctp").is_err());
+
+        let port = PortMapping::parse("[::1]:8080:80").unwrap();
+        assert_eq!(port.host_ip.as_deref(), Some("::1"));
+        assert_eq!((port.host_port, port.container_port), (8080, 80));
+        assert!(PortMapping::parse("[::1]:1:8080:80").is_err());
+        assert!(PortMapping::parse("[::1:8080:80").is_err());
+    }
+
+    #[test]
+    fn parses_mounts_and_limits() {
+        let data = parse_mount("pgdata:/var/lib/postgresql/data").unwrap();
+        assert!(!data.read_only);
+        let config = parse_mount("./nginx.conf:/etc/nginx/nginx.conf:ro").unwrap();
+        assert!(config.read_only);
+        assert_eq!(config.bind(), "./nginx.conf:/etc/nginx/nginx.conf:ro");
+        assert!(parse_mount("/only-a-path").is_err());
+
+        assert_eq!(parse_memory("512m").unwrap(), Some(512 << 20));
+        assert_eq!(parse_memory("1.5GB").unwrap(), Some(3 << 29));
+        assert_eq!(parse_memory("").unwrap(), None);
+        assert!(parse_memory("lots").is_err());
+        assert_eq!(parse_cpus("0.5").unwrap(), Some(0.5));
+        assert!(parse_cpus("-1").is_err());
+    }
+
+    #[test]
+    fn splits_quoted_commands() {
+        assert_eq!(
+            split_command(r#"sh -c "echo 'hi there'" ''"#).unwrap(),
+            ["sh", "-c", "echo 'hi there'", ""]
+        );
+        assert!(split_command("echo \"oops").is_err());
+    }
+
+    #[test]
+    fn finds_conflicts_with_existing_containers() {
+        let mut web = container("abc123", "web");
+        web.published = vec![
+            PortMapping::parse("8080:80").unwrap(),
+            PortMapping::parse("127.0.0.1:9000:9000").unwrap(),
+        ];
+        let spec = RunSpec {
+            image: "nginx:latest".to_string(),
+            name: Some("web".to_string()),
+            ports: vec![
+                PortMapping::parse("8080:80").unwrap(),
+                PortMapping::parse("9090:90").unwrap(),
+                PortMapping::parse("9090:91").unwrap(),
+            ],
+            mounts: vec![parse_mount("data:relative").unwrap()],
+            ..Default::default()
+        };
+        let problems = spec.conflicts(&[web]);
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(problems[0].contains("already used"));
+        assert!(problems[1].contains("8080/tcp is already published by web"));
+        assert!(problems[2].contains("9090/tcp is mapped more than once"));
+        assert!(problems[3].contains("absolute path"));
+
+        let spec = RunSpec {
+            image: "nginx:latest".to_string(),
+            name: Some("-bad".to_string()),
+            ..Default::default()
+        };
+        assert!(spec.conflicts(&[])[0].contains("not a valid name"));
+    }
+
+    #[test]
+    fn ports_clash_only_on_the_same_protocol_and_address() {
+        let mut dns = container("abc123", "dns");
+        dns.published = vec![
+            PortMapping::parse("53:53").unwrap(),
+            PortMapping::parse("127.0.0.1:8080:80").unwrap(),
+        ];
+        let run = |ports: &[&str]| RunSpec {
+            image: "nginx:latest".to_string(),
+            ports: ports
+                .iter()
+                .map(|p| PortMapping::parse(p).unwrap())
+                .collect(),
+            ..Default::default()
+        };
+        let existing = [dns];
+        assert!(run(&["53:53/udp"]).conflicts(&existing).is_empty());
+        assert!(run(&["10.0.0.2:8080:80"]).conflicts(&existing).is_empty());
+        assert_eq!(run(&["8080:80"]).conflicts(&existing).len(), 1);
+        assert_eq!(run(&["0.0.0.0:53:53"]).conflicts(&existing).len(), 1);
+        assert!(
+            run(&["127.0.0.1:9090:80", "127.0.0.2:9090:80"])
+                .conflicts(&[])
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,472 @@
+//! Reaches remote engines over `ssh://` by forwarding the remote Docker socket
+//! to a private local socket with the system OpenSSH client, which takes care
+//! of keys, the agent and `known_hosts`.
+
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, Command};
+use tokio::task::JoinHandle;
+
+const DEFAULT_REMOTE_SOCKET: &str = "/var/run/docker.sock";
+const TUNNEL_TIMEOUT: Duration = Duration::from_secs(15);
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// The `[user@]host[:port][/socket]` part of an `ssh://` Docker host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SshTarget {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Path of the Docker socket on the remote machine.
+    pub socket: String,
+}
+
+impl SshTarget {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (authority, socket) = match spec.find('/') {
+            Some(index) => (&spec[..index], spec[index..].to_string()),
+            None => (spec, DEFAULT_REMOTE_SOCKET.to_string()),
+        };
+
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, rest)) if !user.is_empty() => (Some(user.to_string()), rest),
+            Some(_) => bail!("Empty user name in ssh://{spec}"),
+            None => (None, authority),
+        };
+
+        // IPv6 addresses are bracketed, as in `[::1]:22`, to set them apart
+        // from the port
+        let (host, port) = match host_port.strip_prefix('[') {
+            Some(bracketed) => match bracketed.split_once(']') {
+                Some((host, "")) => (host, None),
+                Some((host, rest)) => match rest.strip_prefix(':') {
+                    Some(port) => (host, Some(port)),
+                    None => bail!("Unexpected \"{rest}\" after the address in ssh://{spec}"),
+                },
+                None => bail!("Missing ']' in ssh://{spec}"),
+            },
+            None => match host_port.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_port, None),
+            },
+        };
+        let port = port
+            .map(|port| {
+                port.parse::<u16>()
+                    .map_err(|_| anyhow::anyhow!("Invalid port \"{port}\" in ssh://{spec}"))
+            })
+            .transpose()?;
+
+        if host.is_empty() {
+            bail!("Missing host name in ssh://{spec}");
+        }
+        // Anything starting with '-' would be read by ssh as an option
+        if host.starts_with('-') || user.as_deref().is_some_and(|u| u.starts_with('-')) {
+            bail!("Invalid SSH destination in ssh://{spec}");
+        }
+
+        Ok(Self {
+            user,
+            host: host.to_string(),
+            port,
+            socket,
+        })
+    }
+
+    fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{user}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// Arguments for an `ssh` process that only forwards `local` to the remote
+    /// Docker socket. `BatchMode` makes it fail instead of prompting, since
+    /// there is no terminal to prompt on.
+    fn forward_args(&self, local: &Path) -> Vec<String> {
+        let mut args = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "ExitOnForwardFailure=yes".to_string(),
+            "-o".to_string(),
+            "StreamLocalBindUnlink=yes".to_string(),
+            "-N".to_string(),
+            "-T".to_string(),
+            "-L".to_string(),
+            format!("{}:{}", local.display(), self.socket),
+        ];
+        if let Some(port) = self.port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        args.push("--".to_string());
+        args.push(self.destination());
+        args
+    }
+}
+
+impl fmt::Display for SshTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ssh://")?;
+        if let Some(user) = &self.user {
+            write!(f, "{user}@")?;
+        }
+        if self.host.contains(':') {
+            write!(f, "[{}]", self.host)?;
+        } else {
+            write!(f, "{}", self.host)?;
+        }
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+        if self.socket != DEFAULT_REMOTE_SOCKET {
+            write!(f, "{}", self.socket)?;
+        }
+        Ok(())
+    }
+}
+
+/// A running `ssh -L` forward. If ssh exits, for instance after the network
+/// dropped, it is started again on the same local socket, so clients keep
+/// working once the host is reachable. The process is killed and the local
+/// socket removed when the tunnel is dropped.
+pub struct SshTunnel {
+    supervisor: JoinHandle<()>,
+    dir: PathBuf,
+    socket: PathBuf,
+}
+
+impl SshTunnel {
+    pub async fn open(target: &SshTarget) -> Result<Self> {
+        Self::open_with("ssh", target).await
+    }
+
+    /// Runs `program` as the OpenSSH client.
+    async fn open_with(program: impl AsRef<OsStr>, target: &SshTarget) -> Result<Self> {
+        let program = program.as_ref().to_os_string();
+        let dir = private_temp_dir()?;
+        let socket = dir.join("docker.sock");
+
+        let child = match start_forward(&program, target, &socket).await {
+            Ok(child) => child,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&dir);
+                return Err(e);
+            }
+        };
+        let supervisor = tokio::spawn(supervise(program, target.clone(), socket.clone(), child));
+        Ok(Self {
+            supervisor,
+            dir,
+            socket,
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        // Dropping the supervisor's child kills ssh
+        self.supervisor.abort();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Starts ssh and waits for it to create `socket`. Its stderr is read until
+/// then for the error message, and drained afterwards so ssh never blocks on
+/// a full pipe.
+async fn start_forward(program: &OsStr, target: &SshTarget, socket: &Path) -> Result<Child> {
+    let mut child = Command::new(program)
+        .args(target.forward_args(socket))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start ssh; is the OpenSSH client installed?")?;
+
+    let started = Instant::now();
+    loop {
+        if socket.exists() {
+            if let Some(mut pipe) = child.stderr.take() {
+                tokio::spawn(async move {
+                    let _ = tokio::io::copy(&mut pipe, &mut tokio::io::sink()).await;
+                });
+            }
+            return Ok(child);
+        }
+
+        if let Some(status) = child.try_wait()? {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                pipe.read_to_string(&mut stderr).await.ok();
+            }
+            bail!(
+                "SSH connection to {target} failed ({status}): {}",
+                stderr.trim()
+            );
+        }
+
+        if started.elapsed() > TUNNEL_TIMEOUT {
+            bail!("Timed out opening SSH tunnel to {target}");
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Restarts the forward whenever ssh exits, waiting longer between attempts
+/// while the host stays unreachable.
+async fn supervise(program: OsString, target: SshTarget, socket: PathBuf, mut child: Child) {
+    loop {
+        let _ = child.wait().await;
+        let mut delay = RECONNECT_DELAY;
+        child = loop {
+            tokio::time::sleep(delay).await;
+            // A socket left behind would pass for the new forward being ready
+            let _ = std::fs::remove_file(&socket);
+            match start_forward(&program, &target, &socket).await {
+                Ok(child) => break child,
+                Err(_) => delay = (delay * 2).min(MAX_RECONNECT_DELAY),
+            }
+        };
+    }
+}
+
+/// Creates a directory only the current user can enter, so other local users
+/// cannot reach the forwarded engine through the socket.
+#[cfg(unix)]
+fn private_temp_dir() -> Result<PathBuf> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    let dir = std::env::temp_dir().join(format!("doctainr-ssh-{}", uuid::Uuid::new_v4()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(dir)
+}
+
+#[cfg(not(unix))]
+fn private_temp_dir() -> Result<PathBuf> {
+    bail!("SSH Docker hosts are only supported on Unix-like systems")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_user_host_port_and_socket() {
+        let target = SshTarget::parse("deploy@lab-01:2222/run/user/1000/docker.sock").unwrap();
+        assert_eq!(target.user.as_deref(), Some("deploy"));
+        assert_eq!(target.host, "lab-01");
+        assert_eq!(target.port, Some(2222));
+        assert_eq!(target.socket, "/run/user/1000/docker.sock");
+
+        let target = SshTarget::parse("lab-02").unwrap();
+        assert_eq!(target.user, None);
+        assert_eq!(target.port, None);
+        assert_eq!(target.socket, DEFAULT_REMOTE_SOCKET);
+        assert_eq!(target.to_string(), "ssh://lab-02");
+    }
+
+    #[test]
+    fn parses_bracketed_ipv6_hosts() {
+        let target = SshTarget::parse("deploy@[::1]:22").unwrap();
+        assert_eq!(target.host, "::1");
+        assert_eq!(target.port, Some(22));
+        assert_eq!(target.to_string(), "ssh://deploy@[::1]:22");
+
+        let target = SshTarget::parse("[fe80::1]/run/docker.sock").unwrap();
+        assert_eq!(target.host, "fe80::1");
+        assert_eq!(target.port, None);
+        assert_eq!(target.socket, "/run/docker.sock");
+
+        assert!(SshTarget::parse("[::1").is_err());
+        assert!(SshTarget::parse("[::1]22").is_err());
+    }
+
+    #[test]
+    fn rejects_option_like_destinations() {
+        assert!(SshTarget::parse("-oProxyCommand=evil").is_err());
+        assert!(SshTarget::parse("@lab").is_err());
+        assert!(SshTarget::parse("lab:ssh").is_err());
+    }
+
+    /// Writes an executable `ssh` stand-in running `body` to a fresh
+    /// directory.
+    #[cfg(unix)]
+    fn stub_ssh(body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("doctainr-ssh-stub-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let program = dir.join("ssh");
+        std::fs::write(&program, format!("#!/bin/sh\n{body}")).unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        program
+    }
+
+    /// An `ssh` stand-in that forwards nothing: it links the `-L` socket to
+    /// the "remote" path, which is local here, records its pid and stays up
+    /// until killed. Only the tunnel's handling of the process is tested
+    /// with it; see `forwards_through_a_real_sshd` for the forwarding.
+    #[cfg(unix)]
+    const LINKING_SSH: &str = r#"while [ $# -gt 0 ]; do
+    if [ "$1" = "-L" ]; then spec=$2; fi
+    shift
+done
+echo $$ > "$(dirname "$0")/ssh.pid"
+ln -s "${spec#*:}" "${spec%%:*}"
+exec sleep 60
+"#;
+
+    #[cfg(unix)]
+    async fn ping(socket: &Path, engine: &tokio::net::UnixListener) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut client = tokio::net::UnixStream::connect(socket).await?;
+        client.write_all(b"GET /_ping").await?;
+        let (mut accepted, _) = engine.accept().await?;
+        let mut received = [0; 10];
+        accepted.read_exact(&mut received).await?;
+        assert_eq!(&received, b"GET /_ping");
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn tunnel_waits_for_the_socket_and_dies_with_it() {
+        use tokio::net::UnixListener;
+
+        let program = stub_ssh(LINKING_SSH);
+        let stub_dir = program.parent().unwrap().to_path_buf();
+        let remote = stub_dir.join("engine.sock");
+        let engine = UnixListener::bind(&remote).unwrap();
+        let target = SshTarget::parse(&format!("deploy@lab-01{}", remote.display())).unwrap();
+
+        let tunnel = SshTunnel::open_with(&program, &target).await.unwrap();
+        ping(tunnel.socket_path(), &engine).await.unwrap();
+
+        let pid = std::fs::read_to_string(stub_dir.join("ssh.pid")).unwrap();
+        let stat = PathBuf::from(format!("/proc/{}/stat", pid.trim()));
+        let socket_dir = tunnel.socket_path().parent().unwrap().to_path_buf();
+        drop(tunnel);
+        assert!(!socket_dir.exists());
+        // Killed, and either reaped already or a zombie waiting to be
+        let started = Instant::now();
+        loop {
+            let state = std::fs::read_to_string(&stat).unwrap_or_default();
+            let running = state
+                .rsplit_once(") ")
+                .is_some_and(|(_, rest)| !rest.starts_with('Z'));
+            if !running {
+                break;
+            }
+            assert!(
+                started.elapsed() < Duration::from_secs(5),
+                "ssh still running"
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        std::fs::remove_dir_all(stub_dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn tunnel_restarts_ssh_when_it_exits() {
+        use tokio::net::UnixListener;
+
+        let program = stub_ssh(LINKING_SSH);
+        let stub_dir = program.parent().unwrap().to_path_buf();
+        let remote = stub_dir.join("engine.sock");
+        let engine = UnixListener::bind(&remote).unwrap();
+        let target = SshTarget::parse(&format!("deploy@lab-01{}", remote.display())).unwrap();
+        let tunnel = SshTunnel::open_with(&program, &target).await.unwrap();
+
+        let pid_file = stub_dir.join("ssh.pid");
+        let first = std::fs::read_to_string(&pid_file).unwrap();
+        let killed = std::process::Command::new("kill")
+            .arg(first.trim())
+            .status()
+            .unwrap();
+        assert!(killed.success());
+
+        let started = Instant::now();
+        loop {
+            let pid = std::fs::read_to_string(&pid_file).unwrap_or_default();
+            if pid != first && !pid.is_empty() && ping(tunnel.socket_path(), &engine).await.is_ok()
+            {
+                break;
+            }
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "ssh was not restarted"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        drop(tunnel);
+        std::fs::remove_dir_all(stub_dir).unwrap();
+    }
+
+    /// Forwards to a socket on this machine through the local sshd, which
+    /// must accept key-based login for `DOCTAINR_TEST_SSH`, e.g.
+    /// `DOCTAINR_TEST_SSH=$USER@localhost cargo test -- --ignored`.
+    #[cfg(unix)]
+    #[tokio::test]
+    #[ignore = "needs a local sshd; set DOCTAINR_TEST_SSH=user@host"]
+    async fn forwards_through_a_real_sshd() {
+        use tokio::net::UnixListener;
+
+        let destination = std::env::var("DOCTAINR_TEST_SSH").expect("DOCTAINR_TEST_SSH is not set");
+        let dir = std::env::temp_dir().join(format!("doctainr-sshd-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let remote = dir.join("engine.sock");
+        let engine = UnixListener::bind(&remote).unwrap();
+        let target = SshTarget::parse(&format!("{destination}{}", remote.display())).unwrap();
+
+        let tunnel = SshTunnel::open(&target).await.unwrap();
+        ping(tunnel.socket_path(), &engine).await.unwrap();
+
+        drop(tunnel);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failed_connections_report_ssh_output() {
+        let program = stub_ssh("echo 'Permission denied (publickey).' >&2\nexit 255\n");
+        let target = SshTarget::parse("deploy@lab-01").unwrap();
+
+        let error = SshTunnel::open_with(&program, &target).await.err().unwrap();
+        assert!(error.to_string().contains("Permission denied (publickey)."));
+
+        std::fs::remove_dir_all(program.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn forward_args_end_with_destination() {
+        let target = SshTarget::parse("deploy@lab-01:2222").unwrap();
+        let args = target.forward_args(Path::new("/tmp/d/docker.sock"));
+        assert!(args.contains(&"/tmp/d/docker.sock:/var/run/docker.sock".to_string()));
+        assert!(args.contains(&"BatchMode=yes".to_string()));
+        assert_eq!(
+            &args[args.len() - 4..],
+            ["-p", "2222", "--", "deploy@lab-01"]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! A stand-in engine for testing `DockerService` end to end: an HTTP server
+//! on a unix socket that records each request and answers it from a closure.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixListener;
+
+use super::host::Endpoint;
+
+/// A request as the engine received it.
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    /// Path without the `/v1.xx` version prefix, e.g. `/containers/create`.
+    pub path: String,
+    /// Query parameters, percent-decoded.
+    pub query: HashMap<String, String>,
+    pub body: String,
+}
+
+type Respond = dyn Fn(&Request) -> (u16, String) + Send + Sync;
+
+pub struct StandIn {
+    dir: PathBuf,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl StandIn {
+    /// Starts serving; `respond` returns the status and JSON body for each
+    /// request. Connections carry one request each.
+    pub fn start(respond: impl Fn(&Request) -> (u16, String) + Send + Sync + 'static) -> Self {
+        let dir = std::env::temp_dir().join(format!("doctainr-engine-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let listener = UnixListener::bind(dir.join("docker.sock")).unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let respond: Arc<Respond> = Arc::new(respond);
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                let respond = respond.clone();
+                tokio::spawn(async move {
+                    let Some(request) = read_request(&mut stream).await else {
+                        return;
+                    };
+                    let (status, body) = respond(&request);
+                    recorded.lock().unwrap().push(request);
+                    let response = format!(
+                        "HTTP/1.1 {status} Stand-in\r\nContent-Type: application/json\r\nApi-Version: 1.47\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        Self { dir, requests }
+    }
+
+    pub fn endpoint(&self) -> Endpoint {
+        Endpoint {
+            host: format!("unix://{}", self.dir.join("docker.sock").display()),
+            tls: None,
+        }
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for StandIn {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+async fn read_request(stream: &mut tokio::net::UnixStream) -> Option<Request> {
+    let mut data = Vec::new();
+    let mut buf = [0; 4096];
+    let head_end = loop {
+        if let Some(at) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break at + 4;
+        }
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return None,
+            Ok(read) => data.extend_from_slice(&buf[..read]),
+        }
+    };
+    let head = String::from_utf8_lossy(&data[..head_end]).into_owned();
+    let length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while data.len() < head_end + length {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(read) => data.extend_from_slice(&buf[..read]),
+        }
+    }
+
+    let mut words = head.split_whitespace();
+    let method = words.next()?.to_string();
+    let target = words.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    // Drop the `/v1.47` prefix bollard puts in front of every path
+    let path = match path.strip_prefix("/v1.") {
+        Some(rest) => rest.find('/').map_or(path, |at| &rest[at..]),
+        None => path,
+    };
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect();
+    let body = String::from_utf8_lossy(&data[head_end..]).into_owned();
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query,
+        body,
+    })
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,114 @@
+//! Resource usage samples streamed by the engine for a running container.
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+
+/// Cumulative network and block I/O byte counters, summed over all
+/// interfaces and devices.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IoCounters {
+    pub net_rx: u64,
+    pub net_tx: u64,
+    pub block_read: u64,
+    pub block_write: u64,
+}
+
+impl IoCounters {
+    /// Bytes moved since `previous`. A counter that went backwards was reset
+    /// (the container restarted or an interface was replaced) and counts as zero.
+    pub fn since(&self, previous: &IoCounters) -> IoCounters {
+        IoCounters {
+            net_rx: self.net_rx.saturating_sub(previous.net_rx),
+            net_tx: self.net_tx.saturating_sub(previous.net_tx),
+            block_read: self.block_read.saturating_sub(previous.block_read),
+            block_write: self.block_write.saturating_sub(previous.block_write),
+        }
+    }
+}
+
+/// One sample of `docker stats`, usually one per second.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ContainerStats {
+    /// Share of one CPU, so a container busy on two cores reads 200%.
+    pub cpu_percent: f64,
+    /// Bytes in use, excluding reclaimable page cache.
+    pub memory_usage: u64,
+    pub memory_limit: u64,
+    /// Counters since the container started.
+    pub totals: IoCounters,
+    /// Bytes moved since the previous sample; zero for the first one.
+    pub delta: IoCounters,
+}
+
+impl ContainerStats {
+    pub fn memory_percent(&self) -> f64 {
+        if self.memory_limit == 0 {
+            return 0.0;
+        }
+        self.memory_usage as f64 / self.memory_limit as f64 * 100.0
+    }
+}
+
+pub type StatsStream = BoxStream<'static, Result<ContainerStats>>;
+
+/// CPU usage the way the Docker CLI computes it: the container's share of the
+/// host's CPU time between two readings, scaled by the number of CPUs.
+pub fn cpu_percent(cpu_delta: u64, system_delta: u64, online_cpus: u64) -> f64 {
+    if cpu_delta == 0 || system_delta == 0 {
+        return 0.0;
+    }
+    cpu_delta as f64 / system_delta as f64 * online_cpus.max(1) as f64 * 100.0
+}
+
+/// Fills in `delta` of each sample from the `totals` of the one before it.
+pub fn with_deltas(samples: StatsStream) -> StatsStream {
+    samples
+        .scan(None::<IoCounters>, |previous, sample| {
+            let sample = sample.map(|mut sample| {
+                if let Some(previous) = previous.as_ref() {
+                    sample.delta = sample.totals.since(previous);
+                }
+                *previous = Some(sample.totals);
+                sample
+            });
+            futures_util::future::ready(Some(sample))
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(net_rx: u64, block_write: u64) -> Result<ContainerStats> {
+        Ok(ContainerStats {
+            totals: IoCounters {
+                net_rx,
+                block_write,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn cpu_percent_scales_by_cpus() {
+        assert_eq!(cpu_percent(50, 1000, 4), 20.0);
+        assert_eq!(cpu_percent(0, 1000, 4), 0.0);
+        assert_eq!(cpu_percent(50, 0, 4), 0.0);
+    }
+
+    #[tokio::test]
+    async fn deltas_follow_totals_and_survive_resets() {
+        let samples =
+            futures_util::stream::iter([sample(100, 10), sample(250, 10), sample(40, 30)]);
+        let deltas: Vec<_> = with_deltas(samples.boxed())
+            .map(|sample| sample.unwrap().delta)
+            .collect()
+            .await;
+        assert_eq!(deltas[0], IoCounters::default());
+        assert_eq!((deltas[1].net_rx, deltas[1].block_write), (150, 0));
+        assert_eq!((deltas[2].net_rx, deltas[2].block_write), (0, 20));
+    }
+}
@@ -0,0 +1,292 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Installs ring as rustls' process-wide crypto provider. Both ring and
+/// aws-lc-rs are compiled in through our dependencies, and rustls panics when
+/// building a client config if it has to choose between them itself. Safe to
+/// call more than once.
+pub fn install_crypto_provider() {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+}
+
+/// Certificate material for engines exposed on TCP with TLS (usually port
+/// 2376). With `cert` and `key` left empty the engine is verified against
+/// `ca` but no client certificate is presented.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsOptions {
+    pub ca: PathBuf,
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl TlsOptions {
+    /// Uses the `ca.pem`, `cert.pem` and `key.pem` layout shared by
+    /// `DOCKER_CERT_PATH` and docker-machine.
+    pub fn from_cert_dir(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref();
+        Self {
+            ca: dir.join("ca.pem"),
+            cert: dir.join("cert.pem"),
+            key: dir.join("key.pem"),
+        }
+    }
+
+    /// Follows the Docker CLI: TLS is enabled by any non-empty
+    /// `DOCKER_TLS_VERIFY`, `0` included, and certificates come from
+    /// `DOCKER_CERT_PATH`, defaulting to `~/.docker`.
+    pub fn from_env() -> Option<Self> {
+        let verify = std::env::var("DOCKER_TLS_VERIFY").unwrap_or_default();
+        if verify.is_empty() {
+            return None;
+        }
+
+        let dir = std::env::var_os("DOCKER_CERT_PATH")
+            .map(PathBuf::from)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .or_else(|| dirs::home_dir().map(|home| home.join(".docker")))?;
+        Some(Self::from_cert_dir(dir))
+    }
+
+    pub fn has_client_cert(&self) -> bool {
+        !(self.cert.as_os_str().is_empty() && self.key.as_os_str().is_empty())
+    }
+
+    /// Checks that every file exists before handing them to the client, so a
+    /// typo produces a message naming the missing file.
+    pub fn check(&self) -> Result<()> {
+        let mut files = vec![("CA certificate", &self.ca)];
+        if self.has_client_cert() {
+            files.push(("client certificate", &self.cert));
+            files.push(("client key", &self.key));
+        }
+        for (what, path) in files {
+            if path.as_os_str().is_empty() {
+                bail!("TLS {what} path is not set");
+            }
+            if !path.is_file() {
+                bail!("TLS {what} not found at {}", path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use rcgen::{
+        BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa,
+        KeyPair,
+    };
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+    use tokio_rustls::rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+    use tokio_rustls::rustls::server::WebPkiClientVerifier;
+    use tokio_rustls::rustls::{RootCertStore, ServerConfig, crypto};
+
+    use super::*;
+    use crate::services::{ContainerEngine, DockerService, Endpoint, describe_error};
+
+    const VERSION: &str = r#"{"ApiVersion":"1.47","Version":"27.0.0","Os":"linux","Arch":"amd64"}"#;
+    const INFO: &str = r#"{"OperatingSystem":"TLS stand-in"}"#;
+
+    /// A CA with a server certificate for 127.0.0.1 and a client
+    /// certificate, as `dockerd --tlsverify` is set up.
+    struct Pki {
+        ca: Certificate,
+        server: (Certificate, KeyPair),
+        client: (Certificate, KeyPair),
+    }
+
+    impl Pki {
+        fn generate() -> Self {
+            let ca_key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "stand-in CA");
+            let ca = params.self_signed(&ca_key).unwrap();
+
+            let issue = |names: Vec<String>, purpose| {
+                let key = KeyPair::generate().unwrap();
+                let mut params = CertificateParams::new(names).unwrap();
+                params.extended_key_usages = vec![purpose];
+                let cert = params.signed_by(&key, &ca, &ca_key).unwrap();
+                (cert, key)
+            };
+            let server = issue(
+                vec!["127.0.0.1".to_string()],
+                ExtendedKeyUsagePurpose::ServerAuth,
+            );
+            let client = issue(
+                vec!["client".to_string()],
+                ExtendedKeyUsagePurpose::ClientAuth,
+            );
+            Self { ca, server, client }
+        }
+
+        /// Writes `ca.pem` from `ca`, and this PKI's client certificate and key.
+        fn write_client_files(&self, ca: &Certificate) -> PathBuf {
+            let dir = std::env::temp_dir().join(format!("doctainr-tls-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("ca.pem"), ca.pem()).unwrap();
+            std::fs::write(dir.join("cert.pem"), self.client.0.pem()).unwrap();
+            std::fs::write(dir.join("key.pem"), self.client.1.serialize_pem()).unwrap();
+            dir
+        }
+
+        /// Serves `_ping`, `version` and `info` over TLS, one request per
+        /// connection. With `client_auth`, only to clients holding a
+        /// certificate from this CA.
+        async fn serve(&self, client_auth: bool) -> SocketAddr {
+            let provider = Arc::new(crypto::ring::default_provider());
+            let mut roots = RootCertStore::empty();
+            roots.add(self.ca.der().clone()).unwrap();
+            let verifier =
+                WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                    .build()
+                    .unwrap();
+            let key = PrivatePkcs8KeyDer::from(self.server.1.serialize_der());
+            let builder = ServerConfig::builder_with_provider(provider)
+                .with_safe_default_protocol_versions()
+                .unwrap();
+            let builder = if client_auth {
+                builder.with_client_cert_verifier(verifier)
+            } else {
+                builder.with_no_client_auth()
+            };
+            let config = builder
+                .with_single_cert(vec![self.server.0.der().clone()], PrivateKeyDer::Pkcs8(key))
+                .unwrap();
+            let acceptor = TlsAcceptor::from(Arc::new(config));
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            tokio::spawn(async move {
+                while let Ok((tcp, _)) = listener.accept().await {
+                    let acceptor = acceptor.clone();
+                    tokio::spawn(async move {
+                        let Ok(mut stream) = acceptor.accept(tcp).await else {
+                            return;
+                        };
+                        let mut request = Vec::new();
+                        let mut buf = [0; 1024];
+                        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                            match stream.read(&mut buf).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(read) => request.extend_from_slice(&buf[..read]),
+                            }
+                        }
+                        let head = String::from_utf8_lossy(&request);
+                        let path = head.split_whitespace().nth(1).unwrap_or_default();
+                        let body = match path.rsplit('/').next() {
+                            Some("_ping") => "OK",
+                            Some("version") => VERSION,
+                            Some("info") => INFO,
+                            _ => "{}",
+                        };
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nApi-Version: 1.47\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                            body.len()
+                        );
+                        let _ = stream.write_all(response.as_bytes()).await;
+                        let _ = stream.shutdown().await;
+                    });
+                }
+            });
+            addr
+        }
+    }
+
+    async fn probe(addr: SocketAddr, tls: TlsOptions) -> anyhow::Result<String> {
+        install_crypto_provider();
+        let endpoint = Endpoint {
+            host: format!("tcp://{addr}"),
+            tls: Some(tls),
+        };
+        let service = DockerService::connect(&endpoint).await?;
+        Ok(service.probe().await?.os)
+    }
+
+    #[tokio::test]
+    async fn connects_to_a_tls_stand_in() {
+        let pki = Pki::generate();
+        let addr = pki.serve(true).await;
+
+        let trusted = pki.write_client_files(&pki.ca);
+        let tls = TlsOptions::from_cert_dir(&trusted);
+        assert_eq!(probe(addr, tls).await.unwrap(), "TLS stand-in");
+
+        // A CA that did not issue the server certificate fails the handshake
+        let other = Pki::generate();
+        let untrusted = other.write_client_files(&other.ca);
+        let error = probe(addr, TlsOptions::from_cert_dir(&untrusted))
+            .await
+            .unwrap_err();
+        assert!(
+            describe_error(&error).starts_with("TLS certificate error"),
+            "{error:#}"
+        );
+
+        std::fs::remove_dir_all(trusted).unwrap();
+        std::fs::remove_dir_all(untrusted).unwrap();
+    }
+
+    #[tokio::test]
+    async fn connects_with_only_a_ca() {
+        let pki = Pki::generate();
+        let addr = pki.serve(false).await;
+
+        let dir = pki.write_client_files(&pki.ca);
+        let tls = TlsOptions {
+            ca: dir.join("ca.pem"),
+            ..Default::default()
+        };
+        tls.check().unwrap();
+        assert_eq!(probe(addr, tls).await.unwrap(), "TLS stand-in");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cert_dir_uses_docker_file_names() {
+        let tls = TlsOptions::from_cert_dir("/certs");
+        assert_eq!(tls.ca, PathBuf::from("/certs/ca.pem"));
+        assert_eq!(tls.cert, PathBuf::from("/certs/cert.pem"));
+        assert_eq!(tls.key, PathBuf::from("/certs/key.pem"));
+    }
+
+    #[test]
+    fn check_names_the_missing_file() {
+        let dir = std::env::temp_dir().join(format!("doctainr-tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ca.pem"), "ca").unwrap();
+
+        let error = TlsOptions::from_cert_dir(&dir).check().unwrap_err();
+        assert!(error.to_string().contains("client certificate not found"));
+
+        std::fs::write(dir.join("cert.pem"), "cert").unwrap();
+        std::fs::write(dir.join("key.pem"), "key").unwrap();
+        assert!(TlsOptions::from_cert_dir(&dir).check().is_ok());
+
+        // A certificate without its key is a mistake, not a CA-only setup
+        let tls = TlsOptions {
+            key: PathBuf::new(),
+            ..TlsOptions::from_cert_dir(&dir)
+        };
+        assert!(
+            tls.check()
+                .unwrap_err()
+                .to_string()
+                .contains("client key path is not set")
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Turns ANSI SGR color codes in log output into styled text runs. Other
+//! escape sequences (cursor movement, erasing) are dropped.
+
+/// A run of text sharing one style, with CSS classes such as `ansi-fg-1
+/// ansi-bold`, or an empty string when unstyled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnsiSpan {
+    pub text: String,
+    pub class: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Style {
+    /// Palette index 0-15; 8-15 are the bright variants.
+    fg: Option<u8>,
+    bg: Option<u8>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Style {
+    fn apply(&mut self, params: &str) {
+        let codes: Vec<u16> = params
+            .split(';')
+            .map(|code| code.parse().unwrap_or(0))
+            .collect();
+        let mut index = 0;
+        while index < codes.len() {
+            match codes[index] {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                code @ 30..=37 => self.fg = Some((code - 30) as u8),
+                39 => self.fg = None,
+                code @ 40..=47 => self.bg = Some((code - 40) as u8),
+                49 => self.bg = None,
+                code @ 90..=97 => self.fg = Some((code - 90 + 8) as u8),
+                code @ 100..=107 => self.bg = Some((code - 100 + 8) as u8),
+                // 256-color and true-color forms are skipped, not rendered
+                38 | 48 => {
+                    index += match codes.get(index + 1) {
+                        Some(5) => 2,
+                        Some(2) => 4,
+                        _ => 0,
+                    };
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+    }
+
+    fn class(&self) -> String {
+        let mut classes = Vec::new();
+        if let Some(fg) = self.fg {
+            classes.push(format!("ansi-fg-{fg}"));
+        }
+        if let Some(bg) = self.bg {
+            classes.push(format!("ansi-bg-{bg}"));
+        }
+        for (on, name) in [
+            (self.bold, "ansi-bold"),
+            (self.dim, "ansi-dim"),
+            (self.italic, "ansi-italic"),
+            (self.underline, "ansi-underline"),
+        ] {
+            if on {
+                classes.push(name.to_string());
+            }
+        }
+        classes.join(" ")
+    }
+}
+
+/// Splits `text` into styled runs. The style resets at the start of every
+/// call, so each log line is rendered on its own.
+pub fn parse_ansi(text: &str) -> Vec<AnsiSpan> {
+    let mut spans = Vec::new();
+    let mut style = Style::default();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            current.push(c);
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            // Lone ESC or a non-CSI sequence: drop the next character too
+            chars.next();
+            continue;
+        }
+        chars.next();
+
+        // CSI: parameters up to a final byte in '@'..='~'
+        let mut params = String::new();
+        let mut command = None;
+        for c in chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                command = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if command == Some('m') {
+            if !current.is_empty() {
+                spans.push(AnsiSpan {
+                    text: std::mem::take(&mut current),
+                    class: style.class(),
+                });
+            }
+            style.apply(&params);
+        }
+    }
+
+    if !current.is_empty() {
+        spans.push(AnsiSpan {
+            text: current,
+            class: style.class(),
+        });
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, class: &str) -> AnsiSpan {
+        AnsiSpan {
+            text: text.to_string(),
+            class: class.to_string(),
+        }
+    }
+
+    #[test]
+    fn splits_colored_runs() {
+        assert_eq!(
+            parse_ansi("\u{1b}[1;31mERROR\u{1b}[0m disk full"),
+            [span("ERROR", "ansi-fg-1 ansi-bold"), span(" disk full", "")]
+        );
+        assert_eq!(
+            parse_ansi("\u{1b}[92mok\u{1b}[39m"),
+            [span("ok", "ansi-fg-10")]
+        );
+    }
+
+    #[test]
+    fn drops_other_escape_sequences() {
+        assert_eq!(
+            parse_ansi("\u{1b}[2Kprogress \u{1b}[38;5;208m50%"),
+            [span("progress ", ""), span("50%", "")]
+        );
+        assert_eq!(parse_ansi("plain"), [span("plain", "")]);
+    }
+}
@@ -1,55 +1,600 @@
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dioxus::core::{Task, spawn_forever};
 use dioxus::prelude::*;
+use futures_util::future::BoxFuture;
+use futures_util::{FutureExt, StreamExt};
+
+use crate::services::{
+    ContainerAction, ContainerEngine, ContainerInfo, DockerContexts, DockerService, Endpoint,
+    EngineEvent, EngineReport, ImageInfo, ImageRemoveOptions, PruneReport, PruneScope,
+    SharedEngine, VolumeInfo, describe_error, split_tag,
+};
+use crate::utils::{
+    AppConfig, BulkReport, BulkResource, BulkTarget, EngineConfig, Listing, LoadState, LoadStates,
+    Notification, Notifications, Severity, TableViews, format_bytes, unix_now,
+};
+
+use super::bulk::{BULK_CONCURRENCY, run_bounded};
+
+/// Name of the engine reached through the Settings host or the active Docker
+/// context. Additional engines are registered under their own names.
+pub const PRIMARY_ENGINE: &str = "primary";
+
+/// Wait before resubscribing to a dropped events stream, doubled after each
+/// attempt that delivers nothing, up to `EVENTS_RETRY_MAX`.
+const EVENTS_RETRY: Duration = Duration::from_secs(1);
+const EVENTS_RETRY_MAX: Duration = Duration::from_secs(30);
+
+/// Source of the warning raised when a refresh finds no engine connected.
+const NO_ENGINE_SOURCE: &str = "no-engine";
+
+/// How long a new notification shows as a toast.
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineStatus {
+    Connecting,
+    Connected,
+    Unreachable(String),
+}
+
+impl EngineStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EngineStatus::Connecting => "Connecting",
+            EngineStatus::Connected => "Connected",
+            EngineStatus::Unreachable(_) => "Unreachable",
+        }
+    }
 
-use crate::services::{ContainerInfo, ContainerState, DockerService, ImageInfo, VolumeInfo};
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            EngineStatus::Connecting => "pending",
+            EngineStatus::Connected => "running",
+            EngineStatus::Unreachable(_) => "stopped",
+        }
+    }
+}
+
+/// One engine in the workspace and the state of its connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EngineState {
+    pub name: String,
+    pub endpoint: Endpoint,
+    pub status: EngineStatus,
+    /// Runtime and version (e.g. "Podman 5.2.1"), once the engine has answered.
+    pub runtime: Option<String>,
+}
 
 #[derive(Clone)]
 pub struct AppState {
-    pub docker_host: Signal<String>,
+    pub config: Signal<AppConfig>,
+    /// Problems found (and corrected) when loading the config file.
+    pub config_warnings: Signal<Vec<String>>,
+    /// The endpoint of the primary engine.
+    pub endpoint: Signal<Endpoint>,
+    /// Docker CLI contexts found in `~/.docker`.
+    pub contexts: Signal<DockerContexts>,
+    /// Context `endpoint` was taken from, or `None` for a host set in Settings.
+    pub active_context: Signal<Option<String>>,
+    /// Every engine in the workspace, primary first.
+    pub engines: Signal<Vec<EngineState>>,
     pub containers: Signal<Vec<ContainerInfo>>,
     pub images: Signal<Vec<ImageInfo>>,
     pub volumes: Signal<Vec<VolumeInfo>>,
     pub last_action: Signal<Option<String>>,
-    pub error_message: Signal<Option<String>>,
-    pub is_loading: Signal<bool>,
-    docker_service: Option<DockerService>,
+    /// Errors and warnings for the user, until dismissed.
+    pub notifications: Signal<Notifications>,
+    /// Load state of each list of each engine.
+    pub loads: Signal<LoadStates>,
+    /// `(engine, id)` of containers with an action in flight. Further
+    /// actions on them are ignored until it finishes.
+    pub busy_containers: Signal<HashSet<(String, String)>>,
+    /// Filters and sort order of each resource table, kept across navigation.
+    pub tables: Signal<TableViews>,
+    /// Progress of the latest bulk action, item by item.
+    pub bulk_report: Signal<Option<BulkReport>>,
+    /// Held by the bulk action that is running; later ones wait for it in
+    /// the order they were requested.
+    bulk_turn: Arc<tokio::sync::Mutex<()>>,
+    /// Whether the window is on screen; background polling pauses otherwise.
+    pub window_visible: Signal<bool>,
+    /// Reloads in flight, by engine and list, and whether another was
+    /// requested meanwhile.
+    refreshing: Signal<HashMap<(String, Listing), bool>>,
+    services: Signal<HashMap<String, SharedEngine>>,
+    /// Task following the events stream of each engine, cancelled when the
+    /// engine is removed or replaced so its connection is closed.
+    watchers: Signal<HashMap<String, Task>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let docker_service = match DockerService::new() {
-            Ok(service) => Some(service),
-            Err(e) => {
-                eprintln!("Failed to connect to Docker: {}", e);
-                None
-            }
-        };
+        let (initial_config, initial_contexts, initial_warnings) = use_hook(|| {
+            let (config, mut warnings) = match AppConfig::load() {
+                Ok(loaded) => loaded,
+                Err(e) => (AppConfig::default(), vec![format!("{e:#}")]),
+            };
+            let contexts = load_contexts(&mut warnings);
+            (config, contexts, warnings)
+        });
+        let state = Self::from_parts(initial_config, initial_contexts, initial_warnings);
 
-        let docker_host = use_signal(|| {
-            std::env::var("DOCKER_HOST")
-                .unwrap_or_else(|_| "unix:///var/run/docker.sock".to_string())
+        // Connect and load data once, not on every re-render of the root component
+        use_hook(|| {
+            state.reconnect();
+            state.sync_engines();
+            state.poll();
         });
+
+        state
+    }
+
+    /// Creates the signals without touching the config file or connecting to
+    /// any engine.
+    fn from_parts(
+        initial_config: AppConfig,
+        initial_contexts: DockerContexts,
+        initial_warnings: Vec<String>,
+    ) -> Self {
+        let (initial_endpoint, initial_context) =
+            initial_config.resolve_endpoint(&initial_contexts);
+        let config = use_signal(|| initial_config.clone());
+        let config_warnings = use_signal(|| initial_warnings);
+        let endpoint = use_signal(|| initial_endpoint);
+        let contexts = use_signal(|| initial_contexts);
+        let active_context = use_signal(|| initial_context);
+        let engines = use_signal(Vec::new);
+        // Connecting may open an SSH tunnel, so services are built asynchronously
+        let services = use_signal(HashMap::new);
         let containers = use_signal(Vec::new);
         let images = use_signal(Vec::new);
         let volumes = use_signal(Vec::new);
         let last_action = use_signal(|| None);
-        let error_message = use_signal(|| None);
-        let is_loading = use_signal(|| false);
+        let notifications = use_signal(Notifications::default);
+        let loads = use_signal(LoadStates::default);
+        let busy_containers = use_signal(HashSet::new);
+        let tables = use_signal(TableViews::default);
+        let bulk_report = use_signal(|| None);
+        let bulk_turn = use_hook(|| Arc::new(tokio::sync::Mutex::new(())));
+        let window_visible = use_signal(|| true);
+        let refreshing = use_signal(HashMap::new);
+        let watchers = use_signal(HashMap::new);
 
-        let state = Self {
-            docker_host,
+        Self {
+            config,
+            config_warnings,
+            endpoint,
+            contexts,
+            active_context,
+            engines,
             containers,
             images,
             volumes,
             last_action,
-            error_message,
-            is_loading,
-            docker_service,
+            notifications,
+            loads,
+            busy_containers,
+            tables,
+            bulk_report,
+            bulk_turn,
+            window_visible,
+            refreshing,
+            services,
+            watchers,
+        }
+    }
+
+    /// Points the primary engine at a different endpoint. Its service is
+    /// dropped, its cached resources are cleared and everything is reloaded
+    /// from the new endpoint.
+    pub fn set_endpoint(&self, endpoint: Endpoint) {
+        let mut endpoint_signal = self.endpoint;
+        endpoint_signal.set(endpoint);
+        self.reconnect();
+    }
+
+    /// Stores `config` as the active preferences, writes it to disk and
+    /// reconnects every engine whose endpoint changed.
+    pub fn save_config(&self, config: AppConfig) {
+        let mut config_signal = self.config;
+        let mut config_warnings = self.config_warnings;
+        let mut active_context = self.active_context;
+        let result = config.save();
+        let (endpoint, context) = config.resolve_endpoint(&self.contexts.peek());
+        let endpoint_changed = endpoint != *self.endpoint.peek();
+        config_signal.set(config);
+        config_warnings.set(Vec::new());
+        active_context.set(context);
+
+        if endpoint_changed {
+            self.set_endpoint(endpoint);
+        }
+        self.sync_engines();
+
+        match result {
+            Ok(()) => self.record_action("Saved settings"),
+            Err(e) => self.notify(Notification::new(
+                Severity::Error,
+                "Settings not saved",
+                format!("{e:#}"),
+            )),
+        }
+    }
+
+    /// Re-reads the Docker CLI contexts from disk.
+    pub fn reload_contexts(&self) {
+        let mut warnings = Vec::new();
+        let loaded = load_contexts(&mut warnings);
+        self.contexts.clone().set(loaded);
+        if !warnings.is_empty() {
+            self.notify(Notification::new(
+                Severity::Warning,
+                "Docker contexts",
+                warnings.join("; "),
+            ));
+        }
+    }
+
+    /// Connects to the Docker CLI context called `name`, or to the host from
+    /// Settings when `name` is `None`, and reloads every resource.
+    pub fn switch_context(&self, name: Option<String>) {
+        let endpoint = match &name {
+            Some(name) => match self.contexts.peek().find(name) {
+                Some(context) => context.endpoint.clone(),
+                None => {
+                    self.notify(Notification::new(
+                        Severity::Error,
+                        "Unknown context",
+                        format!("Unknown Docker context \"{name}\""),
+                    ));
+                    return;
+                }
+            },
+            None => self.config.peek().endpoint(),
         };
 
-        // Spawn initial data load
-        state.refresh_all();
+        self.active_context.clone().set(name.clone());
+        self.set_endpoint(endpoint);
+        self.record_action(match name {
+            Some(name) => format!("Switched to context {name}"),
+            None => "Switched to the Settings host".to_string(),
+        });
+    }
 
-        state
+    /// Rebuilds the primary engine's service from the current `endpoint` value.
+    pub fn reconnect(&self) {
+        let endpoint = self.endpoint.peek().clone();
+        self.connect_engine(PRIMARY_ENGINE.to_string(), endpoint);
+    }
+
+    /// Brings the additional engines in line with the config: removed engines
+    /// are disconnected, new or changed ones are (re)connected.
+    fn sync_engines(&self) {
+        let wanted: Vec<EngineConfig> = self.config.peek().engines.clone();
+        let existing: Vec<EngineState> = self.engines.peek().clone();
+
+        for engine in &existing {
+            let kept =
+                engine.name == PRIMARY_ENGINE || wanted.iter().any(|w| w.name == engine.name);
+            if !kept {
+                self.remove_engine(&engine.name);
+            }
+        }
+
+        for engine in wanted {
+            let unchanged = existing
+                .iter()
+                .any(|e| e.name == engine.name && e.endpoint == engine.endpoint);
+            if !unchanged {
+                self.connect_engine(engine.name, engine.endpoint);
+            }
+        }
+    }
+
+    /// Replaces the service for `name` with a new connection to `endpoint` and
+    /// reloads that engine's resources. Other engines are left untouched.
+    fn connect_engine(&self, name: String, endpoint: Endpoint) {
+        self.clear_engine(&name);
+        self.set_engine_status(&name, Some(&endpoint), EngineStatus::Connecting);
+
+        let app_state = self.clone();
+        // Not tied to the calling view, so navigating away cannot abort the switch
+        spawn_forever(async move {
+            let result = DockerService::connect(&endpoint).await;
+
+            // A newer connection attempt superseded this one while it was pending
+            let current = app_state
+                .engines
+                .peek()
+                .iter()
+                .any(|e| e.name == name && e.endpoint == endpoint);
+            if !current {
+                return;
+            }
+
+            match result {
+                Ok(service) => {
+                    app_state.attach_engine(&name, &endpoint, Arc::new(service));
+                    app_state.identify_engine(&name);
+                }
+                Err(e) => {
+                    let context = format!("Failed to connect to {}", endpoint.host);
+                    app_state.notify(
+                        Notification::engine_error(&context, &e)
+                            .with_source(format!("connect:{name}")),
+                    );
+                    let message = describe_error(&e);
+                    app_state.set_engine_status(&name, None, EngineStatus::Unreachable(message));
+                }
+            }
+        });
+    }
+
+    /// Connects to `endpoint` with a throwaway service and probes it. The
+    /// active connection and the config are left alone; saving applies them.
+    pub async fn test_connection(&self, endpoint: Endpoint) -> anyhow::Result<EngineReport> {
+        let service = DockerService::connect(&endpoint).await?;
+        service.probe().await
+    }
+
+    /// Registers a connected `engine` under `name` and loads its resources.
+    /// Any engine already known by that name is replaced.
+    fn attach_engine(&self, name: &str, endpoint: &Endpoint, engine: SharedEngine) {
+        self.services
+            .clone()
+            .write()
+            .insert(name.to_string(), engine);
+        self.set_engine_status(name, Some(endpoint), EngineStatus::Connected);
+        self.resolve(&format!("connect:{name}"));
+        self.resolve(NO_ENGINE_SOURCE);
+        // The first load waits for the events stream to open
+        let mut loads = self.loads;
+        for list in [Listing::Containers, Listing::Images, Listing::Volumes] {
+            loads.write().set(name, list, LoadState::Loading);
+        }
+        self.watch_events(name);
+    }
+
+    /// Follows the events stream of engine `name` and applies each change to
+    /// the resource lists. Everything is loaded once the stream is open, and
+    /// again each time it drops and is reopened, to catch up on changes
+    /// missed in between. Runs until the engine is removed or replaced.
+    fn watch_events(&self, name: &str) {
+        self.stop_watching(name);
+        let Some(service) = self.service(name) else {
+            return;
+        };
+        let key = name.to_string();
+        let name = name.to_string();
+        let app_state = self.clone();
+
+        let task = spawn_forever(async move {
+            let mut retry = EVENTS_RETRY;
+            loop {
+                // Subscribe before loading, so no change falls in between.
+                // Streams are lazy; peeking sends the request and keeps
+                // anything already received for the loop below.
+                let mut events = service.events().peekable();
+                let _ = futures_util::poll!(Pin::new(&mut events).peek());
+                app_state.refresh_engine(&name);
+                while let Some(Ok(event)) = events.next().await {
+                    retry = EVENTS_RETRY;
+                    app_state.apply_event(&name, &service, event);
+                }
+
+                tokio::time::sleep(retry).await;
+                retry = (retry * 2).min(EVENTS_RETRY_MAX);
+            }
+        });
+        self.watchers.clone().write().insert(key, task);
+    }
+
+    /// Cancels the events task of `name`, dropping its stream and its hold
+    /// on the service.
+    fn stop_watching(&self, name: &str) {
+        if let Some(task) = self.watchers.clone().write().remove(name) {
+            task.cancel();
+        }
+    }
+
+    fn apply_event(&self, engine: &str, service: &SharedEngine, event: EngineEvent) {
+        let engine = engine.to_string();
+        match event {
+            EngineEvent::ContainerChanged(id) => {
+                self.sync_container(engine, service.clone(), id);
+            }
+            EngineEvent::ContainerRemoved(id) => {
+                // Events carry full ids, the list the 12-character short form
+                self.containers
+                    .clone()
+                    .write()
+                    .retain(|c| !(c.engine == engine && id.starts_with(&c.id)));
+            }
+            EngineEvent::ImagesChanged => self.refresh_engine_images(engine, service.clone()),
+            EngineEvent::ImageRemoved(id) => {
+                self.images
+                    .clone()
+                    .write()
+                    .retain(|image| !(image.engine == engine && image.id == id));
+            }
+            EngineEvent::VolumeCreated(_) => self.refresh_engine_volumes(engine, service.clone()),
+            EngineEvent::VolumeRemoved(name) => {
+                self.volumes
+                    .clone()
+                    .write()
+                    .retain(|volume| !(volume.engine == engine && volume.name == name));
+            }
+        }
+    }
+
+    /// Re-reads the row of container `id` alone: updated in place, added when
+    /// new, dropped when the container is gone.
+    fn sync_container(&self, engine: String, service: SharedEngine, id: String) {
+        let mut containers = self.containers;
+
+        spawn(async move {
+            let Ok(found) = service.find_container(&id).await else {
+                // The next full refresh reports the engine's state
+                return;
+            };
+            let mut all = containers.write();
+            let position = all
+                .iter()
+                .position(|c| c.engine == engine && id.starts_with(&c.id));
+            match (found, position) {
+                (Some(mut info), Some(index)) => {
+                    info.set_engine(&engine);
+                    all[index] = info;
+                }
+                (Some(mut info), None) => {
+                    info.set_engine(&engine);
+                    all.push(info);
+                }
+                (None, Some(index)) => {
+                    all.remove(index);
+                }
+                (None, None) => {}
+            }
+        });
+    }
+
+    /// Asks a freshly connected engine which runtime it is. Failures are left
+    /// to the resource refresh to report.
+    fn identify_engine(&self, name: &str) {
+        let Some(service) = self.service(name) else {
+            return;
+        };
+        let Some(endpoint) = self.engine_endpoint(name) else {
+            return;
+        };
+        let app_state = self.clone();
+        let name = name.to_string();
+        spawn_forever(async move {
+            let Ok(report) = service.probe().await else {
+                return;
+            };
+            // Skip if the engine was pointed elsewhere in the meantime
+            if app_state.engine_endpoint(&name) == Some(endpoint) {
+                app_state.set_engine_runtime(&name, &report);
+            }
+        });
+    }
+
+    fn engine_endpoint(&self, name: &str) -> Option<Endpoint> {
+        self.engines
+            .peek()
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| e.endpoint.clone())
+    }
+
+    fn set_engine_runtime(&self, name: &str, report: &EngineReport) {
+        let mut engines = self.engines;
+        if let Some(engine) = engines.write().iter_mut().find(|e| e.name == name) {
+            engine.runtime = Some(format!("{} {}", report.kind.label(), report.engine_version));
+        }
+    }
+
+    fn remove_engine(&self, name: &str) {
+        self.clear_engine(name);
+        self.engines.clone().write().retain(|e| e.name != name);
+    }
+
+    /// Drops the service and every cached resource of one engine.
+    fn clear_engine(&self, name: &str) {
+        self.stop_watching(name);
+        self.services.clone().write().remove(name);
+        self.containers.clone().write().retain(|c| c.engine != name);
+        self.images.clone().write().retain(|i| i.engine != name);
+        self.volumes.clone().write().retain(|v| v.engine != name);
+        self.loads.clone().write().remove_engine(name);
+        self.refreshing
+            .clone()
+            .write()
+            .retain(|(engine, _), _| engine != name);
+    }
+
+    /// Updates the status of `name`, registering the engine when `endpoint` is
+    /// given and it is not known yet. The primary engine always stays first.
+    fn set_engine_status(&self, name: &str, endpoint: Option<&Endpoint>, status: EngineStatus) {
+        let mut engines_signal = self.engines;
+        let mut engines = engines_signal.write();
+        match engines.iter_mut().find(|e| e.name == name) {
+            Some(engine) => {
+                if let Some(endpoint) = endpoint {
+                    engine.endpoint = endpoint.clone();
+                    engine.runtime = None;
+                }
+                engine.status = status;
+            }
+            None => {
+                let Some(endpoint) = endpoint else {
+                    return;
+                };
+                let engine = EngineState {
+                    name: name.to_string(),
+                    endpoint: endpoint.clone(),
+                    status,
+                    runtime: None,
+                };
+                if name == PRIMARY_ENGINE {
+                    engines.insert(0, engine);
+                } else {
+                    engines.push(engine);
+                }
+            }
+        }
+    }
+
+    /// Services of every connected engine, in workspace order.
+    fn connected_services(&self) -> Vec<(String, SharedEngine)> {
+        let services = self.services.peek();
+        self.engines
+            .peek()
+            .iter()
+            .filter_map(|e| Some((e.name.clone(), services.get(&e.name)?.clone())))
+            .collect()
+    }
+
+    /// The connected engine called `engine`, for views that talk to it directly.
+    pub fn service(&self, engine: &str) -> Option<SharedEngine> {
+        self.services.peek().get(engine).cloned()
+    }
+
+    /// Whether `service` is still what `engine` is connected to, rather than
+    /// a connection that has since been removed or replaced.
+    fn is_current(&self, engine: &str, service: &SharedEngine) -> bool {
+        self.services
+            .peek()
+            .get(engine)
+            .is_some_and(|current| Arc::ptr_eq(current, service))
+    }
+
+    /// Queues `notification` and shows it as a toast for a few seconds.
+    pub fn notify(&self, notification: Notification) {
+        let mut notifications = self.notifications;
+        let id = notifications.write().push(notification, unix_now());
+        spawn_forever(async move {
+            tokio::time::sleep(TOAST_DURATION).await;
+            notifications.write().hide_toast(id);
+        });
+    }
+
+    /// Dismisses the notifications raised by `source`, which works again.
+    fn resolve(&self, source: &str) {
+        let raised = self
+            .notifications
+            .peek()
+            .iter()
+            .any(|notification| notification.source.as_deref() == Some(source));
+        if raised {
+            self.notifications.clone().write().resolve(source);
+        }
     }
 
     pub fn refresh_all(&self) {
@@ -58,130 +603,1010 @@ impl AppState {
         self.refresh_volumes();
     }
 
+    /// Reloads every list of every engine without showing them as loading.
+    fn refresh_in_background(&self) {
+        for (engine, _) in self.connected_services() {
+            self.refresh_engine(&engine);
+        }
+    }
+
+    fn refresh_engine(&self, engine: &str) {
+        if let Some(service) = self.service(engine) {
+            self.refresh_engine_containers(engine.to_string(), service.clone());
+            self.refresh_engine_images(engine.to_string(), service.clone());
+            self.refresh_engine_volumes(engine.to_string(), service);
+        }
+    }
+
+    /// Reloads containers from every engine. Each engine is queried
+    /// independently, so a slow or unreachable one does not hold up the rest.
     pub fn refresh_containers(&self) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut containers = self.containers.clone();
-            let mut error_message = self.error_message.clone();
-            let mut is_loading = self.is_loading.clone();
-
-            spawn(async move {
-                is_loading.set(true);
-                match service.list_containers().await {
-                    Ok(data) => {
-                        containers.set(data);
-                        error_message.set(None);
-                    }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to list containers: {}", e)));
-                    }
-                }
-                is_loading.set(false);
-            });
-        } else {
-            self.error_message
-                .clone()
-                .set(Some("Docker service not available".to_string()));
+        let services = self.connected_services();
+        if services.is_empty() {
+            self.notify(
+                Notification::new(
+                    Severity::Warning,
+                    "No engine connected",
+                    "Docker service not available",
+                )
+                .with_source(NO_ENGINE_SOURCE),
+            );
+        }
+        for (engine, service) in services {
+            self.refresh_list(engine, service, Listing::Containers, Reload::Shown);
         }
     }
 
+    fn refresh_engine_containers(&self, engine: String, service: SharedEngine) {
+        self.refresh_list(engine, service, Listing::Containers, Reload::Background);
+    }
+
     pub fn refresh_images(&self) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut images = self.images.clone();
-            let mut error_message = self.error_message.clone();
-
-            spawn(async move {
-                match service.list_images().await {
-                    Ok(data) => {
-                        images.set(data);
-                        error_message.set(None);
-                    }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to list images: {}", e)));
-                    }
-                }
-            });
+        for (engine, service) in self.connected_services() {
+            self.refresh_list(engine, service, Listing::Images, Reload::Shown);
         }
     }
 
+    /// Reloads the images of one engine in the background, after a change
+    /// that only touched that engine.
+    pub fn refresh_engine_images(&self, engine: String, service: SharedEngine) {
+        self.refresh_list(engine, service, Listing::Images, Reload::Background);
+    }
+
     pub fn refresh_volumes(&self) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut volumes = self.volumes.clone();
-            let mut error_message = self.error_message.clone();
-
-            spawn(async move {
-                match service.list_volumes().await {
-                    Ok(data) => {
-                        volumes.set(data);
-                        error_message.set(None);
-                    }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to list volumes: {}", e)));
-                    }
+        for (engine, service) in self.connected_services() {
+            self.refresh_list(engine, service, Listing::Volumes, Reload::Shown);
+        }
+    }
+
+    fn refresh_engine_volumes(&self, engine: String, service: SharedEngine) {
+        self.refresh_list(engine, service, Listing::Volumes, Reload::Background);
+    }
+
+    /// Reloads one list of one engine. Requests made while that reload is
+    /// running are coalesced into a single extra reload once it finishes, so
+    /// the list still reflects them. The task outlives the calling view, as
+    /// an aborted reload would hold up later ones. A background reload of a
+    /// list that has loaded before keeps showing when it was last updated.
+    /// Once the engine is replaced the reload stops, and the engine's new
+    /// connection reloads on its own.
+    fn refresh_list(&self, engine: String, service: SharedEngine, list: Listing, reload: Reload) {
+        let mut loads = self.loads;
+        if reload == Reload::Shown || loads.peek().get(&engine, list) == LoadState::Idle {
+            loads.write().set(&engine, list, LoadState::Loading);
+        }
+        let mut refreshing = self.refreshing;
+        let key = (engine.clone(), list);
+        if let Some(again) = refreshing.write().get_mut(&key) {
+            *again = true;
+            return;
+        }
+        refreshing.write().insert(key.clone(), false);
+        let app_state = self.clone();
+
+        spawn_forever(async move {
+            loop {
+                match list {
+                    Listing::Containers => app_state.load_containers(&engine, &service).await,
+                    Listing::Images => app_state.load_images(&engine, &service).await,
+                    Listing::Volumes => app_state.load_volumes(&engine, &service).await,
                 }
-            });
+                // `clear_engine` dropped this reload's entry, which may belong
+                // to the new connection's reload by now
+                if !app_state.is_current(&engine, &service) {
+                    break;
+                }
+                let mut pending = refreshing.write();
+                if pending.get(&key) == Some(&true) {
+                    pending.insert(key.clone(), false);
+                } else {
+                    pending.remove(&key);
+                    break;
+                }
+            }
+        });
+    }
+
+    async fn load_containers(&self, engine: &str, service: &SharedEngine) {
+        let result = service.list_containers().await;
+        if !self.is_current(engine, service) {
+            return;
+        }
+        match result {
+            Ok(data) => {
+                replace_engine_items(self.containers, engine, data);
+                self.set_engine_status(engine, None, EngineStatus::Connected);
+                self.loaded(engine, Listing::Containers);
+            }
+            Err(e) => {
+                self.load_failed(engine, Listing::Containers, &e);
+                let message = describe_error(&e);
+                self.set_engine_status(engine, None, EngineStatus::Unreachable(message));
+            }
         }
     }
 
-    pub fn start_container(&self, id: String) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut last_action = self.last_action.clone();
-            let mut error_message = self.error_message.clone();
-            let id_clone = id.clone();
-            let app_state = self.clone();
+    async fn load_images(&self, engine: &str, service: &SharedEngine) {
+        let result = service.list_images().await;
+        if !self.is_current(engine, service) {
+            return;
+        }
+        match result {
+            Ok(data) => {
+                replace_engine_items(self.images, engine, data);
+                self.loaded(engine, Listing::Images);
+            }
+            Err(e) => self.load_failed(engine, Listing::Images, &e),
+        }
+    }
 
-            spawn(async move {
-                match service.start_container(&id_clone).await {
-                    Ok(_) => {
-                        last_action.set(Some(format!("Started container {}", id_clone)));
-                        error_message.set(None);
-                        // Refresh containers to get updated state
-                        app_state.refresh_containers();
-                    }
-                    Err(e) => {
-                        error_message.set(Some(format!("Failed to start container: {}", e)));
-                    }
+    async fn load_volumes(&self, engine: &str, service: &SharedEngine) {
+        let result = service.list_volumes().await;
+        if !self.is_current(engine, service) {
+            return;
+        }
+        match result {
+            Ok(data) => {
+                replace_engine_items(self.volumes, engine, data);
+                self.loaded(engine, Listing::Volumes);
+            }
+            Err(e) => self.load_failed(engine, Listing::Volumes, &e),
+        }
+    }
+
+    fn loaded(&self, engine: &str, list: Listing) {
+        self.loads
+            .clone()
+            .write()
+            .set(engine, list, LoadState::Loaded(unix_now()));
+        self.resolve(&load_source(engine, list));
+    }
+
+    fn load_failed(&self, engine: &str, list: Listing, error: &anyhow::Error) {
+        let state = LoadState::Failed {
+            at: unix_now(),
+            message: describe_error(error),
+        };
+        self.loads.clone().write().set(engine, list, state);
+        let context = format!("Failed to list {} on {engine}", list.noun());
+        self.notify(
+            Notification::engine_error(&context, error).with_source(load_source(engine, list)),
+        );
+    }
+
+    /// Reloads every list each `refresh_interval_secs` while `auto_refresh`
+    /// is on and the window is visible. Changes to either setting apply from
+    /// the next tick.
+    fn poll(&self) {
+        let app_state = self.clone();
+
+        spawn_forever(async move {
+            loop {
+                let interval = app_state.config.peek().refresh_interval_secs;
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let enabled = app_state.config.peek().auto_refresh;
+                if enabled && *app_state.window_visible.peek() {
+                    app_state.refresh_in_background();
                 }
-            });
+            }
+        });
+    }
+
+    /// Called when the window is shown or hidden. Lists are reloaded on
+    /// return, since polling skipped them while hidden.
+    pub fn set_window_visible(&self, visible: bool) {
+        let mut window_visible = self.window_visible;
+        let was_visible = *window_visible.peek();
+        window_visible.set(visible);
+        if visible && !was_visible && self.config.peek().auto_refresh {
+            self.refresh_in_background();
         }
     }
 
-    pub fn stop_container(&self, id: String) {
-        if let Some(service) = &self.docker_service {
-            let service = service.clone();
-            let mut last_action = self.last_action.clone();
-            let mut error_message = self.error_message.clone();
-            let id_clone = id.clone();
-            let app_state = self.clone();
+    /// Runs `action` on container `id` of `engine`, then re-reads its row so
+    /// it shows the new state without waiting for the event. While another
+    /// action runs on the container the request is dropped, and
+    /// `last_action` says so.
+    pub fn run_container_action(&self, engine: &str, id: &str, action: ContainerAction) {
+        let Some(service) = self.service(engine) else {
+            return;
+        };
+        let mut busy_containers = self.busy_containers;
+        let key = (engine.to_string(), id.to_string());
+        if !busy_containers.write().insert(key.clone()) {
+            self.record_action(format!(
+                "{} container {id}: ignored, another action is still running on it",
+                action.label()
+            ));
+            return;
+        }
+        let mut last_action = self.last_action;
+        let engine = engine.to_string();
+        let id = id.to_string();
+        let app_state = self.clone();
 
-            spawn(async move {
-                match service.stop_container(&id_clone).await {
-                    Ok(_) => {
-                        last_action.set(Some(format!("Stopped container {}", id_clone)));
-                        error_message.set(None);
-                        // Refresh containers to get updated state
-                        app_state.refresh_containers();
+        // Outlives the calling view, so the row is not left marked busy
+        spawn_forever(async move {
+            let result = service.run_action(&id, action).await;
+            busy_containers.write().remove(&key);
+            match result {
+                Ok(()) => {
+                    last_action.set(Some(format!("{} container {}", action.done_label(), id)));
+                    if let ContainerAction::Remove(options) = action
+                        && options.volumes
+                    {
+                        app_state.refresh_engine_volumes(engine.clone(), service.clone());
                     }
+                    app_state.sync_container(engine, service, id);
+                }
+                Err(e) => {
+                    let context =
+                        format!("Failed to {} container {id}", action.label().to_lowercase());
+                    app_state.notify(Notification::engine_error(&context, &e));
+                }
+            }
+        });
+    }
+
+    /// Runs `action` on every target container.
+    pub fn run_bulk_container_action(&self, targets: Vec<BulkTarget>, action: ContainerAction) {
+        self.run_bulk(
+            BulkResource::Containers,
+            action.label(),
+            targets,
+            move |service, id| async move { service.run_action(&id, action).await }.boxed(),
+        );
+    }
+
+    pub fn remove_images(&self, targets: Vec<BulkTarget>, options: ImageRemoveOptions) {
+        self.run_bulk(
+            BulkResource::Images,
+            "Remove",
+            targets,
+            move |service, id| async move { service.remove_image(&id, options).await }.boxed(),
+        );
+    }
+
+    /// Adds the tag `reference`, written `repository[:tag]`, to image `id` of
+    /// `engine`. An invalid reference is returned as an error right away.
+    pub fn tag_image(&self, engine: &str, id: &str, reference: &str) -> anyhow::Result<()> {
+        let (repository, tag) = split_tag(reference)?;
+        let Some(service) = self.service(engine) else {
+            anyhow::bail!("Engine {engine} is not connected");
+        };
+        let id = id.to_string();
+        let engine = engine.to_string();
+        let app_state = self.clone();
+
+        spawn_forever(async move {
+            match service.tag_image(&id, &repository, &tag).await {
+                Ok(()) => {
+                    app_state.record_action(format!("Tagged {repository}:{tag}"));
+                    app_state.refresh_engine_images(engine, service);
+                }
+                Err(e) => app_state.notify(Notification::engine_error("Failed to tag image", &e)),
+            }
+        });
+        Ok(())
+    }
+
+    /// Removes the tag `reference` from its image on `engine`. The engine
+    /// deletes the image along with its last tag.
+    pub fn remove_tag(&self, engine: &str, reference: &str) {
+        let Some(service) = self.service(engine) else {
+            return;
+        };
+        let reference = reference.to_string();
+        let engine = engine.to_string();
+        let app_state = self.clone();
+
+        spawn_forever(async move {
+            let options = ImageRemoveOptions::default();
+            match service.remove_image(&reference, options).await {
+                Ok(()) => {
+                    app_state.record_action(format!("Removed tag {reference}"));
+                    app_state.refresh_engine_images(engine, service);
+                }
+                Err(e) => {
+                    let context = format!("Failed to remove tag {reference}");
+                    app_state.notify(Notification::engine_error(&context, &e));
+                }
+            }
+        });
+    }
+
+    /// Prunes the images in `scope` on each of `engines` that is connected,
+    /// then reports what was deleted and the space reclaimed.
+    pub fn prune_images(&self, engines: &[String], scope: PruneScope) {
+        let services: Vec<(String, SharedEngine)> = self
+            .connected_services()
+            .into_iter()
+            .filter(|(engine, _)| engines.contains(engine))
+            .collect();
+        let app_state = self.clone();
+
+        spawn_forever(async move {
+            let mut total = PruneReport::default();
+            for (engine, service) in services {
+                match service.prune_images(scope).await {
+                    Ok(report) => total.merge(report),
                     Err(e) => {
-                        error_message.set(Some(format!("Failed to stop container: {}", e)));
+                        let context = format!("Failed to prune images on {engine}");
+                        app_state.notify(Notification::engine_error(&context, &e));
                     }
                 }
-            });
-        }
+                app_state.refresh_engine_images(engine, service);
+            }
+            let summary = format!(
+                "Deleted {} images and layers, reclaimed {}",
+                total.deleted.len(),
+                format_bytes(total.reclaimed_bytes)
+            );
+            app_state.record_action(format!("{}: {summary}", scope.label()));
+            app_state.notify(Notification::new(Severity::Info, scope.label(), summary));
+        });
+    }
+
+    pub fn remove_volumes(&self, targets: Vec<BulkTarget>) {
+        self.run_bulk(BulkResource::Volumes, "Remove", targets, |service, id| {
+            async move { service.remove_volume(&id).await }.boxed()
+        });
     }
 
-    pub fn set_container_state(&self, id: &str, next_state: ContainerState) {
-        match next_state {
-            ContainerState::Running => self.start_container(id.to_string()),
-            ContainerState::Stopped => self.stop_container(id.to_string()),
+    /// Calls `call` for every target on its engine, a few at a time, and
+    /// records each outcome in `bulk_report` as it arrives. The engines
+    /// involved are refreshed once all calls have finished. A request made
+    /// while another bulk action runs is queued behind it. Containers are
+    /// marked busy until the action finishes; those already busy fail
+    /// without a call.
+    fn run_bulk<F>(&self, resource: BulkResource, action: &str, targets: Vec<BulkTarget>, call: F)
+    where
+        F: Fn(SharedEngine, String) -> BoxFuture<'static, anyhow::Result<()>> + 'static,
+    {
+        if targets.is_empty() {
+            return;
         }
+        let mut busy_containers = self.busy_containers;
+        let mut claimed = HashSet::new();
+        if resource == BulkResource::Containers {
+            let mut busy = busy_containers.write();
+            for target in &targets {
+                let key = (target.engine.clone(), target.id.clone());
+                if busy.insert(key.clone()) {
+                    claimed.insert(key);
+                }
+            }
+        }
+        let bulk_turn = self.bulk_turn.clone();
+        if bulk_turn.try_lock().is_err() {
+            self.record_action(format!(
+                "{action} {}: queued behind the running bulk action",
+                resource.noun()
+            ));
+        }
+        let mut bulk_report = self.bulk_report;
+        let mut engines: Vec<String> = targets.iter().map(|t| t.engine.clone()).collect();
+        engines.sort();
+        engines.dedup();
+        let action = action.to_string();
+        let app_state = self.clone();
+
+        // Outlives the calling view, so the containers are not left marked busy
+        spawn_forever(async move {
+            let _turn = bulk_turn.lock().await;
+            bulk_report.set(Some(BulkReport::new(resource, &action, targets.clone())));
+            let services = app_state.services.peek().clone();
+            let outcomes = run_bounded(targets, BULK_CONCURRENCY, |target| {
+                let key = (target.engine.clone(), target.id.clone());
+                let call = services
+                    .get(&target.engine)
+                    .map(|service| call(service.clone(), target.id.clone()));
+                let claimed = resource != BulkResource::Containers || claimed.contains(&key);
+                async move {
+                    match call {
+                        _ if !claimed => anyhow::bail!("Another action is still running on it"),
+                        Some(call) => call.await,
+                        None => anyhow::bail!("Engine {} is not connected", target.engine),
+                    }
+                }
+            });
+            let mut outcomes = std::pin::pin!(outcomes);
+            while let Some((index, status)) = outcomes.next().await {
+                if let Some(item) = bulk_report
+                    .write()
+                    .as_mut()
+                    .and_then(|report| report.items.get_mut(index))
+                {
+                    item.status = status;
+                }
+            }
+
+            busy_containers.write().retain(|key| !claimed.contains(key));
+            if let Some(report) = bulk_report.peek().as_ref() {
+                app_state.record_action(report.summary());
+            }
+            for engine in engines {
+                app_state.refresh_engine(&engine);
+            }
+        });
     }
 
     pub fn record_action(&self, message: impl Into<String>) {
-        let mut last_action_signal = self.last_action.clone();
+        let mut last_action_signal = self.last_action;
         last_action_signal.set(Some(message.into()));
     }
 }
+
+/// Whether a reload shows in the load indicator: a refresh the user asked
+/// for does, while polls and event catch-ups keep showing the last update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Reload {
+    Shown,
+    Background,
+}
+
+/// Source of the notification a failed load of `list` raises.
+fn load_source(engine: &str, list: Listing) -> String {
+    format!("{}:{engine}", list.noun())
+}
+
+/// Resources that remember which engine they were listed from.
+trait EngineItem {
+    fn engine(&self) -> &str;
+    fn set_engine(&mut self, engine: &str);
+}
+
+impl EngineItem for ContainerInfo {
+    fn engine(&self) -> &str {
+        &self.engine
+    }
+
+    fn set_engine(&mut self, engine: &str) {
+        self.engine = engine.to_string();
+    }
+}
+
+impl EngineItem for ImageInfo {
+    fn engine(&self) -> &str {
+        &self.engine
+    }
+
+    fn set_engine(&mut self, engine: &str) {
+        self.engine = engine.to_string();
+    }
+}
+
+impl EngineItem for VolumeInfo {
+    fn engine(&self) -> &str {
+        &self.engine
+    }
+
+    fn set_engine(&mut self, engine: &str) {
+        self.engine = engine.to_string();
+    }
+}
+
+/// Swaps one engine's entries in `signal` for `items`, leaving the entries of
+/// other engines as they are.
+fn replace_engine_items<T: EngineItem + 'static>(
+    mut signal: Signal<Vec<T>>,
+    engine: &str,
+    mut items: Vec<T>,
+) {
+    for item in &mut items {
+        item.set_engine(engine);
+    }
+    let mut all = signal.write();
+    all.retain(|item| item.engine() != engine);
+    all.extend(items);
+}
+
+fn load_contexts(warnings: &mut Vec<String>) -> DockerContexts {
+    let contexts = DockerContexts::load().unwrap_or_else(|e| {
+        warnings.push(format!("Failed to read Docker contexts: {e:#}"));
+        DockerContexts::default()
+    });
+    warnings.extend(contexts.skipped.iter().cloned());
+    contexts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        ContainerState, FakeEngine, ImageReference, Operation, RemoveOptions, container,
+    };
+    use crate::utils::BulkStatus;
+
+    fn app() -> Element {
+        let state =
+            AppState::from_parts(AppConfig::default(), DockerContexts::default(), Vec::new());
+        use_context_provider(|| state);
+        rsx! {}
+    }
+
+    fn fake_endpoint(name: &str) -> Endpoint {
+        Endpoint {
+            host: format!("unix:///tmp/{name}.sock"),
+            tls: None,
+        }
+    }
+
+    fn with_state<T>(dom: &VirtualDom, f: impl FnOnce(AppState) -> T) -> T {
+        dom.in_scope(ScopeId::APP, || f(consume_context::<AppState>()))
+    }
+
+    /// Drives spawned tasks until nothing is left to do.
+    async fn settle(dom: &mut VirtualDom) {
+        for _ in 0..20 {
+            let work = tokio::time::timeout(Duration::from_millis(50), dom.wait_for_work());
+            if work.await.is_err() {
+                break;
+            }
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_tags_items_and_isolates_failing_engines() {
+        let primary = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_image(ImageInfo {
+                engine: String::new(),
+                id: "sha256:1".to_string(),
+                tags: vec![ImageReference::parse("nginx:latest").unwrap()],
+                digests: Vec::new(),
+                size: "1.0MB".to_string(),
+                size_bytes: 1_000_000,
+                created: 0,
+                labels: Default::default(),
+            })
+            .with_volume(VolumeInfo {
+                engine: String::new(),
+                name: "data".to_string(),
+                driver: "local".to_string(),
+                mountpoint: "/var/lib/docker/volumes/data".to_string(),
+                size: "--".to_string(),
+                created: None,
+                labels: Default::default(),
+            });
+        let build = FakeEngine::new().with_container(container("def456", "worker"));
+        build.fail(Operation::ListContainers, "connection refused");
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(PRIMARY_ENGINE, &fake_endpoint("primary"), Arc::new(primary));
+            state.attach_engine("build", &fake_endpoint("build"), Arc::new(build.clone()));
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            let containers = state.containers.peek();
+            assert_eq!(containers.len(), 1);
+            assert_eq!(containers[0].engine, PRIMARY_ENGINE);
+            assert_eq!(state.images.peek()[0].engine, PRIMARY_ENGINE);
+            assert_eq!(state.volumes.peek()[0].engine, PRIMARY_ENGINE);
+
+            let engines = state.engines.peek();
+            assert_eq!(engines[0].status, EngineStatus::Connected);
+            assert_eq!(
+                engines[1].status,
+                EngineStatus::Unreachable("connection refused".to_string())
+            );
+
+            let notifications = state.notifications.peek();
+            let failure = notifications.iter().next().unwrap();
+            assert_eq!(notifications.len(), 1);
+            assert_eq!(failure.title, "Engine unreachable");
+            assert_eq!(failure.source.as_deref(), Some("containers:build"));
+        });
+
+        build.recover(Operation::ListContainers);
+        with_state(&dom, |state| state.refresh_containers());
+        settle(&mut dom).await;
+        with_state(&dom, |state| {
+            assert_eq!(state.containers.peek().len(), 2);
+            assert_eq!(state.engines.peek()[1].status, EngineStatus::Connected);
+            assert!(state.notifications.peek().is_empty());
+        });
+    }
+
+    #[tokio::test]
+    async fn no_engine_warning_clears_once_an_engine_connects() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.refresh_containers();
+            state.refresh_containers();
+            let notifications = state.notifications.peek();
+            assert_eq!(notifications.len(), 1);
+            assert_eq!(
+                notifications.iter().next().unwrap().source.as_deref(),
+                Some(NO_ENGINE_SOURCE)
+            );
+        });
+
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(FakeEngine::new()),
+            );
+        });
+        settle(&mut dom).await;
+        with_state(&dom, |state| assert!(state.notifications.peek().is_empty()));
+    }
+
+    #[tokio::test]
+    async fn starting_a_container_refreshes_its_state() {
+        let engine = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_latency(Duration::from_millis(5));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(engine.clone()),
+            );
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            assert!(matches!(
+                state.loads.peek().of(Listing::Containers),
+                LoadState::Loaded(_)
+            ));
+            // A second click while the first is in flight is ignored
+            state.run_container_action(PRIMARY_ENGINE, "abc123", ContainerAction::Start);
+            state.run_container_action(PRIMARY_ENGINE, "abc123", ContainerAction::Start);
+            assert_eq!(state.busy_containers.peek().len(), 1);
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            assert_eq!(state.containers.peek()[0].state, ContainerState::Running);
+            assert_eq!(
+                state.last_action.peek().as_deref(),
+                Some("Started container abc123")
+            );
+            assert!(state.busy_containers.peek().is_empty());
+        });
+        let starts = engine
+            .calls()
+            .into_iter()
+            .filter(|call| *call == Operation::StartContainer)
+            .count();
+        assert_eq!(starts, 1);
+    }
+
+    #[tokio::test]
+    async fn bulk_actions_report_each_item() {
+        let engine = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_container(container("def456", "db"));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(engine.clone()),
+            );
+        });
+        settle(&mut dom).await;
+
+        let target = |id: &str| BulkTarget {
+            engine: PRIMARY_ENGINE.to_string(),
+            id: id.to_string(),
+            name: id.to_string(),
+        };
+        with_state(&dom, |state| {
+            let targets = vec![target("abc123"), target("missing"), target("def456")];
+            state.run_bulk_container_action(targets, ContainerAction::Start)
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            let report = state.bulk_report.peek().clone().unwrap();
+            assert_eq!(report.summary(), "Start: 2 of 3 containers done, 1 failed");
+            assert_eq!(
+                report.items[1].status,
+                BulkStatus::Failed("No such container: missing".to_string())
+            );
+            let containers = state.containers.peek();
+            assert!(
+                containers
+                    .iter()
+                    .all(|c| c.state == ContainerState::Running)
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn bulk_actions_queue_and_mark_containers_busy() {
+        let engine = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_container(container("def456", "db"))
+            .with_latency(Duration::from_millis(5));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(engine.clone()),
+            );
+        });
+        settle(&mut dom).await;
+
+        let target = |id: &str| BulkTarget {
+            engine: PRIMARY_ENGINE.to_string(),
+            id: id.to_string(),
+            name: id.to_string(),
+        };
+        with_state(&dom, |state| {
+            state.run_bulk_container_action(vec![target("abc123")], ContainerAction::Start);
+            state.run_bulk_container_action(vec![target("def456")], ContainerAction::Start);
+            assert_eq!(state.busy_containers.peek().len(), 2);
+            // A single action on a container the bulk action holds is dropped
+            state.run_container_action(PRIMARY_ENGINE, "abc123", ContainerAction::Stop);
+            assert_eq!(
+                state.last_action.peek().as_deref(),
+                Some("Stop container abc123: ignored, another action is still running on it")
+            );
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            let report = state.bulk_report.peek().clone().unwrap();
+            assert_eq!(report.items[0].target.id, "def456");
+            assert_eq!(report.summary(), "Start: 1 of 1 containers done");
+            assert!(state.busy_containers.peek().is_empty());
+            assert!(
+                state
+                    .containers
+                    .peek()
+                    .iter()
+                    .all(|c| c.state == ContainerState::Running)
+            );
+        });
+        assert!(!engine.calls().contains(&Operation::StopContainer));
+    }
+
+    #[tokio::test]
+    async fn prunes_only_the_engines_asked_for() {
+        let primary = FakeEngine::new();
+        let build = FakeEngine::new();
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(primary.clone()),
+            );
+            state.attach_engine("build", &fake_endpoint("build"), Arc::new(build.clone()));
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            state.prune_images(&["build".to_string()], PruneScope::Dangling)
+        });
+        settle(&mut dom).await;
+
+        assert!(!primary.calls().contains(&Operation::PruneImages));
+        assert!(build.calls().contains(&Operation::PruneImages));
+        with_state(&dom, |state| {
+            assert_eq!(
+                state.last_action.peek().as_deref(),
+                Some("Prune dangling: Deleted 0 images and layers, reclaimed 0B")
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn events_update_lists_and_resync_after_reconnect() {
+        let engine = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_container(container("def456", "db"));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(engine.clone()),
+            );
+        });
+        settle(&mut dom).await;
+
+        // The stream is open before the first load
+        let calls = engine.calls();
+        let position = |operation| calls.iter().position(|c| *c == operation);
+        assert!(position(Operation::Events) < position(Operation::ListContainers));
+
+        // Changes made by another client arrive as events
+        engine.start_container("web").await.unwrap();
+        let force = RemoveOptions {
+            force: true,
+            volumes: false,
+        };
+        engine.remove_container("db", force).await.unwrap();
+        settle(&mut dom).await;
+        with_state(&dom, |state| {
+            let containers = state.containers.peek();
+            assert_eq!(containers.len(), 1);
+            assert_eq!(containers[0].state, ContainerState::Running);
+        });
+        let lists = |engine: &FakeEngine| {
+            let calls = engine.calls();
+            calls
+                .iter()
+                .filter(|c| **c == Operation::ListContainers)
+                .count()
+        };
+        assert_eq!(lists(&engine), 1);
+
+        // Changes missed while the stream is down are caught up on resubscribe
+        engine.drop_events();
+        engine.stop_container("web").await.unwrap();
+        settle(&mut dom).await;
+        tokio::time::pause();
+        tokio::time::sleep(EVENTS_RETRY + Duration::from_millis(100)).await;
+        tokio::time::resume();
+        settle(&mut dom).await;
+        with_state(&dom, |state| {
+            assert_eq!(
+                state.containers.peek()[0].state,
+                ContainerState::Exited(Some(0))
+            );
+        });
+        assert_eq!(lists(&engine), 2);
+        assert_eq!(
+            engine
+                .calls()
+                .iter()
+                .filter(|c| **c == Operation::Events)
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn replaced_and_removed_engines_stop_watching_events() {
+        let old = FakeEngine::new().with_container(container("abc123", "web"));
+        let new = FakeEngine::new().with_container(container("def456", "db"));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(PRIMARY_ENGINE, &fake_endpoint("old"), Arc::new(old.clone()));
+        });
+        settle(&mut dom).await;
+        assert_eq!(old.open_streams(), 1);
+
+        with_state(&dom, |state| {
+            state.attach_engine(PRIMARY_ENGINE, &fake_endpoint("new"), Arc::new(new.clone()));
+        });
+        settle(&mut dom).await;
+        assert_eq!(old.open_streams(), 0);
+        assert_eq!(new.open_streams(), 1);
+
+        with_state(&dom, |state| state.remove_engine(PRIMARY_ENGINE));
+        settle(&mut dom).await;
+        assert_eq!(new.open_streams(), 0);
+    }
+
+    #[tokio::test]
+    async fn only_first_and_manual_loads_show_loading() {
+        let engine = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_latency(Duration::from_millis(5));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(engine.clone()),
+            );
+            assert!(state.loads.peek().of(Listing::Containers).is_loading());
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            state.refresh_in_background();
+            assert!(matches!(
+                state.loads.peek().of(Listing::Containers),
+                LoadState::Loaded(_)
+            ));
+            state.refresh_containers();
+            assert!(state.loads.peek().of(Listing::Containers).is_loading());
+        });
+        settle(&mut dom).await;
+        with_state(&dom, |state| {
+            assert!(matches!(
+                state.loads.peek().of(Listing::Containers),
+                LoadState::Loaded(_)
+            ));
+        });
+    }
+
+    #[tokio::test]
+    async fn overlapping_refreshes_are_coalesced() {
+        let engine = FakeEngine::new()
+            .with_container(container("abc123", "web"))
+            .with_latency(Duration::from_millis(20));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(
+                PRIMARY_ENGINE,
+                &fake_endpoint("primary"),
+                Arc::new(engine.clone()),
+            );
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            for _ in 0..3 {
+                state.refresh_containers();
+            }
+        });
+        settle(&mut dom).await;
+
+        let lists = engine
+            .calls()
+            .into_iter()
+            .filter(|c| *c == Operation::ListContainers)
+            .count();
+        // The first load, then one reload plus one for the two coalesced requests
+        assert_eq!(lists, 3);
+        with_state(&dom, |state| assert!(state.refreshing.peek().is_empty()));
+    }
+
+    #[tokio::test]
+    async fn replacing_an_engine_drops_its_running_reload() {
+        let old = FakeEngine::new()
+            .with_container(container("abc123", "old-host"))
+            .with_latency(Duration::from_millis(20));
+        let new = FakeEngine::new().with_container(container("def456", "new-host"));
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        with_state(&dom, |state| {
+            state.attach_engine(PRIMARY_ENGINE, &fake_endpoint("old"), Arc::new(old.clone()));
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            // Swap the engine while a reload from the old one is in flight
+            state.refresh_containers();
+            state.clear_engine(PRIMARY_ENGINE);
+            assert!(state.refreshing.peek().is_empty());
+            state.attach_engine(PRIMARY_ENGINE, &fake_endpoint("new"), Arc::new(new));
+        });
+        settle(&mut dom).await;
+
+        with_state(&dom, |state| {
+            let names: Vec<String> = state
+                .containers
+                .peek()
+                .iter()
+                .map(|c| c.name.clone())
+                .collect();
+            assert_eq!(names, ["new-host"]);
+            assert!(state.refreshing.peek().is_empty());
+        });
+    }
+}
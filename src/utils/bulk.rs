@@ -0,0 +1,193 @@
+//! Actions run on many selected items at once, with a result per item.
+
+use std::future::Future;
+
+use futures_util::{Stream, StreamExt, stream};
+
+use crate::services::describe_error;
+
+/// Engine calls a bulk action keeps in flight at the same time.
+pub const BULK_CONCURRENCY: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BulkResource {
+    Containers,
+    Images,
+    Volumes,
+}
+
+impl BulkResource {
+    pub fn noun(&self) -> &'static str {
+        match self {
+            BulkResource::Containers => "containers",
+            BulkResource::Images => "images",
+            BulkResource::Volumes => "volumes",
+        }
+    }
+}
+
+/// One selected item: what the engine is called with, and what to show.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulkTarget {
+    pub engine: String,
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BulkStatus {
+    Pending,
+    Done,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulkItem {
+    pub target: BulkTarget,
+    pub status: BulkStatus,
+}
+
+/// Progress and outcome of one bulk action, item by item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulkReport {
+    pub resource: BulkResource,
+    /// Label of the action, e.g. `Stop`.
+    pub action: String,
+    pub items: Vec<BulkItem>,
+}
+
+impl BulkReport {
+    pub fn new(resource: BulkResource, action: &str, targets: Vec<BulkTarget>) -> Self {
+        Self {
+            resource,
+            action: action.to_string(),
+            items: targets
+                .into_iter()
+                .map(|target| BulkItem {
+                    target,
+                    status: BulkStatus::Pending,
+                })
+                .collect(),
+        }
+    }
+
+    fn count(&self, wanted: impl Fn(&BulkStatus) -> bool) -> usize {
+        self.items
+            .iter()
+            .filter(|item| wanted(&item.status))
+            .count()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.count(|status| *status == BulkStatus::Pending)
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.count(|status| *status == BulkStatus::Done)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|status| matches!(status, BulkStatus::Failed(_)))
+    }
+
+    /// `Stop: 8 of 10 containers done, 2 failed`.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{}: {} of {} {} done",
+            self.action,
+            self.succeeded(),
+            self.items.len(),
+            self.resource.noun()
+        );
+        if self.failed() > 0 {
+            summary.push_str(&format!(", {} failed", self.failed()));
+        }
+        if self.pending() > 0 {
+            summary.push_str(&format!(", {} in progress", self.pending()));
+        }
+        summary
+    }
+}
+
+/// Calls `call` for every target, at most `limit` at a time. Yields the index
+/// of each target with its outcome as the calls finish, in any order.
+pub fn run_bounded<F, Fut>(
+    targets: Vec<BulkTarget>,
+    limit: usize,
+    call: F,
+) -> impl Stream<Item = (usize, BulkStatus)>
+where
+    F: Fn(BulkTarget) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    stream::iter(targets.into_iter().enumerate())
+        .map(move |(index, target)| {
+            let call = call(target);
+            async move {
+                match call.await {
+                    Ok(()) => (index, BulkStatus::Done),
+                    Err(e) => (index, BulkStatus::Failed(describe_error(&e))),
+                }
+            }
+        })
+        .buffer_unordered(limit.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use anyhow::bail;
+
+    use super::*;
+
+    fn targets(count: usize) -> Vec<BulkTarget> {
+        (0..count)
+            .map(|n| BulkTarget {
+                engine: "primary".to_string(),
+                id: format!("id{n}"),
+                name: format!("item{n}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn runs_with_bounded_concurrency() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut report = BulkReport::new(BulkResource::Containers, "Stop", targets(10));
+
+        let outcomes: Vec<_> = run_bounded(targets(10), 3, |target| {
+            let running = running.clone();
+            let peak = peak.clone();
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                if target.id == "id4" {
+                    bail!("No such container: {}", target.id);
+                }
+                Ok(())
+            }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            report.summary(),
+            "Stop: 0 of 10 containers done, 10 in progress"
+        );
+        for (index, status) in outcomes {
+            report.items[index].status = status;
+        }
+        assert_eq!(report.summary(), "Stop: 9 of 10 containers done, 1 failed");
+        assert_eq!(
+            report.items[4].status,
+            BulkStatus::Failed("No such container: id4".to_string())
+        );
+    }
+}
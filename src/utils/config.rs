@@ -0,0 +1,377 @@
+//! User preferences persisted as JSON in the platform config directory.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Route;
+use crate::services::{DockerContexts, DockerHost, Endpoint, TlsOptions, default_docker_host};
+use crate::utils::PRIMARY_ENGINE;
+
+/// Version written to new config files. Bump it and add a step to
+/// [`migrate`] whenever the on-disk layout changes.
+pub const CONFIG_VERSION: u64 = 2;
+
+const MIN_REFRESH_SECS: u64 = 2;
+const MAX_REFRESH_SECS: u64 = 3600;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Dark, Theme::Light];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Theme::Dark => "theme-dark",
+            Theme::Light => "theme-light",
+        }
+    }
+}
+
+/// Page shown when the app starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartPage {
+    #[default]
+    Dashboard,
+    Containers,
+    Images,
+    Volumes,
+}
+
+impl StartPage {
+    pub const ALL: [StartPage; 4] = [
+        StartPage::Dashboard,
+        StartPage::Containers,
+        StartPage::Images,
+        StartPage::Volumes,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StartPage::Dashboard => "Dashboard",
+            StartPage::Containers => "Containers",
+            StartPage::Images => "Images",
+            StartPage::Volumes => "Volumes",
+        }
+    }
+
+    pub fn route(&self) -> Route {
+        match self {
+            StartPage::Dashboard => Route::Dashboard {},
+            StartPage::Containers => Route::Containers {},
+            StartPage::Images => Route::Images {},
+            StartPage::Volumes => Route::Volumes {},
+        }
+    }
+}
+
+/// Optional table columns. Identifying columns (names, state) are always shown.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnPreferences {
+    pub container_image: bool,
+    pub container_ports: bool,
+    /// Live CPU, memory and I/O of running containers.
+    pub container_stats: bool,
+    pub image_id: bool,
+    pub image_size: bool,
+    pub volume_driver: bool,
+    pub volume_mountpoint: bool,
+}
+
+impl Default for ColumnPreferences {
+    fn default() -> Self {
+        Self {
+            container_image: true,
+            container_ports: true,
+            container_stats: true,
+            image_id: true,
+            image_size: true,
+            volume_driver: true,
+            volume_mountpoint: true,
+        }
+    }
+}
+
+/// An additional engine managed side by side with the primary connection.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub endpoint: Endpoint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub version: u64,
+    /// Connect to the Docker CLI's current context instead of `docker_host`.
+    pub use_docker_context: bool,
+    pub docker_host: String,
+    /// Client certificates for `tcp://` hosts that require mutual TLS.
+    pub tls: Option<TlsOptions>,
+    /// Extra engines (build servers, VMs, ...) shown next to the primary one.
+    pub engines: Vec<EngineConfig>,
+    /// Seconds between background reloads of every list.
+    pub refresh_interval_secs: u64,
+    /// Reload lists every `refresh_interval_secs`, on top of engine events.
+    pub auto_refresh: bool,
+    pub theme: Theme,
+    pub start_page: StartPage,
+    pub columns: ColumnPreferences,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            use_docker_context: true,
+            docker_host: default_docker_host(),
+            tls: TlsOptions::from_env(),
+            engines: Vec::new(),
+            refresh_interval_secs: 10,
+            auto_refresh: true,
+            theme: Theme::default(),
+            start_page: StartPage::default(),
+            columns: ColumnPreferences::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Location of the config file, e.g. `~/.config/doctainr/settings.json`.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("doctainr").join("settings.json"))
+    }
+
+    /// Loads the config file, falling back to defaults when it does not exist.
+    /// Problems that were corrected while validating are returned as warnings.
+    pub fn load() -> Result<(Self, Vec<String>)> {
+        let Some(path) = Self::path() else {
+            return Ok((Self::default(), Vec::new()));
+        };
+        if !path.exists() {
+            return Ok((Self::default(), Vec::new()));
+        }
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_json(&raw).with_context(|| format!("Invalid settings in {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().context("No config directory on this platform")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        // Write to a sibling file first so a crash never leaves a truncated config
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    fn from_json(raw: &str) -> Result<(Self, Vec<String>)> {
+        let value: Value = serde_json::from_str(raw)?;
+        let config: AppConfig = serde_json::from_value(migrate(value)?)?;
+        Ok(config.validated())
+    }
+
+    pub fn endpoint(&self) -> Endpoint {
+        Endpoint {
+            host: self.docker_host.clone(),
+            tls: self.tls.clone(),
+        }
+    }
+
+    /// Picks the endpoint to connect to and the name of the context it came
+    /// from: the CLI's current context when following contexts, otherwise the
+    /// host entered in Settings.
+    pub fn resolve_endpoint(&self, contexts: &DockerContexts) -> (Endpoint, Option<String>) {
+        if self.use_docker_context
+            && let Some(context) = contexts.current_context()
+        {
+            return (context.endpoint.clone(), Some(context.name.clone()));
+        }
+        (self.endpoint(), None)
+    }
+
+    /// Replaces out-of-range values with defaults, describing each fix.
+    /// Missing certificate files are reported but left in place.
+    pub fn validated(mut self) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        if let Some(tls) = &self.tls
+            && let Err(e) = tls.check()
+        {
+            warnings.push(e.to_string());
+        }
+
+        if let Err(e) = DockerHost::parse(&self.docker_host) {
+            let fallback = default_docker_host();
+            warnings.push(format!("Ignoring saved Docker host: {e}; using {fallback}"));
+            self.docker_host = fallback;
+        }
+
+        let mut names = vec![PRIMARY_ENGINE.to_string()];
+        self.engines.retain_mut(|engine| {
+            let name = engine.name.trim().to_string();
+            let problem = if name.is_empty() {
+                Some("has no name".to_string())
+            } else if names.contains(&name) {
+                Some("reuses a name that is already taken".to_string())
+            } else {
+                DockerHost::parse(&engine.endpoint.host)
+                    .err()
+                    .map(|e| format!("has an invalid host: {e}"))
+            };
+            match problem {
+                Some(problem) => {
+                    warnings.push(format!("Ignoring engine \"{name}\", which {problem}"));
+                    false
+                }
+                None => {
+                    engine.name = name.clone();
+                    names.push(name);
+                    true
+                }
+            }
+        });
+
+        let clamped = self
+            .refresh_interval_secs
+            .clamp(MIN_REFRESH_SECS, MAX_REFRESH_SECS);
+        if clamped != self.refresh_interval_secs {
+            warnings.push(format!(
+                "Refresh interval must be between {MIN_REFRESH_SECS} and {MAX_REFRESH_SECS} seconds; using {clamped}"
+            ));
+            self.refresh_interval_secs = clamped;
+        }
+
+        (self, warnings)
+    }
+}
+
+/// Upgrades a config document written by an older release to the current
+/// layout, one version at a time.
+fn migrate(mut value: Value) -> Result<Value> {
+    let Some(object) = value.as_object_mut() else {
+        anyhow::bail!("Expected a JSON object");
+    };
+
+    // Every file Doctainr writes is versioned, starting at 1. One written by
+    // hand without a version is read as current, with defaults for the rest.
+    let mut version = match object.get("version") {
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .with_context(|| format!("Invalid settings version {version}"))?,
+        None => CONFIG_VERSION,
+    };
+    if version > CONFIG_VERSION {
+        anyhow::bail!("Settings were written by a newer version of Doctainr (v{version})");
+    }
+
+    while version < CONFIG_VERSION {
+        match version {
+            // v1 files always held an explicitly chosen host, so keep using it
+            1 => {
+                object
+                    .entry("use_docker_context")
+                    .or_insert(Value::Bool(false));
+            }
+            _ => unreachable!("no migration from config v{version}"),
+        }
+        version += 1;
+    }
+
+    object.insert("version".to_string(), Value::from(CONFIG_VERSION));
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_v1_files() {
+        let (config, warnings) =
+            AppConfig::from_json(r#"{ "version": 1, "docker_host": "tcp://build:2375" }"#).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.docker_host, "tcp://build:2375");
+        assert!(!config.use_docker_context);
+        assert_eq!(config.columns, ColumnPreferences::default());
+    }
+
+    #[test]
+    fn reads_unversioned_files_as_current() {
+        let (config, _) = AppConfig::from_json(r#"{ "docker_host": "tcp://build:2375" }"#).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(config.use_docker_context);
+        assert!(AppConfig::from_json(r#"{ "version": 0 }"#).is_err());
+    }
+
+    #[test]
+    fn rejects_files_from_newer_versions() {
+        assert!(AppConfig::from_json(r#"{ "version": 99 }"#).is_err());
+    }
+
+    #[test]
+    fn validation_repairs_bad_values() {
+        let (config, warnings) = AppConfig::from_json(
+            r#"{ "version": 2, "docker_host": "ftp://nope", "refresh_interval_secs": 0 }"#,
+        )
+        .unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(config.docker_host, default_docker_host());
+        assert_eq!(config.refresh_interval_secs, MIN_REFRESH_SECS);
+    }
+
+    #[test]
+    fn validation_drops_conflicting_engines() {
+        let (config, warnings) = AppConfig::from_json(
+            r#"{ "version": 2, "engines": [
+                { "name": "build", "host": "tcp://build:2375" },
+                { "name": "build", "host": "tcp://other:2375" },
+                { "name": "primary", "host": "tcp://vm:2375" },
+                { "name": "broken", "host": "localhost" }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(config.engines.len(), 1);
+        assert_eq!(config.engines[0].endpoint.host, "tcp://build:2375");
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let config = AppConfig {
+            theme: Theme::Light,
+            start_page: StartPage::Containers,
+            ..AppConfig::default()
+        };
+        let raw = serde_json::to_string(&config).unwrap();
+        assert_eq!(AppConfig::from_json(&raw).unwrap().0, config);
+    }
+}
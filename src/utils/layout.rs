@@ -0,0 +1,10 @@
+/// Builds an inline `grid-template-columns` style from `(width, visible)` pairs,
+/// so optional table columns can be hidden without breaking row alignment.
+pub fn grid_style(columns: &[(&str, bool)]) -> String {
+    let widths: Vec<&str> = columns
+        .iter()
+        .filter(|(_, visible)| *visible)
+        .map(|(width, _)| *width)
+        .collect();
+    format!("grid-template-columns: {};", widths.join(" "))
+}
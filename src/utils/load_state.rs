@@ -0,0 +1,138 @@
+//! Where each resource list stands: loading, loaded and when, or failed.
+
+use std::collections::HashMap;
+
+/// The lists `AppState` keeps per engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Listing {
+    Containers,
+    Images,
+    Volumes,
+}
+
+impl Listing {
+    pub fn noun(&self) -> &'static str {
+        match self {
+            Listing::Containers => "containers",
+            Listing::Images => "images",
+            Listing::Volumes => "volumes",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LoadState {
+    /// Not requested yet.
+    #[default]
+    Idle,
+    Loading,
+    /// Loaded at this Unix time.
+    Loaded(i64),
+    /// The latest load failed at this Unix time.
+    Failed {
+        at: i64,
+        message: String,
+    },
+}
+
+impl LoadState {
+    pub fn is_loading(&self) -> bool {
+        *self == LoadState::Loading
+    }
+}
+
+/// Load state of every list of every engine.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadStates {
+    states: HashMap<(String, Listing), LoadState>,
+}
+
+impl LoadStates {
+    pub fn set(&mut self, engine: &str, list: Listing, state: LoadState) {
+        self.states.insert((engine.to_string(), list), state);
+    }
+
+    /// State of `list` on `engine` alone.
+    pub fn get(&self, engine: &str, list: Listing) -> LoadState {
+        self.states
+            .get(&(engine.to_string(), list))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_engine(&mut self, engine: &str) {
+        self.states.retain(|(name, _), _| name != engine);
+    }
+
+    /// `list` across all engines: loading while any engine is, failed when
+    /// any engine's latest load failed, and otherwise as old as the stalest
+    /// engine's data.
+    pub fn of(&self, list: Listing) -> LoadState {
+        let mut engines: Vec<_> = self
+            .states
+            .iter()
+            .filter(|((_, listed), _)| *listed == list)
+            .collect();
+        engines.sort_by(|a, b| a.0.0.cmp(&b.0.0));
+
+        if engines.iter().any(|(_, state)| state.is_loading()) {
+            return LoadState::Loading;
+        }
+        let failure = engines
+            .iter()
+            .filter_map(|((engine, _), state)| match state {
+                LoadState::Failed { at, message } => Some((*at, engine, message)),
+                _ => None,
+            })
+            .max_by_key(|(at, _, _)| *at);
+        if let Some((at, engine, message)) = failure {
+            return LoadState::Failed {
+                at,
+                message: format!("{engine}: {message}"),
+            };
+        }
+        engines
+            .iter()
+            .filter_map(|(_, state)| match state {
+                LoadState::Loaded(at) => Some(*at),
+                _ => None,
+            })
+            .min()
+            .map_or(LoadState::Idle, LoadState::Loaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combines_engines() {
+        let mut loads = LoadStates::default();
+        assert_eq!(loads.of(Listing::Images), LoadState::Idle);
+
+        loads.set("primary", Listing::Images, LoadState::Loaded(100));
+        loads.set("build", Listing::Images, LoadState::Loaded(90));
+        assert_eq!(loads.of(Listing::Images), LoadState::Loaded(90));
+
+        loads.set("build", Listing::Images, LoadState::Loading);
+        assert!(loads.of(Listing::Images).is_loading());
+        assert_eq!(loads.of(Listing::Volumes), LoadState::Idle);
+
+        let failed = LoadState::Failed {
+            at: 120,
+            message: "connection refused".to_string(),
+        };
+        loads.set("build", Listing::Images, failed);
+        assert_eq!(
+            loads.of(Listing::Images),
+            LoadState::Failed {
+                at: 120,
+                message: "build: connection refused".to_string()
+            }
+        );
+
+        loads.remove_engine("build");
+        assert_eq!(loads.of(Listing::Images), LoadState::Loaded(100));
+    }
+}
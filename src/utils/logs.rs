@@ -0,0 +1,234 @@
+//! Client-side handling of streamed container output: splitting chunks into
+//! lines, keeping a bounded history, and parsing `--since`/`--until` values.
+
+use std::collections::VecDeque;
+
+use anyhow::{Result, bail};
+
+use crate::services::{LogChunk, LogStream};
+
+/// Lines kept per log pane. Older lines are dropped first.
+pub const LOG_BUFFER_LINES: usize = 5_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLine {
+    pub stream: LogStream,
+    /// RFC 3339 timestamp, when the logs were requested with timestamps.
+    pub timestamp: Option<String>,
+    pub text: String,
+}
+
+impl LogLine {
+    /// Splits the engine's `2024-05-01T10:00:00.000000000Z ` prefix off `line`
+    /// when timestamps were requested.
+    fn parse(stream: LogStream, line: &str, timestamps: bool) -> Self {
+        let (timestamp, text) = match line.split_once(' ') {
+            Some((prefix, rest)) if timestamps && looks_like_timestamp(prefix) => {
+                (Some(prefix.to_string()), rest)
+            }
+            _ => (None, line),
+        };
+        Self {
+            stream,
+            timestamp,
+            text: text.trim_end_matches('\r').to_string(),
+        }
+    }
+}
+
+fn looks_like_timestamp(prefix: &str) -> bool {
+    prefix.len() >= 20
+        && prefix.as_bytes()[..4].iter().all(u8::is_ascii_digit)
+        && prefix.as_bytes()[10] == b'T'
+}
+
+/// The most recent `capacity` lines of a log stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+    /// Lines evicted to stay within `capacity`.
+    dropped: usize,
+    /// Text after the last newline of each stream, waiting for the rest.
+    partial: [String; 2],
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::with_capacity(LOG_BUFFER_LINES)
+    }
+}
+
+impl LogBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+            partial: Default::default(),
+        }
+    }
+
+    /// Adds the complete lines of `chunk`. A trailing partial line is held
+    /// back until the chunk that finishes it arrives.
+    pub fn push_chunk(&mut self, chunk: &LogChunk, timestamps: bool) {
+        let slot = match chunk.stream {
+            LogStream::Stdout => 0,
+            LogStream::Stderr => 1,
+        };
+        let mut text = std::mem::take(&mut self.partial[slot]);
+        text.push_str(&chunk.text);
+
+        let mut pieces: Vec<&str> = text.split('\n').collect();
+        let rest = pieces.pop().unwrap_or_default().to_string();
+        for piece in pieces {
+            self.push(LogLine::parse(chunk.stream, piece, timestamps));
+        }
+        self.partial[slot] = rest;
+    }
+
+    /// Adds the partial lines held back by `push_chunk`, for when the stream
+    /// ends without a final newline.
+    pub fn flush(&mut self, timestamps: bool) {
+        for (slot, stream) in [LogStream::Stdout, LogStream::Stderr]
+            .into_iter()
+            .enumerate()
+        {
+            let rest = std::mem::take(&mut self.partial[slot]);
+            if !rest.is_empty() {
+                self.push(LogLine::parse(stream, &rest, timestamps));
+            }
+        }
+    }
+
+    fn push(&mut self, line: LogLine) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+}
+
+/// Parses a `since`/`until` bound like the Docker CLI: empty for none, a
+/// Unix timestamp, or a duration before `now` such as `90s`, `15m`, `2h`
+/// or `1d`.
+pub fn parse_time_bound(value: &str, now: i64) -> Result<Option<i64>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Ok(Some(timestamp));
+    }
+
+    let unit = value.chars().last().unwrap_or_default();
+    let amount = &value[..value.len() - unit.len_utf8()];
+    let seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => bail!("\"{value}\" is not a Unix timestamp or a duration like 15m"),
+    };
+    match amount.parse::<i64>() {
+        Ok(amount) if amount >= 0 => Ok(Some(now - amount * seconds)),
+        _ => bail!("\"{value}\" is not a Unix timestamp or a duration like 15m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(stream: LogStream, text: &str) -> LogChunk {
+        LogChunk {
+            stream,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn joins_partial_lines_per_stream() {
+        let mut buffer = LogBuffer::default();
+        buffer.push_chunk(&chunk(LogStream::Stdout, "hello wo"), false);
+        buffer.push_chunk(&chunk(LogStream::Stderr, "oops\n"), false);
+        buffer.push_chunk(&chunk(LogStream::Stdout, "rld\nbye\n"), false);
+
+        let lines: Vec<_> = buffer
+            .lines()
+            .map(|l| (l.stream, l.text.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (LogStream::Stderr, "oops"),
+                (LogStream::Stdout, "hello world"),
+                (LogStream::Stdout, "bye"),
+            ]
+        );
+    }
+
+    #[test]
+    fn flush_keeps_a_last_line_without_newline() {
+        let mut buffer = LogBuffer::default();
+        buffer.push_chunk(&chunk(LogStream::Stdout, "ready\nexiting"), false);
+        assert_eq!(buffer.len(), 1);
+
+        buffer.flush(false);
+        let texts: Vec<_> = buffer.lines().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["ready", "exiting"]);
+        buffer.flush(false);
+        assert_eq!(buffer.len(), 2);
+    }
+
+    #[test]
+    fn drops_oldest_lines_beyond_capacity() {
+        let mut buffer = LogBuffer::with_capacity(2);
+        buffer.push_chunk(&chunk(LogStream::Stdout, "1\n2\n3\n"), false);
+        let texts: Vec<_> = buffer.lines().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["2", "3"]);
+        assert_eq!(buffer.dropped(), 1);
+    }
+
+    #[test]
+    fn splits_timestamps_when_requested() {
+        let mut buffer = LogBuffer::default();
+        let text = "2024-05-01T10:00:00.123456789Z listening on :80\n";
+        buffer.push_chunk(&chunk(LogStream::Stdout, text), true);
+        let line = buffer.lines().next().unwrap();
+        assert_eq!(
+            line.timestamp.as_deref(),
+            Some("2024-05-01T10:00:00.123456789Z")
+        );
+        assert_eq!(line.text, "listening on :80");
+
+        let line = LogLine::parse(LogStream::Stdout, "GET / 200", true);
+        assert_eq!(line.timestamp, None);
+    }
+
+    #[test]
+    fn parses_time_bounds() {
+        assert_eq!(parse_time_bound("", 1_000).unwrap(), None);
+        assert_eq!(
+            parse_time_bound("1700000000", 0).unwrap(),
+            Some(1_700_000_000)
+        );
+        assert_eq!(parse_time_bound("15m", 10_000).unwrap(), Some(9_100));
+        assert_eq!(parse_time_bound("1d", 100_000).unwrap(), Some(13_600));
+        assert!(parse_time_bound("soon", 0).is_err());
+        assert!(parse_time_bound("m", 0).is_err());
+    }
+}
@@ -1,3 +1,24 @@
+mod ansi;
 mod app_state;
+mod bulk;
+mod config;
+mod layout;
+mod load_state;
+mod logs;
+mod notifications;
+mod stats;
+mod table;
 
-pub use app_state::AppState;
+pub use ansi::parse_ansi;
+pub use app_state::{AppState, EngineStatus, PRIMARY_ENGINE};
+pub use bulk::{BulkReport, BulkResource, BulkStatus, BulkTarget};
+pub use config::{AppConfig, EngineConfig, StartPage, Theme};
+pub use layout::grid_style;
+pub use load_state::{Listing, LoadState, LoadStates};
+pub use logs::{LogBuffer, parse_time_bound};
+pub use notifications::{Notification, Notifications, Severity};
+pub use stats::{StatsHistory, format_bytes, sparkline_points};
+pub use table::{
+    Selection, SortColumn, SortOrder, TableView, TableViews, facet_values, format_age, format_date,
+    unix_now,
+};
@@ -0,0 +1,213 @@
+//! Messages for the user, kept until dismissed. New ones also show briefly
+//! as toasts; the notification panel lists them all.
+
+use crate::services::EngineError;
+
+/// Notifications kept; the oldest are dropped first.
+pub const MAX_NOTIFICATIONS: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub id: u64,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+    /// What the user can do about it.
+    pub hint: Option<String>,
+    /// What raised it, e.g. `containers:primary`. A later success from the
+    /// same source resolves it.
+    pub source: Option<String>,
+    /// Unix time of the latest occurrence.
+    pub at: i64,
+    /// Occurrences folded into this notification.
+    pub count: u32,
+    /// Still shown as a toast.
+    pub toast: bool,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            severity,
+            title: title.into(),
+            message: message.into(),
+            hint: None,
+            source: None,
+            at: 0,
+            count: 1,
+            toast: true,
+        }
+    }
+
+    /// An error notification titled by the kind of engine failure.
+    pub fn engine_error(context: &str, error: &anyhow::Error) -> Self {
+        let error = EngineError::classify(error);
+        let mut notification = Notification::new(
+            Severity::Error,
+            error.title(),
+            format!("{context}: {error}"),
+        );
+        notification.hint = error.hint().map(str::to_string);
+        notification
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+}
+
+/// Notifications, newest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Notifications {
+    items: Vec<Notification>,
+    next_id: u64,
+}
+
+impl Notifications {
+    /// Adds `notification` at time `now` and returns its id. A repeat of a
+    /// notification still listed is folded into it instead, without showing
+    /// another toast, so a failing background refresh does not flood the list.
+    pub fn push(&mut self, mut notification: Notification, now: i64) -> u64 {
+        let repeat = self.items.iter().position(|existing| {
+            existing.severity == notification.severity
+                && existing.title == notification.title
+                && existing.message == notification.message
+        });
+        if let Some(index) = repeat {
+            let mut existing = self.items.remove(index);
+            existing.count += 1;
+            existing.at = now;
+            let id = existing.id;
+            self.items.insert(0, existing);
+            return id;
+        }
+
+        self.next_id += 1;
+        notification.id = self.next_id;
+        notification.at = now;
+        self.items.insert(0, notification);
+        self.items.truncate(MAX_NOTIFICATIONS);
+        self.next_id
+    }
+
+    pub fn dismiss(&mut self, id: u64) {
+        self.items.retain(|notification| notification.id != id);
+    }
+
+    /// Dismisses what `source` raised, once it works again.
+    pub fn resolve(&mut self, source: &str) {
+        self.items
+            .retain(|notification| notification.source.as_deref() != Some(source));
+    }
+
+    pub fn hide_toast(&mut self, id: u64) {
+        if let Some(notification) = self.items.iter_mut().find(|n| n.id == id) {
+            notification.toast = false;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Notification> {
+        self.items.iter()
+    }
+
+    pub fn toasts(&self) -> impl Iterator<Item = &Notification> {
+        self.items.iter().filter(|notification| notification.toast)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The most severe level listed, for the badge on the panel button.
+    pub fn highest(&self) -> Option<Severity> {
+        self.items
+            .iter()
+            .map(|notification| notification.severity)
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_are_folded_and_sources_resolved() {
+        let mut notifications = Notifications::default();
+        let failed = || {
+            Notification::new(Severity::Error, "Engine unreachable", "connection refused")
+                .with_source("containers:primary")
+        };
+        let first = notifications.push(failed(), 100);
+        notifications.hide_toast(first);
+        let info = notifications.push(Notification::new(Severity::Info, "Saved", "ok"), 105);
+        assert_eq!(notifications.push(failed(), 110), first);
+
+        let listed: Vec<_> = notifications.iter().collect();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(
+            (listed[0].id, listed[0].count, listed[0].at),
+            (first, 2, 110)
+        );
+        assert!(!listed[0].toast);
+        assert_eq!(notifications.highest(), Some(Severity::Error));
+
+        notifications.resolve("containers:primary");
+        assert_eq!(notifications.len(), 1);
+        notifications.dismiss(info);
+        assert!(notifications.is_empty());
+    }
+
+    #[test]
+    fn keeps_the_newest() {
+        let mut notifications = Notifications::default();
+        for n in 0..MAX_NOTIFICATIONS + 5 {
+            notifications.push(
+                Notification::new(Severity::Info, "Note", format!("{n}")),
+                n as i64,
+            );
+        }
+        assert_eq!(notifications.len(), MAX_NOTIFICATIONS);
+        let newest = notifications.iter().next().unwrap();
+        assert_eq!(newest.message, format!("{}", MAX_NOTIFICATIONS + 4));
+    }
+
+    #[test]
+    fn engine_errors_carry_a_title_and_hint() {
+        let error = anyhow::anyhow!("permission denied while trying to connect");
+        let notification = Notification::engine_error("Failed to connect to primary", &error);
+        assert_eq!(notification.title, "Permission denied");
+        assert_eq!(
+            notification.message,
+            "Failed to connect to primary: permission denied while trying to connect"
+        );
+        assert!(notification.hint.unwrap().contains("docker group"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Recent stats samples per container and the helpers that render them.
+
+use std::collections::VecDeque;
+
+use crate::services::ContainerStats;
+
+/// Samples kept per container; at one per second that is the last minute.
+pub const STATS_HISTORY_SAMPLES: usize = 60;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatsHistory {
+    samples: VecDeque<ContainerStats>,
+}
+
+impl StatsHistory {
+    pub fn push(&mut self, sample: ContainerStats) {
+        if self.samples.len() == STATS_HISTORY_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<&ContainerStats> {
+        self.samples.back()
+    }
+
+    /// One value per sample, oldest first.
+    pub fn series(&self, value: impl Fn(&ContainerStats) -> f64) -> Vec<f64> {
+        self.samples.iter().map(value).collect()
+    }
+}
+
+/// SVG `polyline` points drawing `values` across a `width` x `height` box,
+/// scaled so the largest value (or `floor`, if larger) touches the top.
+pub fn sparkline_points(values: &[f64], width: f64, height: f64, floor: f64) -> String {
+    if values.is_empty() {
+        return String::new();
+    }
+    let max = values.iter().copied().fold(floor, f64::max);
+    let step = width / (STATS_HISTORY_SAMPLES - 1) as f64;
+    // Right-aligned, so the newest sample is always at the right edge
+    let start = width - step * (values.len() - 1) as f64;
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let x = start + step * index as f64;
+            let y = if max > 0.0 {
+                height - value / max * height
+            } else {
+                height
+            };
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Byte counts the way `docker stats` prints them, e.g. `12.3MB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1}{}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_keeps_the_last_minute() {
+        let mut history = StatsHistory::default();
+        for second in 0..(STATS_HISTORY_SAMPLES + 5) {
+            history.push(ContainerStats {
+                cpu_percent: second as f64,
+                ..Default::default()
+            });
+        }
+        let cpu = history.series(|sample| sample.cpu_percent);
+        assert_eq!(cpu.len(), STATS_HISTORY_SAMPLES);
+        assert_eq!(cpu[0], 5.0);
+        assert_eq!(history.latest().unwrap().cpu_percent, 64.0);
+    }
+
+    #[test]
+    fn sparkline_scales_to_the_largest_value() {
+        let points = sparkline_points(&[0.0, 50.0, 100.0], 59.0, 20.0, 1.0);
+        assert_eq!(points, "57.0,20.0 58.0,10.0 59.0,0.0");
+        // Idle series stay on the baseline instead of filling the chart
+        let idle = sparkline_points(&[0.0, 0.2], 59.0, 20.0, 1.0);
+        assert_eq!(idle, "58.0,20.0 59.0,16.0");
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0GB");
+    }
+}
@@ -0,0 +1,452 @@
+//! Filtering and sorting shared by the resource tables. Each table keeps a
+//! `TableView` in `AppState`, so its filters survive navigating away.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::services::{ContainerInfo, ImageInfo, VolumeInfo};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortColumn {
+    Name,
+    Image,
+    Created,
+    Size,
+    State,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// A value items are compared by. Numbers sort before text.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortKey {
+    Number(i64),
+    Text(String),
+}
+
+/// What a table row exposes to filtering and sorting.
+pub trait TableItem {
+    fn engine(&self) -> &str;
+
+    /// Identifies the item within its engine.
+    fn id(&self) -> &str;
+
+    /// Text the filter box matches against, case-insensitively.
+    fn search_text(&self) -> String;
+
+    /// The state facet value, for tables that have one.
+    fn state(&self) -> Option<String> {
+        None
+    }
+
+    fn labels(&self) -> Vec<String>;
+
+    fn sort_key(&self, column: SortColumn) -> SortKey;
+}
+
+/// Filters and sort order of one table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableView {
+    pub query: String,
+    pub engine: Option<String>,
+    pub state: Option<String>,
+    /// `key=value` label an item must carry.
+    pub label: Option<String>,
+    /// `None` keeps the engine's order.
+    pub sort: Option<(SortColumn, SortOrder)>,
+}
+
+impl TableView {
+    /// Sorts by `column`, ascending first, then descending, then unsorted.
+    pub fn toggle_sort(&mut self, column: SortColumn) {
+        self.sort = match self.sort {
+            Some((current, SortOrder::Ascending)) if current == column => {
+                Some((column, SortOrder::Descending))
+            }
+            Some((current, SortOrder::Descending)) if current == column => None,
+            _ => Some((column, SortOrder::Ascending)),
+        };
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        !self.query.trim().is_empty()
+            || self.engine.is_some()
+            || self.state.is_some()
+            || self.label.is_some()
+    }
+
+    pub fn matches<T: TableItem>(&self, item: &T) -> bool {
+        let query = self.query.trim().to_lowercase();
+        (query.is_empty() || item.search_text().to_lowercase().contains(&query))
+            && self
+                .engine
+                .as_deref()
+                .is_none_or(|engine| item.engine() == engine)
+            && self
+                .state
+                .as_ref()
+                .is_none_or(|state| item.state().as_ref() == Some(state))
+            && self
+                .label
+                .as_ref()
+                .is_none_or(|label| item.labels().contains(label))
+    }
+
+    /// The matching items, in the chosen order.
+    pub fn apply<T: TableItem + Clone>(&self, items: &[T]) -> Vec<T> {
+        let mut visible: Vec<T> = items
+            .iter()
+            .filter(|item| self.matches(*item))
+            .cloned()
+            .collect();
+        if let Some((column, order)) = self.sort {
+            // Stable, so equal keys keep the engine's order
+            visible.sort_by(|a, b| {
+                let ordering = a.sort_key(column).cmp(&b.sort_key(column));
+                match order {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
+                }
+            });
+        }
+        visible
+    }
+}
+
+/// Per-view table state kept in `AppState`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableViews {
+    pub containers: TableView,
+    pub images: TableView,
+    pub volumes: TableView,
+}
+
+/// Rows checked for a bulk action, by engine and id.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    keys: HashSet<(String, String)>,
+}
+
+impl Selection {
+    fn key<T: TableItem>(item: &T) -> (String, String) {
+        (item.engine().to_string(), item.id().to_string())
+    }
+
+    pub fn contains<T: TableItem>(&self, item: &T) -> bool {
+        self.keys.contains(&Self::key(item))
+    }
+
+    pub fn set<T: TableItem>(&mut self, item: &T, selected: bool) {
+        if selected {
+            self.keys.insert(Self::key(item));
+        } else {
+            self.keys.remove(&Self::key(item));
+        }
+    }
+
+    /// Checks or unchecks every item in `items`, e.g. all filtered rows.
+    pub fn set_all<T: TableItem>(&mut self, items: &[T], selected: bool) {
+        for item in items {
+            self.set(item, selected);
+        }
+    }
+
+    /// Whether `items` is not empty and every item in it is checked.
+    pub fn all_selected<T: TableItem>(&self, items: &[T]) -> bool {
+        !items.is_empty() && items.iter().all(|item| self.contains(item))
+    }
+
+    /// The checked items among `items`, in their order. Checked items that
+    /// are filtered out are left alone.
+    pub fn pick<T: TableItem + Clone>(&self, items: &[T]) -> Vec<T> {
+        items
+            .iter()
+            .filter(|item| self.contains(*item))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+/// Distinct state and label values present in `items`, for the facet menus.
+pub fn facet_values<T: TableItem>(items: &[T]) -> (Vec<String>, Vec<String>) {
+    let mut states = BTreeSet::new();
+    let mut labels = BTreeSet::new();
+    for item in items {
+        states.extend(item.state());
+        labels.extend(item.labels());
+    }
+    (states.into_iter().collect(), labels.into_iter().collect())
+}
+
+/// The current time as a Unix timestamp.
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// How long ago a Unix timestamp was, in the largest whole unit: `3 days ago`.
+pub fn format_age(created: i64, now: i64) -> String {
+    if created <= 0 {
+        return "--".to_string();
+    }
+    let seconds = (now - created).max(0);
+    let (amount, unit) = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3_600 => (seconds / 60, "minute"),
+        3_600..86_400 => (seconds / 3_600, "hour"),
+        86_400..2_592_000 => (seconds / 86_400, "day"),
+        2_592_000..31_536_000 => (seconds / 2_592_000, "month"),
+        _ => (seconds / 31_536_000, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{amount} {unit}{plural} ago")
+}
+
+/// The UTC calendar date of a Unix timestamp: `2024-05-31`.
+pub fn format_date(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        return "--".to_string();
+    }
+    // Days since 1970-01-01 to a civil date, counting eras of 400 years
+    // from 0000-03-01 so leap days fall at the end of each year
+    let days = timestamp / 86_400 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn label_pairs(labels: &BTreeMap<String, String>) -> Vec<String> {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect()
+}
+
+fn text(value: &str) -> SortKey {
+    SortKey::Text(value.to_lowercase())
+}
+
+impl TableItem for ContainerInfo {
+    fn engine(&self) -> &str {
+        &self.engine
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn search_text(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.name, self.id, self.image, self.status, self.ports
+        )
+    }
+
+    fn state(&self) -> Option<String> {
+        Some(self.state.name().to_string())
+    }
+
+    fn labels(&self) -> Vec<String> {
+        label_pairs(&self.labels)
+    }
+
+    fn sort_key(&self, column: SortColumn) -> SortKey {
+        match column {
+            SortColumn::Image => text(&self.image),
+            SortColumn::Created => SortKey::Number(self.created),
+            SortColumn::State => text(self.state.name()),
+            SortColumn::Name | SortColumn::Size => text(&self.name),
+        }
+    }
+}
+
+impl TableItem for ImageInfo {
+    fn engine(&self) -> &str {
+        &self.engine
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn search_text(&self) -> String {
+        let tags: Vec<String> = self.tags.iter().map(|tag| tag.to_string()).collect();
+        format!("{} {} {}", tags.join(" "), self.digests.join(" "), self.id)
+    }
+
+    /// Images are either tagged or dangling.
+    fn state(&self) -> Option<String> {
+        Some(
+            if self.is_dangling() {
+                "dangling"
+            } else {
+                "tagged"
+            }
+            .to_string(),
+        )
+    }
+
+    fn labels(&self) -> Vec<String> {
+        label_pairs(&self.labels)
+    }
+
+    fn sort_key(&self, column: SortColumn) -> SortKey {
+        match column {
+            SortColumn::Created => SortKey::Number(self.created),
+            SortColumn::Size => SortKey::Number(self.size_bytes),
+            _ => text(&self.name()),
+        }
+    }
+}
+
+impl TableItem for VolumeInfo {
+    fn engine(&self) -> &str {
+        &self.engine
+    }
+
+    fn id(&self) -> &str {
+        &self.name
+    }
+
+    fn search_text(&self) -> String {
+        format!("{} {} {}", self.name, self.driver, self.mountpoint)
+    }
+
+    fn labels(&self) -> Vec<String> {
+        label_pairs(&self.labels)
+    }
+
+    fn sort_key(&self, column: SortColumn) -> SortKey {
+        match column {
+            // RFC 3339 timestamps sort correctly as text
+            SortColumn::Created => text(self.created.as_deref().unwrap_or_default()),
+            _ => text(&self.name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{ContainerState, container};
+
+    fn containers() -> Vec<ContainerInfo> {
+        let mut web = container("abc123", "web");
+        web.engine = "primary".to_string();
+        web.state = ContainerState::Running;
+        web.created = 200;
+        web.labels
+            .insert("com.docker.compose.project".to_string(), "shop".to_string());
+        let mut db = container("def456", "db");
+        db.engine = "primary".to_string();
+        db.image = "postgres:16".to_string();
+        db.created = 100;
+        let mut worker = container("987fed", "Worker");
+        worker.engine = "build".to_string();
+        worker.created = 300;
+        vec![web, db, worker]
+    }
+
+    fn names(items: &[ContainerInfo]) -> Vec<&str> {
+        items.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn filters_by_text_engine_and_facets() {
+        let items = containers();
+        let mut view = TableView {
+            query: "POSTGRES".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(names(&view.apply(&items)), ["db"]);
+
+        view.query.clear();
+        view.engine = Some("primary".to_string());
+        view.state = Some("exited".to_string());
+        assert_eq!(names(&view.apply(&items)), ["db"]);
+
+        view.state = None;
+        view.label = Some("com.docker.compose.project=shop".to_string());
+        assert_eq!(names(&view.apply(&items)), ["web"]);
+        assert!(view.is_filtered());
+
+        let (states, labels) = facet_values(&items);
+        assert_eq!(states, ["exited", "running"]);
+        assert_eq!(labels, ["com.docker.compose.project=shop"]);
+    }
+
+    #[test]
+    fn sort_cycles_through_orders() {
+        let items = containers();
+        let mut view = TableView::default();
+
+        view.toggle_sort(SortColumn::Created);
+        assert_eq!(names(&view.apply(&items)), ["db", "web", "Worker"]);
+        view.toggle_sort(SortColumn::Created);
+        assert_eq!(names(&view.apply(&items)), ["Worker", "web", "db"]);
+        view.toggle_sort(SortColumn::Created);
+        assert_eq!(names(&view.apply(&items)), ["web", "db", "Worker"]);
+
+        // Names compare case-insensitively
+        view.toggle_sort(SortColumn::Name);
+        assert_eq!(names(&view.apply(&items)), ["db", "web", "Worker"]);
+    }
+
+    #[test]
+    fn selection_only_picks_visible_items() {
+        let items = containers();
+        let mut selection = Selection::default();
+        selection.set_all(&items, true);
+        assert!(selection.all_selected(&items));
+
+        let view = TableView {
+            engine: Some("primary".to_string()),
+            ..Default::default()
+        };
+        let visible = view.apply(&items);
+        assert_eq!(names(&selection.pick(&visible)), ["web", "db"]);
+
+        selection.set(&items[0], false);
+        assert!(!selection.all_selected(&visible));
+        assert_eq!(names(&selection.pick(&visible)), ["db"]);
+        assert!(!selection.all_selected::<ContainerInfo>(&[]));
+    }
+
+    #[test]
+    fn formats_ages() {
+        let now = 1_000_000;
+        assert_eq!(format_age(0, now), "--");
+        assert_eq!(format_age(now - 10, now), "just now");
+        assert_eq!(format_age(now - 3_600, now), "1 hour ago");
+        assert_eq!(format_age(now - 3 * 86_400, now), "3 days ago");
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "--");
+        assert_eq!(format_date(86_399), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_717_200_000), "2024-06-01");
+    }
+}
@@ -0,0 +1,179 @@
+use dioxus::prelude::*;
+
+use crate::Route;
+use crate::components::{SectionHeader, StatusPill};
+use crate::services::{ContainerDetails, ContainerState, describe_error};
+use crate::utils::AppState;
+
+use super::container_inspect::{
+    ConfigTab, EnvTab, HealthTab, LabelsTab, MountsTab, NetworksTab, RawTab,
+};
+use super::container_logs::LogPane;
+use super::container_stats::StatsCharts;
+use super::container_terminal::TerminalPane;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DetailTab {
+    Logs,
+    Terminal,
+    Config,
+    Environment,
+    Mounts,
+    Networks,
+    Labels,
+    Health,
+    Raw,
+}
+
+impl DetailTab {
+    const ALL: [DetailTab; 9] = [
+        DetailTab::Logs,
+        DetailTab::Terminal,
+        DetailTab::Config,
+        DetailTab::Environment,
+        DetailTab::Mounts,
+        DetailTab::Networks,
+        DetailTab::Labels,
+        DetailTab::Health,
+        DetailTab::Raw,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            DetailTab::Logs => "Logs",
+            DetailTab::Terminal => "Terminal",
+            DetailTab::Config => "Config",
+            DetailTab::Environment => "Environment",
+            DetailTab::Mounts => "Mounts",
+            DetailTab::Networks => "Networks",
+            DetailTab::Labels => "Labels",
+            DetailTab::Health => "Health",
+            DetailTab::Raw => "Raw JSON",
+        }
+    }
+}
+
+#[component]
+pub fn ContainerDetail(engine: String, id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut tab = use_signal(|| DetailTab::Logs);
+    let mut details = use_signal(|| None::<Result<ContainerDetails, String>>);
+
+    let container = app_state
+        .containers
+        .read()
+        .iter()
+        .find(|c| c.engine == engine && c.id == id)
+        .cloned();
+
+    let running = container
+        .as_ref()
+        .is_some_and(|c| c.state == ContainerState::Running);
+
+    // Inspect again whenever a refresh changes the container's status, so
+    // health, IPs and restart counts stay current
+    let app_state_for_status = app_state.clone();
+    let status_engine = engine.clone();
+    let status_id = id.clone();
+    let status = use_memo(move || {
+        app_state_for_status
+            .containers
+            .read()
+            .iter()
+            .find(|c| c.engine == status_engine && c.id == status_id)
+            .map(|c| c.status.clone())
+    });
+    let app_state_for_inspect = app_state.clone();
+    let inspect_engine = engine.clone();
+    let inspect_id = id.clone();
+    use_effect(move || {
+        status.read();
+        let Some(service) = app_state_for_inspect.service(&inspect_engine) else {
+            details.set(Some(Err(format!(
+                "Engine {inspect_engine} is not connected"
+            ))));
+            return;
+        };
+        let id = inspect_id.clone();
+        spawn(async move {
+            let result = service.inspect_container(&id).await;
+            details.set(Some(result.map_err(|e| describe_error(&e))));
+        });
+    });
+
+    let (title, subtitle) = match &container {
+        Some(container) => (
+            container.name.clone(),
+            format!("{} · {} on {}", container.id, container.image, engine),
+        ),
+        None => (id.clone(), format!("Container on {engine}")),
+    };
+    let current_tab = tab();
+
+    rsx! {
+        SectionHeader { title, subtitle: Some(subtitle) }
+
+        div { class: "action-bar",
+            Link { to: Route::Containers {}, class: "button secondary", "← Containers" }
+            if let Some(container) = &container {
+                StatusPill {
+                    label: container.state.label(),
+                    class_name: container.state.css_class().to_string()
+                }
+                span { class: "row-subtitle", "{container.status}" }
+            }
+        }
+
+        if running {
+            StatsCharts { key: "{engine}/{id}", engine: engine.clone(), id: id.clone() }
+        }
+
+        div { class: "tab-bar",
+            for candidate in DetailTab::ALL {
+                button {
+                    class: tab_class(candidate == current_tab),
+                    onclick: move |_| tab.set(candidate),
+                    {candidate.label()}
+                }
+            }
+        }
+
+        {match current_tab {
+            DetailTab::Logs => rsx! {
+                LogPane { key: "{engine}/{id}", engine: engine.clone(), id: id.clone() }
+            },
+            DetailTab::Terminal => rsx! {
+                if running {
+                    TerminalPane { key: "{engine}/{id}", engine: engine.clone(), id: id.clone() }
+                } else {
+                    div { class: "card terminal-card",
+                        p { class: "card-hint", "Start the container to open a terminal in it." }
+                    }
+                }
+            },
+            inspect_tab => match details() {
+                None => rsx! {
+                    div { class: "card detail-card",
+                        p { class: "card-hint", "Inspecting container…" }
+                    }
+                },
+                Some(Err(error)) => rsx! {
+                    div { class: "error-message", "⚠️ Failed to inspect container: {error}" }
+                },
+                Some(Ok(details)) => match inspect_tab {
+                    DetailTab::Environment => rsx! { EnvTab { details } },
+                    DetailTab::Mounts => rsx! { MountsTab { details } },
+                    DetailTab::Networks => rsx! { NetworksTab { details } },
+                    DetailTab::Labels => rsx! { LabelsTab { details } },
+                    DetailTab::Health => rsx! { HealthTab { details } },
+                    DetailTab::Raw => rsx! { RawTab { details } },
+                    _ => rsx! { ConfigTab { details } },
+                },
+            },
+        }}
+    }
+}
+
+fn tab_class(active: bool) -> &'static str {
+    if active { "tab active" } else { "tab" }
+}
@@ -0,0 +1,250 @@
+//! Tabs of the container detail page that present the inspect response.
+
+use dioxus::prelude::*;
+
+use crate::components::CopyButton;
+use crate::services::ContainerDetails;
+use crate::utils::format_bytes;
+
+/// A labelled value with a copy button. Empty values show as `--`.
+#[component]
+fn DetailRow(label: String, value: String) -> Element {
+    rsx! {
+        div { class: "detail-row",
+            span { class: "detail-key", "{label}" }
+            if value.is_empty() {
+                span { class: "detail-value muted", "--" }
+            } else {
+                code { class: "detail-value", "{value}" }
+                CopyButton { value: value.clone() }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn ConfigTab(details: ContainerDetails) -> Element {
+    let restart = match details.restart_policy.as_str() {
+        "on-failure" if details.restart_max_retries > 0 => {
+            format!("on-failure (max {} retries)", details.restart_max_retries)
+        }
+        policy => policy.to_string(),
+    };
+    let working_dir = details.working_dir.clone().unwrap_or_default();
+    let user = details.user.clone().unwrap_or_default();
+    let created = details.created.clone().unwrap_or_default();
+    let limits = &details.limits;
+    let unlimited = || "unlimited".to_string();
+    let memory = |bytes: Option<i64>| {
+        bytes
+            .map(|bytes| format_bytes(bytes as u64))
+            .unwrap_or_else(unlimited)
+    };
+    let memory_swap = match limits.memory_swap {
+        Some(-1) => unlimited(),
+        swap => memory(swap),
+    };
+
+    rsx! {
+        div { class: "card detail-card",
+            h3 { "Process" }
+            DetailRow { label: "Entrypoint", value: shell_words(&details.entrypoint) }
+            DetailRow { label: "Command", value: shell_words(&details.command) }
+            DetailRow { label: "Working dir", value: working_dir }
+            DetailRow { label: "User", value: user }
+            DetailRow { label: "Image", value: details.info.image.clone() }
+            DetailRow { label: "Created", value: created }
+
+            h3 { "Restart policy" }
+            DetailRow { label: "Policy", value: restart }
+            DetailRow { label: "Restart count", value: details.restart_count.to_string() }
+
+            h3 { "Resource limits" }
+            DetailRow {
+                label: "CPUs",
+                value: limits.cpus.map(|cpus| format!("{cpus}")).unwrap_or_else(unlimited)
+            }
+            DetailRow {
+                label: "CPU shares",
+                value: limits.cpu_shares.map(|shares| shares.to_string()).unwrap_or_default()
+            }
+            DetailRow { label: "CPU set", value: limits.cpuset.clone().unwrap_or_default() }
+            DetailRow { label: "Memory", value: memory(limits.memory) }
+            DetailRow { label: "Memory + swap", value: memory_swap }
+            DetailRow {
+                label: "PIDs",
+                value: limits.pids.map(|pids| pids.to_string()).unwrap_or_else(unlimited)
+            }
+        }
+    }
+}
+
+#[component]
+pub fn EnvTab(details: ContainerDetails) -> Element {
+    let mut reveal = use_signal(|| false);
+    let revealed = reveal();
+
+    rsx! {
+        div { class: "card detail-card",
+            div { class: "detail-toolbar",
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: revealed,
+                        onchange: move |event| reveal.set(event.checked())
+                    }
+                    "Reveal secrets"
+                }
+            }
+            if details.env.is_empty() {
+                p { class: "card-hint", "No environment variables." }
+            }
+            for var in details.env.iter() {
+                div { class: "detail-row",
+                    span { class: "detail-key", "{var.key}" }
+                    code { class: "detail-value", {var.display_value(revealed)} }
+                    CopyButton { value: var.value.clone() }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn MountsTab(details: ContainerDetails) -> Element {
+    rsx! {
+        div { class: "card detail-card",
+            if details.mounts.is_empty() {
+                p { class: "card-hint", "No mounts." }
+            }
+            for mount in details.mounts.iter() {
+                div { class: "detail-group",
+                    h3 {
+                        "{mount.destination} "
+                        span { class: "pill created", "{mount.kind}" }
+                        if mount.read_only {
+                            span { class: "pill paused", "read-only" }
+                        }
+                    }
+                    DetailRow { label: "Source", value: mount.source.clone() }
+                    DetailRow { label: "Destination", value: mount.destination.clone() }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn NetworksTab(details: ContainerDetails) -> Element {
+    rsx! {
+        div { class: "card detail-card",
+            if details.networks.is_empty() {
+                p { class: "card-hint", "Not attached to any network." }
+            }
+            for network in details.networks.iter() {
+                div { class: "detail-group",
+                    h3 { "{network.network}" }
+                    DetailRow {
+                        label: "IPv4 address",
+                        value: network.ip_address.clone().unwrap_or_default()
+                    }
+                    DetailRow {
+                        label: "IPv6 address",
+                        value: network.ipv6_address.clone().unwrap_or_default()
+                    }
+                    DetailRow {
+                        label: "Gateway",
+                        value: network.gateway.clone().unwrap_or_default()
+                    }
+                    DetailRow {
+                        label: "MAC address",
+                        value: network.mac_address.clone().unwrap_or_default()
+                    }
+                    DetailRow { label: "Aliases", value: network.aliases.join(", ") }
+                }
+            }
+            DetailRow { label: "Published ports", value: details.info.ports.clone() }
+        }
+    }
+}
+
+#[component]
+pub fn LabelsTab(details: ContainerDetails) -> Element {
+    rsx! {
+        div { class: "card detail-card",
+            if details.labels.is_empty() {
+                p { class: "card-hint", "No labels." }
+            }
+            for (key, value) in details.labels.iter() {
+                DetailRow { label: key.clone(), value: value.clone() }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn HealthTab(details: ContainerDetails) -> Element {
+    let status = details
+        .info
+        .health
+        .map(|health| health.label().to_string())
+        .unwrap_or_else(|| "No health check configured".to_string());
+
+    rsx! {
+        div { class: "card detail-card",
+            DetailRow { label: "Status", value: status }
+            for check in details.health_log.iter().rev() {
+                div { class: "detail-group",
+                    h3 {
+                        "{check.start} "
+                        if check.exit_code == 0 {
+                            span { class: "pill running", "passed" }
+                        } else {
+                            span { class: "pill failed", "exit {check.exit_code}" }
+                        }
+                    }
+                    pre { class: "health-output", "{check.output}" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn RawTab(details: ContainerDetails) -> Element {
+    let mut reveal = use_signal(|| false);
+    let revealed = reveal();
+    let json = details.raw_json(revealed);
+
+    rsx! {
+        div { class: "card detail-card",
+            div { class: "detail-toolbar",
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: revealed,
+                        onchange: move |event| reveal.set(event.checked())
+                    }
+                    "Reveal secrets"
+                }
+                CopyButton { value: json.clone() }
+            }
+            pre { class: "raw-json", "{json}" }
+        }
+    }
+}
+
+/// Joins a command for display, quoting arguments that contain whitespace.
+fn shell_words(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            if word.is_empty() || word.contains(char::is_whitespace) {
+                format!("{word:?}")
+            } else {
+                word.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
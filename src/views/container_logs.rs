@@ -0,0 +1,227 @@
+use dioxus::core::Task;
+use dioxus::prelude::*;
+use futures_util::StreamExt;
+
+use crate::services::{LogOptions, LogStream, describe_error};
+use crate::utils::{AppState, LogBuffer, parse_ansi, parse_time_bound, unix_now};
+
+const LOG_PANE_ID: &str = "log-pane";
+const SCROLL_TO_END: &str = r#"
+    const pane = document.getElementById("log-pane");
+    if (pane) { pane.scrollTop = pane.scrollHeight; }
+"#;
+
+/// Streams the output of one container. The stream is restarted whenever the
+/// request options change and cancelled with the component.
+#[component]
+pub fn LogPane(engine: String, id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut follow = use_signal(|| true);
+    let mut timestamps = use_signal(|| false);
+    let mut tail = use_signal(|| "200".to_string());
+    let mut since = use_signal(String::new);
+    let mut until = use_signal(String::new);
+    let mut auto_scroll = use_signal(|| true);
+    let mut buffer = use_signal(LogBuffer::default);
+    let mut streaming = use_signal(|| false);
+    let mut stream_error = use_signal(|| None::<String>);
+    let mut task = use_signal(|| None::<Task>);
+
+    let restart = use_callback(move |_: ()| {
+        if let Some(previous) = task.write().take() {
+            previous.cancel();
+        }
+        buffer.set(LogBuffer::default());
+        streaming.set(false);
+
+        let now = unix_now();
+        let options = log_options(
+            &tail.peek(),
+            &since.peek(),
+            &until.peek(),
+            follow(),
+            timestamps(),
+            now,
+        );
+        let options = match options {
+            Ok(options) => options,
+            Err(e) => {
+                stream_error.set(Some(format!("{e:#}")));
+                return;
+            }
+        };
+        let Some(service) = app_state.service(&engine) else {
+            stream_error.set(Some(format!("Engine {engine} is not connected")));
+            return;
+        };
+        stream_error.set(None);
+
+        let id = id.clone();
+        let handle = spawn(async move {
+            streaming.set(true);
+            let with_timestamps = options.timestamps;
+            let mut chunks = service.logs(&id, options);
+            while let Some(chunk) = chunks.next().await {
+                match chunk {
+                    Ok(chunk) => buffer.write().push_chunk(&chunk, with_timestamps),
+                    Err(e) => {
+                        stream_error.set(Some(describe_error(&e)));
+                        break;
+                    }
+                }
+            }
+            buffer.write().flush(with_timestamps);
+            streaming.set(false);
+        });
+        task.set(Some(handle));
+    });
+
+    // Runs on mount and again whenever the follow or timestamp toggles change,
+    // since both are read by `restart`; text fields apply on "Apply"
+    use_effect(move || restart.call(()));
+
+    // Keep the newest line in view unless scrolling is paused
+    use_effect(move || {
+        buffer.read();
+        if auto_scroll() {
+            let _ = document::eval(SCROLL_TO_END);
+        }
+    });
+
+    let show_timestamps = timestamps();
+    let log = buffer.read();
+    let line_count = log.len();
+    let dropped = log.dropped();
+    let status = if streaming() {
+        if follow() { "Following" } else { "Loading" }
+    } else {
+        "Stopped"
+    };
+    let lines: Vec<_> = log
+        .lines()
+        .map(|line| {
+            let class = match line.stream {
+                LogStream::Stdout => "log-line stdout",
+                LogStream::Stderr => "log-line stderr",
+            };
+            (class, line.timestamp.clone(), parse_ansi(&line.text))
+        })
+        .collect();
+    drop(log);
+
+    rsx! {
+        div { class: "card log-card",
+            div { class: "log-controls",
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: follow(),
+                        onchange: move |event| follow.set(event.checked())
+                    }
+                    "Follow"
+                }
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: show_timestamps,
+                        onchange: move |event| timestamps.set(event.checked())
+                    }
+                    "Timestamps"
+                }
+                label { class: "log-field",
+                    "Tail"
+                    input {
+                        class: "text-input",
+                        value: "{tail}",
+                        placeholder: "all",
+                        oninput: move |event| tail.set(event.value())
+                    }
+                }
+                label { class: "log-field",
+                    "Since"
+                    input {
+                        class: "text-input",
+                        value: "{since}",
+                        placeholder: "15m or Unix time",
+                        oninput: move |event| since.set(event.value())
+                    }
+                }
+                label { class: "log-field",
+                    "Until"
+                    input {
+                        class: "text-input",
+                        value: "{until}",
+                        placeholder: "Unix time",
+                        oninput: move |event| until.set(event.value())
+                    }
+                }
+                button {
+                    class: "button primary",
+                    onclick: move |_| restart.call(()),
+                    "Apply"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| {
+                        let paused = auto_scroll();
+                        auto_scroll.set(!paused);
+                    },
+                    if auto_scroll() { "Pause scroll" } else { "Resume scroll" }
+                }
+            }
+
+            if let Some(error) = stream_error() {
+                div { class: "error-message", "⚠️ {error}" }
+            }
+
+            div { class: "log-status",
+                span { "{status} · {line_count} lines" }
+                if dropped > 0 {
+                    span { " · {dropped} older lines discarded" }
+                }
+            }
+
+            div { id: LOG_PANE_ID, class: "log-pane",
+                for (class, timestamp, spans) in lines {
+                    div { class,
+                        if show_timestamps {
+                            if let Some(timestamp) = timestamp {
+                                span { class: "log-timestamp", "{timestamp}" }
+                            }
+                        }
+                        for run in spans {
+                            span { class: run.class, "{run.text}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Builds the request from the pane's inputs. An empty or `all` tail means
+/// the whole history.
+fn log_options(
+    tail: &str,
+    since: &str,
+    until: &str,
+    follow: bool,
+    timestamps: bool,
+    now: i64,
+) -> anyhow::Result<LogOptions> {
+    let tail = match tail.trim() {
+        "" | "all" => None,
+        value => Some(
+            value
+                .parse::<u32>()
+                .map_err(|_| anyhow::anyhow!("Tail must be a number of lines or \"all\""))?,
+        ),
+    };
+    Ok(LogOptions {
+        follow,
+        tail,
+        since: parse_time_bound(since, now)?,
+        until: parse_time_bound(until, now)?,
+        timestamps,
+    })
+}
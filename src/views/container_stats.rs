@@ -0,0 +1,200 @@
+use std::collections::{HashMap, HashSet};
+
+use dioxus::core::Task;
+use dioxus::prelude::*;
+use futures_util::StreamExt;
+
+use crate::services::{ContainerInfo, ContainerState, ContainerStats};
+use crate::utils::{AppState, StatsHistory, format_bytes, sparkline_points};
+
+const SPARKLINE_WIDTH: f64 = 240.0;
+const SPARKLINE_HEIGHT: f64 = 48.0;
+
+/// (engine, container id)
+pub type StatsKey = (String, String);
+
+/// Streams stats for every running container picked by `select` and keeps a
+/// short history of each. Streams start and stop as containers come and go,
+/// and are all cancelled with the calling component.
+pub fn use_container_stats(
+    select: impl Fn(&ContainerInfo) -> bool + 'static,
+) -> Signal<HashMap<StatsKey, StatsHistory>> {
+    let app_state = use_context::<AppState>();
+    let mut history = use_signal(HashMap::<StatsKey, StatsHistory>::new);
+    let mut streams = use_signal(HashMap::<StatsKey, Task>::new);
+
+    use_effect(move || {
+        let wanted: HashSet<StatsKey> = app_state
+            .containers
+            .read()
+            .iter()
+            .filter(|c| c.state == ContainerState::Running && select(c))
+            .map(|c| (c.engine.clone(), c.id.clone()))
+            .collect();
+
+        // A copy for the tasks, which cannot use `streams` while it is borrowed
+        let mut ended = streams;
+        let mut running = streams.write();
+        running.retain(|key, task| {
+            let keep = wanted.contains(key);
+            if !keep {
+                task.cancel();
+            }
+            keep
+        });
+        history.write().retain(|key, _| wanted.contains(key));
+
+        for key in wanted {
+            if running.contains_key(&key) {
+                continue;
+            }
+            let Some(service) = app_state.service(&key.0) else {
+                continue;
+            };
+            let task_key = key.clone();
+            let task = spawn(async move {
+                let mut samples = service.stats(&task_key.1);
+                while let Some(Ok(sample)) = samples.next().await {
+                    history
+                        .write()
+                        .entry(task_key.clone())
+                        .or_default()
+                        .push(sample);
+                }
+                // Ended or failed: the next refresh starts a new stream if the
+                // container is still running
+                ended.write().remove(&task_key);
+            });
+            running.insert(key, task);
+        }
+    });
+
+    history
+}
+
+/// `docker stats`-style summary cells of the newest sample.
+pub struct StatsCells {
+    pub cpu: String,
+    pub memory: String,
+    /// Bytes moved since the previous sample, about a second earlier.
+    pub net: String,
+    pub block: String,
+    /// Bytes moved since the container started.
+    pub net_total: String,
+    pub block_total: String,
+}
+
+impl StatsCells {
+    pub fn new(sample: Option<&ContainerStats>) -> Self {
+        let Some(sample) = sample else {
+            return Self {
+                cpu: "--".to_string(),
+                memory: "--".to_string(),
+                net: "--".to_string(),
+                block: "--".to_string(),
+                net_total: "--".to_string(),
+                block_total: "--".to_string(),
+            };
+        };
+        let pair =
+            |first: u64, second: u64| format!("{} / {}", format_bytes(first), format_bytes(second));
+        let (delta, totals) = (sample.delta, sample.totals);
+        Self {
+            cpu: format!("{:.1}%", sample.cpu_percent),
+            memory: format!(
+                "{} ({:.1}%)",
+                pair(sample.memory_usage, sample.memory_limit),
+                sample.memory_percent()
+            ),
+            net: format!(
+                "↓ {}/s ↑ {}/s",
+                format_bytes(delta.net_rx),
+                format_bytes(delta.net_tx)
+            ),
+            block: format!(
+                "read {}/s write {}/s",
+                format_bytes(delta.block_read),
+                format_bytes(delta.block_write)
+            ),
+            net_total: pair(totals.net_rx, totals.net_tx),
+            block_total: pair(totals.block_read, totals.block_write),
+        }
+    }
+}
+
+/// Live charts of the last minute of stats for one container.
+#[component]
+pub fn StatsCharts(engine: String, id: String) -> Element {
+    let key = (engine.clone(), id.clone());
+    let history = use_container_stats(move |c| c.engine == engine && c.id == id);
+
+    let history = history.read();
+    let Some(stats) = history.get(&key) else {
+        return rsx! {
+            div { class: "card stats-card",
+                p { class: "card-hint", "Waiting for stats…" }
+            }
+        };
+    };
+    let cells = StatsCells::new(stats.latest());
+    let cpu = stats.series(|s| s.cpu_percent);
+    let memory = stats.series(|s| s.memory_usage as f64);
+    let net = stats.series(|s| (s.delta.net_rx + s.delta.net_tx) as f64);
+    let block = stats.series(|s| (s.delta.block_read + s.delta.block_write) as f64);
+    let memory_limit = stats
+        .latest()
+        .map(|s| s.memory_limit as f64)
+        .unwrap_or_default();
+    let net_total = format!("{} since start", cells.net_total);
+    let block_total = format!("{} since start", cells.block_total);
+
+    rsx! {
+        div { class: "cards stats-cards",
+            Sparkline { title: "CPU", value: cells.cpu, values: cpu, floor: 100.0 }
+            Sparkline { title: "Memory", value: cells.memory, values: memory, floor: memory_limit }
+            Sparkline {
+                title: "Network I/O",
+                value: cells.net,
+                hint: Some(net_total),
+                values: net,
+                floor: 1024.0
+            }
+            Sparkline {
+                title: "Block I/O",
+                value: cells.block,
+                hint: Some(block_total),
+                values: block,
+                floor: 1024.0
+            }
+        }
+    }
+}
+
+/// `floor` is the smallest top edge of the chart, so near-idle series stay
+/// close to the baseline.
+#[component]
+fn Sparkline(
+    title: &'static str,
+    value: String,
+    hint: Option<String>,
+    values: Vec<f64>,
+    floor: f64,
+) -> Element {
+    let points = sparkline_points(&values, SPARKLINE_WIDTH, SPARKLINE_HEIGHT, floor);
+
+    rsx! {
+        div { class: "card stats-card",
+            p { class: "card-title", "{title}" }
+            p { class: "stats-value", "{value}" }
+            svg {
+                class: "sparkline",
+                view_box: "0 0 {SPARKLINE_WIDTH} {SPARKLINE_HEIGHT}",
+                preserve_aspect_ratio: "none",
+                polyline { points }
+            }
+            if let Some(hint) = hint {
+                p { class: "card-hint", "{hint}" }
+            }
+        }
+    }
+}
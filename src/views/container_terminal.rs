@@ -0,0 +1,289 @@
+use dioxus::core::Task;
+use dioxus::prelude::*;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::services::{ExecOptions, describe_error};
+use crate::utils::AppState;
+
+const XTERM_JS: &str = "https://cdn.jsdelivr.net/npm/@xterm/xterm@5.5.0/lib/xterm.min.js";
+const XTERM_CSS: &str = "https://cdn.jsdelivr.net/npm/@xterm/xterm@5.5.0/css/xterm.min.css";
+const XTERM_FIT_JS: &str =
+    "https://cdn.jsdelivr.net/npm/@xterm/addon-fit@0.10.0/lib/addon-fit.min.js";
+
+const SHELLS: [&str; 3] = ["/bin/sh", "/bin/bash", "/bin/ash"];
+
+/// Mounts xterm.js in `#exec-terminal` and bridges it to Rust: keystrokes and
+/// size changes are sent as `TerminalEvent`s, output arrives as byte arrays,
+/// and `null` marks the end of the session. If xterm.js has not loaded after
+/// ten seconds the bridge reports `unavailable` and stops.
+const TERMINAL_BRIDGE: &str = r##"
+    const wait = () => new Promise((resolve) => setTimeout(resolve, 50));
+    let container = document.getElementById("exec-terminal");
+    for (let attempt = 0; !(window.Terminal && window.FitAddon && container); attempt++) {
+        if (attempt >= 200) {
+            dioxus.send({ kind: "unavailable" });
+            return;
+        }
+        await wait();
+        container = document.getElementById("exec-terminal");
+    }
+    container.innerHTML = "";
+
+    const term = new Terminal({
+        cursorBlink: true,
+        fontFamily: "ui-monospace, SFMono-Regular, Menlo, Consolas, monospace",
+        fontSize: 13,
+        theme: { background: "#0b0e15" },
+    });
+    const fit = new FitAddon.FitAddon();
+    term.loadAddon(fit);
+    term.open(container);
+    fit.fit();
+    dioxus.send({ kind: "resize", cols: term.cols, rows: term.rows });
+
+    term.onData((data) => dioxus.send({ kind: "input", data }));
+    term.onResize(({ cols, rows }) => dioxus.send({ kind: "resize", cols, rows }));
+    const observer = new ResizeObserver(() => fit.fit());
+    observer.observe(container);
+    term.focus();
+
+    while (true) {
+        const output = await dioxus.recv();
+        if (output === null) {
+            term.write("\r\n\x1b[2m[session ended]\x1b[0m\r\n");
+            observer.disconnect();
+            break;
+        }
+        term.write(new Uint8Array(output));
+    }
+"##;
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum TerminalEvent {
+    Input { data: String },
+    Resize { cols: u16, rows: u16 },
+    Unavailable,
+}
+
+enum Next {
+    Event(Option<TerminalEvent>),
+    Output(Option<anyhow::Result<Vec<u8>>>),
+}
+
+/// Exec form and embedded terminal for a running container.
+#[component]
+pub fn TerminalPane(engine: String, id: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut shell = use_signal(|| SHELLS[0].to_string());
+    let mut user = use_signal(String::new);
+    let mut working_dir = use_signal(String::new);
+    let mut env = use_signal(String::new);
+    let mut session = use_signal(|| None::<Task>);
+    // The terminal stays up after its session ends, so the last output and
+    // the end marker remain readable until the next connect
+    let mut opened = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let connect = move |_| {
+        let env = match ExecOptions::parse_env(&env.peek()) {
+            Ok(env) => env,
+            Err(e) => {
+                error.set(Some(format!("{e:#}")));
+                return;
+            }
+        };
+        let cmd: Vec<String> = shell
+            .peek()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        if cmd.is_empty() {
+            error.set(Some("Enter a command to run".to_string()));
+            return;
+        }
+        let Some(service) = app_state.service(&engine) else {
+            error.set(Some(format!("Engine {engine} is not connected")));
+            return;
+        };
+        let non_empty = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let mut options = ExecOptions {
+            cmd,
+            user: non_empty(&user.peek()),
+            working_dir: non_empty(&working_dir.peek()),
+            env,
+            cols: 80,
+            rows: 24,
+        };
+        error.set(None);
+        if let Some(previous) = session.write().take() {
+            previous.cancel();
+        }
+        opened.set(true);
+
+        let id = id.clone();
+        let task = spawn(async move {
+            let mut terminal = document::eval(TERMINAL_BRIDGE);
+
+            // The terminal reports its fitted size before anything else
+            match terminal.recv().await {
+                Ok(TerminalEvent::Resize { cols, rows }) => {
+                    options.cols = cols;
+                    options.rows = rows;
+                }
+                Ok(TerminalEvent::Unavailable) => {
+                    error.set(Some(
+                        "Could not load xterm.js from the jsDelivr CDN".to_string(),
+                    ));
+                    session.set(None);
+                    return;
+                }
+                _ => {}
+            }
+
+            let mut exec = match service.exec(&id, options).await {
+                Ok(exec) => exec,
+                Err(e) => {
+                    error.set(Some(format!(
+                        "Failed to start exec: {}",
+                        describe_error(&e)
+                    )));
+                    let _ = terminal.send(serde_json::Value::Null);
+                    session.set(None);
+                    return;
+                }
+            };
+
+            loop {
+                let next = tokio::select! {
+                    event = terminal.recv::<TerminalEvent>() => Next::Event(event.ok()),
+                    chunk = exec.output.next() => Next::Output(chunk),
+                };
+                match next {
+                    Next::Event(Some(TerminalEvent::Input { data })) => {
+                        if exec.input.write_all(data.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                    Next::Event(Some(TerminalEvent::Resize { cols, rows })) => {
+                        let _ = service.resize_exec(&exec.exec_id, cols, rows).await;
+                    }
+                    Next::Event(Some(TerminalEvent::Unavailable)) => {}
+                    // The terminal was unmounted
+                    Next::Event(None) => break,
+                    Next::Output(Some(Ok(bytes))) => {
+                        let _ = terminal.send(bytes);
+                    }
+                    Next::Output(Some(Err(e))) => {
+                        error.set(Some(describe_error(&e)));
+                        break;
+                    }
+                    Next::Output(None) => break,
+                }
+            }
+            let _ = terminal.send(serde_json::Value::Null);
+            session.set(None);
+        });
+        session.set(Some(task));
+    };
+
+    let connected = session.read().is_some();
+    let ended = opened() && !connected;
+    let shell_value = shell();
+    let custom_shell = !SHELLS.contains(&shell_value.as_str());
+
+    rsx! {
+        document::Script { src: XTERM_JS }
+        document::Script { src: XTERM_FIT_JS }
+        document::Link { rel: "stylesheet", href: XTERM_CSS }
+
+        div { class: "card terminal-card",
+            div { class: "log-controls",
+                label { class: "log-field",
+                    "Shell"
+                    select {
+                        class: "text-input",
+                        disabled: connected,
+                        onchange: move |event| shell.set(event.value()),
+                        for candidate in SHELLS {
+                            option { value: candidate, selected: shell_value == candidate, {candidate} }
+                        }
+                        option { value: "", selected: custom_shell, "Custom…" }
+                    }
+                }
+                if custom_shell {
+                    label { class: "log-field",
+                        "Command"
+                        input {
+                            class: "text-input",
+                            value: "{shell_value}",
+                            placeholder: "/usr/bin/zsh -l",
+                            disabled: connected,
+                            oninput: move |event| shell.set(event.value())
+                        }
+                    }
+                }
+                label { class: "log-field",
+                    "User"
+                    input {
+                        class: "text-input",
+                        value: "{user}",
+                        placeholder: "default",
+                        disabled: connected,
+                        oninput: move |event| user.set(event.value())
+                    }
+                }
+                label { class: "log-field",
+                    "Working dir"
+                    input {
+                        class: "text-input",
+                        value: "{working_dir}",
+                        placeholder: "default",
+                        disabled: connected,
+                        oninput: move |event| working_dir.set(event.value())
+                    }
+                }
+                if connected {
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| {
+                            if let Some(task) = session.write().take() {
+                                task.cancel();
+                            }
+                        },
+                        "Disconnect"
+                    }
+                } else {
+                    button { class: "button primary", onclick: connect, "Connect" }
+                }
+            }
+
+            if !connected {
+                label { class: "form-label", "Environment (one KEY=VALUE per line)" }
+                textarea {
+                    class: "text-input env-input",
+                    rows: 3,
+                    value: "{env}",
+                    oninput: move |event| env.set(event.value())
+                }
+            }
+
+            if let Some(error) = error() {
+                div { class: "error-message", "⚠️ {error}" }
+            }
+
+            if ended {
+                div { class: "log-status", "Session ended · Connect to start a new one" }
+            }
+
+            if opened() {
+                div { id: "exec-terminal", class: "terminal" }
+            }
+        }
+    }
+}
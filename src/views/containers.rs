@@ -1,14 +1,119 @@
 use dioxus::prelude::*;
 
-use crate::components::{SectionHeader, StatusPill};
-use crate::services::ContainerState;
-use crate::utils::AppState;
+use crate::Route;
+use crate::components::{
+    BulkBar, BulkReportPanel, ConfirmDialog, LoadIndicator, SectionHeader, SortHeader, StatusPill,
+    TableToolbar,
+};
+use crate::services::{
+    ContainerAction, ContainerInfo, DEFAULT_STOP_TIMEOUT_SECS, KillSignal, RemoveOptions,
+};
+use crate::utils::{
+    AppState, BulkResource, BulkTarget, Listing, Selection, SortColumn, facet_values, format_age,
+    grid_style, unix_now,
+};
+
+use super::container_stats::{StatsCells, use_container_stats};
+
+/// An action waiting for the user to confirm it in the dialog.
+#[derive(Clone, PartialEq)]
+struct PendingAction {
+    engine: String,
+    id: String,
+    name: String,
+    action: ContainerAction,
+}
 
 #[component]
 pub fn Containers() -> Element {
     let app_state = use_context::<AppState>();
     // AppState fields are Signals, call them to get the inner value
-    let containers = (app_state.containers)();
+    let mut tables = app_state.tables;
+    // (engine, id) of the row whose action menu is open
+    let mut open_menu = use_signal(|| None::<(String, String)>);
+    let mut confirming = use_signal(|| None::<PendingAction>);
+    let mut selection = use_signal(Selection::default);
+    // Bulk action waiting for confirmation
+    let mut bulk_confirming = use_signal(|| None::<ContainerAction>);
+    let app_state_for_request = app_state.clone();
+    let request = use_callback(move |pending: PendingAction| {
+        open_menu.set(None);
+        if pending.action.needs_confirmation() {
+            confirming.set(Some(pending));
+        } else {
+            app_state_for_request.run_container_action(
+                &pending.engine,
+                &pending.id,
+                pending.action,
+            );
+        }
+    });
+    let app_state_for_confirm = app_state.clone();
+    let app_state_for_bulk = app_state.clone();
+    // Runs on the checked rows that are currently visible
+    let run_bulk = use_callback(move |action: ContainerAction| {
+        let view = tables.peek().containers.clone();
+        let visible = view.apply(&app_state_for_bulk.containers.peek());
+        let targets = selection
+            .peek()
+            .pick(&visible)
+            .iter()
+            .map(bulk_target)
+            .collect();
+        app_state_for_bulk.run_bulk_container_action(targets, action);
+        selection.write().clear();
+        bulk_confirming.set(None);
+    });
+    let mut request_bulk = move |action: ContainerAction| {
+        if action.needs_confirmation() {
+            bulk_confirming.set(Some(action));
+        } else {
+            run_bulk.call(action);
+        }
+    };
+    let app_state_for_stats = app_state.clone();
+    let stats =
+        use_container_stats(move |_| app_state_for_stats.config.read().columns.container_stats);
+    let view = tables.read().containers.clone();
+    let all = (app_state.containers)();
+    let (states, labels) = facet_values(&all);
+    let containers = view.apply(&all);
+    let now = unix_now();
+    let selected_count = selection.read().pick(&containers).len();
+    let all_selected = selection.read().all_selected(&containers);
+    let visible = containers.clone();
+    let mut bulk_report = app_state.bulk_report;
+    let report = bulk_report().filter(|report| report.resource == BulkResource::Containers);
+    let bulk_busy = bulk_report
+        .read()
+        .as_ref()
+        .is_some_and(|report| report.pending() > 0);
+    let engine_names: Vec<String> = app_state
+        .engines
+        .read()
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+    let loading = app_state.loads.read().of(Listing::Containers).is_loading();
+    let busy_containers = app_state.busy_containers;
+    let columns = app_state.config.read().columns.clone();
+    let show_image = columns.container_image;
+    let show_ports = columns.container_ports;
+    let show_stats = columns.container_stats;
+    let row_style = grid_style(&[
+        ("32px", true),
+        ("2fr", true),
+        ("1fr", true),
+        ("1fr", true),
+        ("2fr", show_image),
+        ("1fr", show_ports),
+        ("0.7fr", show_stats),
+        ("1.3fr", show_stats),
+        ("1.3fr", show_stats),
+        ("1.3fr", show_stats),
+        ("1fr", true),
+        ("1fr", true),
+    ]);
 
     rsx! {
         SectionHeader {
@@ -16,61 +121,376 @@ pub fn Containers() -> Element {
             subtitle: Some("Manage running services".to_string())
         }
 
-        div { class: "action-bar",
+        TableToolbar {
+            view: view.clone(),
+            engines: engine_names,
+            states,
+            labels,
+            shown: containers.len(),
+            total: all.len(),
+            onchange: move |next| tables.write().containers = next,
+            LoadIndicator { list: Listing::Containers }
             button {
                 class: "button primary",
+                disabled: loading,
                 onclick: move |_| app_state.refresh_containers(),
                 "Refresh"
             }
+            Link { to: Route::RunContainer {}, class: "button secondary", "Run container" }
+        }
+
+        if selected_count > 0 {
+            BulkBar {
+                count: selected_count,
+                busy: bulk_busy,
+                onclear: move |_| selection.write().clear(),
+                button {
+                    class: "button secondary",
+                    onclick: move |_| request_bulk(ContainerAction::Start),
+                    "Start"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| request_bulk(ContainerAction::Stop),
+                    "Stop"
+                }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| request_bulk(ContainerAction::Restart {
+                        timeout_secs: DEFAULT_STOP_TIMEOUT_SECS,
+                    }),
+                    "Restart…"
+                }
+                button {
+                    class: "button danger",
+                    onclick: move |_| request_bulk(ContainerAction::Remove(RemoveOptions::default())),
+                    "Remove…"
+                }
+            }
+        }
+
+        if let Some(report) = report {
+            BulkReportPanel { report, ondismiss: move |_| bulk_report.set(None) }
         }
 
         div { class: "table",
-            div { class: "row header",
-                span { "Name" }
-                span { "Image" }
-                span { "Ports" }
-                span { "State" }
-                span { "Action" }
+            div { class: "row header", style: "{row_style}",
+                input {
+                    r#type: "checkbox",
+                    title: "Select all shown",
+                    checked: all_selected,
+                    onchange: move |event| selection.write().set_all(&visible, event.checked())
+                }
+                SortHeader {
+                    label: "Name",
+                    column: SortColumn::Name,
+                    sort: view.sort,
+                    onsort: move |column| tables.write().containers.toggle_sort(column)
+                }
+                span { "Engine" }
+                SortHeader {
+                    label: "Created",
+                    column: SortColumn::Created,
+                    sort: view.sort,
+                    onsort: move |column| tables.write().containers.toggle_sort(column)
+                }
+                if show_image {
+                    SortHeader {
+                        label: "Image",
+                        column: SortColumn::Image,
+                        sort: view.sort,
+                        onsort: move |column| tables.write().containers.toggle_sort(column)
+                    }
+                }
+                if show_ports {
+                    span { "Ports" }
+                }
+                if show_stats {
+                    span { "CPU" }
+                    span { "Memory" }
+                    span { "Net I/O" }
+                    span { "Block I/O" }
+                }
+                SortHeader {
+                    label: "State",
+                    column: SortColumn::State,
+                    sort: view.sort,
+                    onsort: move |column| tables.write().containers.toggle_sort(column)
+                }
+                span { "Actions" }
             }
 
             // Use an iterator expression in braces. Each closure builds an rsx! element.
             // Clone only the data we need so closures are 'static' friendly.
             {containers.iter().map(|container| {
+                let engine = container.engine.clone();
                 let id = container.id.clone();
                 let name = container.name.clone();
                 let status = container.status.clone();
                 let image = container.image.clone();
+                let age = format_age(container.created, now);
                 let ports = container.ports.clone();
-                let app_state_for_btn = app_state.clone();
+                let row_style = row_style.clone();
 
-                let next_state = if container.state == ContainerState::Running {
-                    ContainerState::Stopped
-                } else {
-                    ContainerState::Running
-                };
-                let button_label = container.state.action_label();
+                let primary = container.state.primary_action();
+                let primary_label = primary.map(|a| a.label()).unwrap_or_default();
+                let more: Vec<_> = container
+                    .state
+                    .available_actions()
+                    .into_iter()
+                    .filter(|action| Some(*action) != primary)
+                    .collect();
                 let pill_label = container.state.label();
                 let pill_class = container.state.css_class();
+                let health = container.health;
+                let row_key = (engine.clone(), id.clone());
+                let usage = StatsCells::new(
+                    stats.read().get(&row_key).and_then(|history| history.latest()),
+                );
+                let busy = busy_containers.read().contains(&row_key);
+                let menu_open = open_menu().as_ref() == Some(&row_key);
+                let pending = PendingAction {
+                    engine: engine.clone(),
+                    id: id.clone(),
+                    name: name.clone(),
+                    action: ContainerAction::Start,
+                };
+                let pending_for_primary = pending.clone();
+                let checked = selection.read().contains(container);
+                let item = container.clone();
 
                 rsx! {
-                    div { class: "row item",
+                    div { class: "row item", style: "{row_style}",
+                        input {
+                            r#type: "checkbox",
+                            checked,
+                            onchange: move |event| selection.write().set(&item, event.checked())
+                        }
                         div {
-                            p { class: "row-title", "{name}" }
+                            Link {
+                                to: Route::ContainerDetail { engine: engine.clone(), id: id.clone() },
+                                class: "row-title row-link",
+                                "{name}"
+                            }
                             p { class: "row-subtitle", "{status}" }
                         }
-                        span { "{image}" }
-                        span { "{ports}" }
-                        StatusPill { label: pill_label.to_string(), class_name: pill_class.to_string() }
-                        button {
-                            class: "button secondary",
-                            // closure captures owned clones above so it's safe to be 'static'
-                            onclick: move |_| app_state_for_btn.set_container_state(&id, next_state),
-                            "{button_label}"
+                        span { "{engine}" }
+                        span { "{age}" }
+                        if show_image {
+                            span { "{image}" }
+                        }
+                        if show_ports {
+                            span { "{ports}" }
+                        }
+                        if show_stats {
+                            span { class: "stats-cell", "{usage.cpu}" }
+                            span { class: "stats-cell", "{usage.memory}" }
+                            span {
+                                class: "stats-cell",
+                                title: "{usage.net_total} since start",
+                                "{usage.net}"
+                            }
+                            span {
+                                class: "stats-cell",
+                                title: "{usage.block_total} since start",
+                                "{usage.block}"
+                            }
+                        }
+                        div { class: "state-cell",
+                            StatusPill { label: pill_label, class_name: pill_class.to_string() }
+                            if busy {
+                                span { class: "spinner", title: "Action in progress" }
+                            }
+                            if let Some(health) = health {
+                                StatusPill {
+                                    label: health.label().to_string(),
+                                    class_name: health.css_class().to_string()
+                                }
+                            }
+                        }
+                        div { class: "row-actions",
+                            if let Some(action) = primary {
+                                button {
+                                    class: "button secondary",
+                                    disabled: busy,
+                                    onclick: move |_| request.call(PendingAction {
+                                        action,
+                                        ..pending_for_primary.clone()
+                                    }),
+                                    "{primary_label}"
+                                }
+                            }
+                            if !more.is_empty() {
+                                button {
+                                    class: "button secondary menu-toggle",
+                                    title: "More actions",
+                                    disabled: busy,
+                                    onclick: move |_| {
+                                        let next = (!menu_open).then(|| row_key.clone());
+                                        open_menu.set(next);
+                                    },
+                                    "⋯"
+                                }
+                            }
+                            if menu_open {
+                                div { class: "action-menu",
+                                    for action in more {
+                                        button {
+                                            class: menu_item_class(action),
+                                            onclick: {
+                                                let pending = pending.clone();
+                                                move |_| request.call(PendingAction { action, ..pending.clone() })
+                                            },
+                                            {action.label()}
+                                            if action.needs_confirmation() {
+                                                "…"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
                 }
             })}
+        }
+
+        if let Some(pending) = confirming() {
+            ConfirmDialog {
+                title: format!("{} {}?", pending.action.label(), pending.name),
+                message: confirm_message(pending.action),
+                confirm_label: pending.action.label().to_string(),
+                oncancel: move |_| confirming.set(None),
+                onconfirm: {
+                    let pending = pending.clone();
+                    move |_| {
+                        app_state_for_confirm.run_container_action(
+                            &pending.engine,
+                            &pending.id,
+                            pending.action,
+                        );
+                        confirming.set(None);
+                    }
+                },
+                ActionOptions {
+                    action: pending.action,
+                    onchange: move |action| {
+                        confirming.with_mut(|p| {
+                            if let Some(p) = p {
+                                p.action = action;
+                            }
+                        });
+                    }
+                }
+            }
+        }
 
+        if let Some(action) = bulk_confirming() {
+            ConfirmDialog {
+                title: format!("{} {selected_count} containers?", action.label()),
+                message: confirm_message(action),
+                confirm_label: action.label().to_string(),
+                oncancel: move |_| bulk_confirming.set(None),
+                onconfirm: move |_| run_bulk.call(action),
+                ActionOptions { action, onchange: move |action| bulk_confirming.set(Some(action)) }
+            }
         }
     }
 }
+
+fn bulk_target(container: &ContainerInfo) -> BulkTarget {
+    BulkTarget {
+        engine: container.engine.clone(),
+        id: container.id.clone(),
+        name: container.name.clone(),
+    }
+}
+
+fn menu_item_class(action: ContainerAction) -> &'static str {
+    if action.needs_confirmation() {
+        "menu-item danger"
+    } else {
+        "menu-item"
+    }
+}
+
+fn confirm_message(action: ContainerAction) -> String {
+    match action {
+        ContainerAction::Restart { .. } => {
+            "The container is stopped and started again; running processes are interrupted."
+                .to_string()
+        }
+        ContainerAction::Kill(_) => {
+            "The signal is sent immediately, without waiting for a clean shutdown.".to_string()
+        }
+        ContainerAction::Remove(_) => {
+            "The container and its writable layer are deleted. This cannot be undone.".to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Options of the action being confirmed: restart timeout, kill signal, or
+/// remove flags.
+#[component]
+fn ActionOptions(action: ContainerAction, onchange: EventHandler<ContainerAction>) -> Element {
+    match action {
+        ContainerAction::Restart { timeout_secs } => rsx! {
+            label { class: "form-label", "Stop timeout (seconds)" }
+            input {
+                class: "text-input",
+                r#type: "number",
+                min: "0",
+                value: "{timeout_secs}",
+                oninput: move |event| {
+                    if let Ok(timeout_secs) = event.value().parse() {
+                        onchange.call(ContainerAction::Restart { timeout_secs });
+                    }
+                }
+            }
+        },
+        ContainerAction::Kill(signal) => rsx! {
+            label { class: "form-label", "Signal" }
+            select {
+                class: "text-input",
+                onchange: move |event| {
+                    if let Some(signal) = KillSignal::from_name(&event.value()) {
+                        onchange.call(ContainerAction::Kill(signal));
+                    }
+                },
+                for option_signal in KillSignal::ALL {
+                    option {
+                        value: option_signal.name(),
+                        selected: option_signal == signal,
+                        {option_signal.name()}
+                    }
+                }
+            }
+        },
+        ContainerAction::Remove(options) => rsx! {
+            label { class: "checkbox",
+                input {
+                    r#type: "checkbox",
+                    checked: options.force,
+                    onchange: move |event| {
+                        let force = event.checked();
+                        onchange.call(ContainerAction::Remove(RemoveOptions { force, ..options }));
+                    }
+                }
+                "Force (kill the container if it is running)"
+            }
+            label { class: "checkbox",
+                input {
+                    r#type: "checkbox",
+                    checked: options.volumes,
+                    onchange: move |event| {
+                        let volumes = event.checked();
+                        onchange.call(ContainerAction::Remove(RemoveOptions { volumes, ..options }));
+                    }
+                }
+                "Also remove anonymous volumes"
+            }
+        },
+        _ => rsx! {},
+    }
+}
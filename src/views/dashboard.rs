@@ -1,8 +1,8 @@
 use dioxus::prelude::*;
 
-use crate::components::{MetricCard, SectionHeader};
+use crate::components::{MetricCard, SectionHeader, StatusPill};
 use crate::services::ContainerState;
-use crate::utils::AppState;
+use crate::utils::{AppState, EngineStatus, PRIMARY_ENGINE};
 
 #[component]
 pub fn Dashboard() -> Element {
@@ -11,8 +11,19 @@ pub fn Dashboard() -> Element {
     let containers = (app_state.containers)();
     let images = (app_state.images)();
     let volumes = (app_state.volumes)();
-    let docker_host = (app_state.docker_host)();
-    let error_message = (app_state.error_message)();
+    let endpoint = (app_state.endpoint)();
+    let context =
+        (app_state.active_context)().unwrap_or_else(|| "none (Settings host)".to_string());
+    let tls_label = match &endpoint.tls {
+        Some(tls) if tls.has_client_cert() => "mutual TLS",
+        Some(_) => "TLS",
+        None => "none",
+    };
+    let runtime = (app_state.engines)()
+        .into_iter()
+        .find(|engine| engine.name == PRIMARY_ENGINE)
+        .and_then(|engine| engine.runtime)
+        .unwrap_or_else(|| "detecting…".to_string());
 
     let running = containers
         .iter()
@@ -20,16 +31,26 @@ pub fn Dashboard() -> Element {
         .count();
     let stopped = containers.len().saturating_sub(running);
 
+    // Per-engine breakdown: (name, host, status, running, stopped, images, volumes)
+    let engine_rows: Vec<_> = (app_state.engines)()
+        .into_iter()
+        .map(|engine| {
+            let on_engine = |item_engine: &str| item_engine == engine.name;
+            let total = containers.iter().filter(|c| on_engine(&c.engine)).count();
+            let running = containers
+                .iter()
+                .filter(|c| on_engine(&c.engine) && c.state == ContainerState::Running)
+                .count();
+            let image_count = images.iter().filter(|i| on_engine(&i.engine)).count();
+            let volume_count = volumes.iter().filter(|v| on_engine(&v.engine)).count();
+            (engine, running, total - running, image_count, volume_count)
+        })
+        .collect();
+
     rsx! {
         SectionHeader {
             title: "Dashboard".to_string(),
-            subtitle: Some("Overview of your local Docker engine".to_string())
-        }
-
-        if let Some(error) = error_message {
-            div { class: "error-message",
-                "⚠️ {error}"
-            }
+            subtitle: Some("Overview of your Docker engines".to_string())
         }
 
         div { class: "action-bar",
@@ -44,7 +65,7 @@ pub fn Dashboard() -> Element {
             MetricCard {
                 title: "Running containers".to_string(),
                 value: running.to_string(),
-                hint: Some("Across all projects".to_string())
+                hint: Some("Across all engines".to_string())
             }
             MetricCard {
                 title: "Stopped containers".to_string(),
@@ -64,10 +85,49 @@ pub fn Dashboard() -> Element {
         }
 
         div { class: "card",
-            h3 { "Engine" }
-            p { class: "engine-row", "Host: {docker_host}" }
-            p { class: "engine-row", "Context: local" }
+            h3 { "Primary engine" }
+            p { class: "engine-row", "Runtime: {runtime}" }
+            p { class: "engine-row", "Host: {endpoint.host}" }
+            p { class: "engine-row", "TLS: {tls_label}" }
+            p { class: "engine-row", "Context: {context}" }
             p { class: "engine-row", "Compose: ready" }
         }
+
+        if engine_rows.len() > 1 {
+            div { class: "table engines-table",
+                div { class: "row header engines-row",
+                    span { "Engine" }
+                    span { "Status" }
+                    span { "Running" }
+                    span { "Stopped" }
+                    span { "Images" }
+                    span { "Volumes" }
+                }
+                for (engine, running, stopped, image_count, volume_count) in engine_rows {
+                    div { class: "row item engines-row",
+                        div {
+                            p { class: "row-title", "{engine.name}" }
+                            p { class: "row-subtitle", "{engine.endpoint.host}" }
+                            if let Some(runtime) = &engine.runtime {
+                                p { class: "row-subtitle", "{runtime}" }
+                            }
+                        }
+                        div {
+                            StatusPill {
+                                label: engine.status.label().to_string(),
+                                class_name: engine.status.css_class().to_string()
+                            }
+                            if let EngineStatus::Unreachable(reason) = &engine.status {
+                                p { class: "row-subtitle", "{reason}" }
+                            }
+                        }
+                        span { "{running}" }
+                        span { "{stopped}" }
+                        span { "{image_count}" }
+                        span { "{volume_count}" }
+                    }
+                }
+            }
+        }
     }
 }
@@ -1,12 +1,88 @@
 use dioxus::prelude::*;
 
-use crate::components::SectionHeader;
-use crate::utils::AppState;
+use crate::components::{
+    BulkBar, BulkReportPanel, ConfirmDialog, LoadIndicator, SectionHeader, SortHeader, TableToolbar,
+};
+use crate::services::{ContainerInfo, ImageInfo, ImageRemoveOptions, PruneScope};
+use crate::utils::{
+    AppState, BulkResource, BulkTarget, EngineStatus, Listing, Selection, SortColumn, facet_values,
+    format_age, format_date, grid_style, unix_now,
+};
+
+use super::pull_image::PullImageDialog;
 
 #[component]
 pub fn Images() -> Element {
     let app_state = use_context::<AppState>();
-    let images = (app_state.images)();
+    let mut tables = app_state.tables;
+    let view = tables.read().images.clone();
+    let all = (app_state.images)();
+    let (states, labels) = facet_values(&all);
+    let images = view.apply(&all);
+    let mut selection = use_signal(Selection::default);
+    // Images waiting for the user to confirm their removal
+    let mut removing = use_signal(|| None::<Vec<ImageInfo>>);
+    let mut remove_options = use_signal(ImageRemoveOptions::default);
+    let mut tagging = use_signal(|| None::<ImageInfo>);
+    let mut pruning = use_signal(|| None::<PruneScope>);
+    // `(engine, reference)` of an image's last tag, waiting for confirmation
+    let mut untagging = use_signal(|| None::<(String, String)>);
+    let mut pulling = use_signal(|| false);
+    let selected = selection.read().pick(&images);
+    let selected_count = selected.len();
+    let all_selected = selection.read().all_selected(&images);
+    let visible = images.clone();
+    let mut bulk_report = app_state.bulk_report;
+    let report = bulk_report().filter(|report| report.resource == BulkResource::Images);
+    let bulk_busy = bulk_report
+        .read()
+        .as_ref()
+        .is_some_and(|report| report.pending() > 0);
+    let app_state_for_remove = app_state.clone();
+    let app_state_for_untag = app_state.clone();
+    let app_state_for_last_tag = app_state.clone();
+    let app_state_for_prune = app_state.clone();
+    let containers = app_state.containers;
+    let used_by: Vec<usize> = images
+        .iter()
+        .map(|image| {
+            let containers = containers.read();
+            containers.iter().filter(|c| image.is_used_by(c)).count()
+        })
+        .collect();
+    let now = unix_now();
+    let engine_names: Vec<String> = app_state
+        .engines
+        .read()
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+    // Prunes follow the engine filter, like the rows shown
+    let prune_engines: Vec<String> = app_state
+        .engines
+        .read()
+        .iter()
+        .filter(|e| e.status == EngineStatus::Connected)
+        .map(|e| e.name.clone())
+        .filter(|name| view.engine.as_ref().is_none_or(|engine| engine == name))
+        .collect();
+    let loading = app_state.loads.read().of(Listing::Images).is_loading();
+    let columns = app_state.config.read().columns.clone();
+    let row_style = grid_style(&[
+        ("32px", true),
+        ("1.6fr", true),
+        ("1fr", true),
+        ("2.2fr", true),
+        ("1fr", true),
+        ("1fr", columns.image_id),
+        ("0.8fr", true),
+        ("0.8fr", columns.image_size),
+        ("1.2fr", true),
+    ]);
+    let mut request_remove = move |images: Vec<ImageInfo>| {
+        remove_options.set(ImageRemoveOptions::default());
+        removing.set(Some(images));
+    };
 
     rsx! {
         SectionHeader {
@@ -14,29 +90,357 @@ pub fn Images() -> Element {
             subtitle: Some("Local image cache".to_string())
         }
 
-        div { class: "action-bar",
+        TableToolbar {
+            view: view.clone(),
+            engines: engine_names,
+            states,
+            labels,
+            shown: images.len(),
+            total: all.len(),
+            onchange: move |next| tables.write().images = next,
+            LoadIndicator { list: Listing::Images }
             button {
                 class: "button primary",
+                disabled: loading,
                 onclick: move |_| app_state.refresh_images(),
                 "Refresh"
             }
+            button { class: "button secondary", onclick: move |_| pulling.set(true), "Pull image" }
+            button {
+                class: "button secondary",
+                onclick: move |_| pruning.set(Some(PruneScope::Dangling)),
+                "Prune dangling…"
+            }
+            button {
+                class: "button secondary",
+                onclick: move |_| pruning.set(Some(PruneScope::Unused)),
+                "Prune unused…"
+            }
+        }
+
+        if selected_count > 0 {
+            BulkBar {
+                count: selected_count,
+                busy: bulk_busy,
+                onclear: move |_| selection.write().clear(),
+                button {
+                    class: "button danger",
+                    onclick: {
+                        let selected = selected.clone();
+                        move |_| request_remove(selected.clone())
+                    },
+                    "Remove…"
+                }
+            }
+        }
+
+        if let Some(report) = report {
+            BulkReportPanel { report, ondismiss: move |_| bulk_report.set(None) }
         }
 
         div { class: "table",
-            div { class: "row header",
-                span { "Repository" }
-                span { "Tag" }
-                span { "Image ID" }
-                span { "Size" }
+            div { class: "row header", style: "{row_style}",
+                input {
+                    r#type: "checkbox",
+                    title: "Select all shown",
+                    checked: all_selected,
+                    onchange: move |event| selection.write().set_all(&visible, event.checked())
+                }
+                SortHeader {
+                    label: "Repository",
+                    column: SortColumn::Name,
+                    sort: view.sort,
+                    onsort: move |column| tables.write().images.toggle_sort(column)
+                }
+                span { "Engine" }
+                span { "Tags" }
+                SortHeader {
+                    label: "Created",
+                    column: SortColumn::Created,
+                    sort: view.sort,
+                    onsort: move |column| tables.write().images.toggle_sort(column)
+                }
+                if columns.image_id {
+                    span { "Image ID" }
+                }
+                span { "Containers" }
+                if columns.image_size {
+                    SortHeader {
+                        label: "Size",
+                        column: SortColumn::Size,
+                        sort: view.sort,
+                        onsort: move |column| tables.write().images.toggle_sort(column)
+                    }
+                }
+                span { "Actions" }
+            }
+            for (image, used_by) in images.into_iter().zip(used_by) {
+                div { class: "row item images-row", style: "{row_style}",
+                    input {
+                        r#type: "checkbox",
+                        checked: selection.read().contains(&image),
+                        onchange: {
+                            let item = image.clone();
+                            move |event: FormEvent| selection.write().set(&item, event.checked())
+                        }
+                    }
+                    span { {repository(&image)} }
+                    span { "{image.engine}" }
+                    div { class: "image-tags",
+                        for tag in image.tags.iter() {
+                            span { class: "image-tag",
+                                {tag.to_string()}
+                                button {
+                                    class: "image-tag-remove",
+                                    title: "Remove this tag; the image goes with its last tag",
+                                    onclick: {
+                                        let app_state = app_state_for_untag.clone();
+                                        let engine = image.engine.clone();
+                                        let reference = tag.to_string();
+                                        let last = image.tags.len() == 1;
+                                        move |_| {
+                                            if last {
+                                                untagging.set(Some((engine.clone(), reference.clone())));
+                                            } else {
+                                                app_state.remove_tag(&engine, &reference);
+                                            }
+                                        }
+                                    },
+                                    "×"
+                                }
+                            }
+                        }
+                        for digest in image.digests.iter() {
+                            span { class: "card-hint image-digest", title: "{digest}",
+                                {short_digest(digest)}
+                            }
+                        }
+                    }
+                    span { title: format_age(image.created, now), {format_date(image.created)} }
+                    if columns.image_id {
+                        span { title: "{image.id}", {image.short_id()} }
+                    }
+                    span { "{used_by}" }
+                    if columns.image_size {
+                        span { "{image.size}" }
+                    }
+                    div { class: "row-actions",
+                        button {
+                            class: "button secondary",
+                            onclick: {
+                                let image = image.clone();
+                                move |_| tagging.set(Some(image.clone()))
+                            },
+                            "Tag…"
+                        }
+                        button {
+                            class: "button danger",
+                            onclick: {
+                                let image = image.clone();
+                                move |_| request_remove(vec![image.clone()])
+                            },
+                            "Remove…"
+                        }
+                    }
+                }
+            }
+        }
+
+        if pulling() {
+            PullImageDialog { onclose: move |_| pulling.set(false) }
+        }
+
+        if let Some(images) = removing() {
+            ConfirmDialog {
+                title: remove_title(&images),
+                message: "Tags of the image are removed with it. Images with several tags, or used by a stopped container, are only removed when forced; images a running container uses cannot be removed.".to_string(),
+                confirm_label: "Remove".to_string(),
+                oncancel: move |_| removing.set(None),
+                onconfirm: {
+                    let images = images.clone();
+                    move |_| {
+                        let targets = images.iter().map(image_target).collect();
+                        app_state_for_remove.remove_images(targets, remove_options());
+                        selection.write().clear();
+                        removing.set(None);
+                    }
+                },
+                RemoveImageOptions {
+                    users: image_users(&images, &containers.read()),
+                    options: remove_options(),
+                    onchange: move |options| remove_options.set(options)
+                }
             }
-            for image in images {
-                div { class: "row item images-row",
-                    span { "{image.repository}" }
-                    span { "{image.tag}" }
-                    span { "{image.id}" }
-                    span { "{image.size}" }
+        }
+
+        if let Some(image) = tagging() {
+            TagImageDialog { image, onclose: move |_| tagging.set(None) }
+        }
+
+        if let Some((engine, reference)) = untagging() {
+            ConfirmDialog {
+                title: format!("Remove {reference}?"),
+                message: "This is the image's last tag, so the image is deleted along with it.".to_string(),
+                confirm_label: "Remove".to_string(),
+                oncancel: move |_| untagging.set(None),
+                onconfirm: {
+                    let reference = reference.clone();
+                    move |_| {
+                        app_state_for_last_tag.remove_tag(&engine, &reference);
+                        untagging.set(None);
+                    }
                 }
             }
         }
+
+        if let Some(scope) = pruning() {
+            ConfirmDialog {
+                title: format!("{}?", scope.label()),
+                message: prune_message(scope, &prune_engines),
+                confirm_label: "Prune".to_string(),
+                oncancel: move |_| pruning.set(None),
+                onconfirm: {
+                    let engines = prune_engines.clone();
+                    move |_| {
+                        app_state_for_prune.prune_images(&engines, scope);
+                        pruning.set(None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn remove_title(images: &[ImageInfo]) -> String {
+    match images {
+        [image] => format!("Remove {}?", image.name()),
+        _ => format!("Remove {} images?", images.len()),
+    }
+}
+
+/// Repository of the first tag, as `docker images` lists it.
+fn repository(image: &ImageInfo) -> String {
+    match image.tags.first() {
+        Some(tag) => tag.repository(),
+        None => "<none>".to_string(),
+    }
+}
+
+/// `repository@sha256:` and the first 12 hex digits of the digest.
+fn short_digest(digest: &str) -> String {
+    let Some((repository, hash)) = digest.split_once('@') else {
+        return digest.to_string();
+    };
+    match hash.split_once(':') {
+        Some((algorithm, hex)) if hex.len() > 12 => {
+            format!("{repository}@{algorithm}:{}…", &hex[..12])
+        }
+        _ => digest.to_string(),
+    }
+}
+
+fn prune_message(scope: PruneScope, engines: &[String]) -> String {
+    let images = match scope {
+        PruneScope::Dangling => "untagged images that no container uses",
+        PruneScope::Unused => "every image that no container uses, tagged or not",
+    };
+    match engines {
+        [] => "No engine is connected.".to_string(),
+        _ => format!("Deletes {images} on {}.", engines.join(", ")),
+    }
+}
+
+/// Names of the containers using any of `images`.
+fn image_users(images: &[ImageInfo], containers: &[ContainerInfo]) -> Vec<String> {
+    let mut users: Vec<String> = containers
+        .iter()
+        .filter(|container| images.iter().any(|image| image.is_used_by(container)))
+        .map(|container| container.name.clone())
+        .collect();
+    users.sort();
+    users.dedup();
+    users
+}
+
+/// Force and prune options of the remove dialog, and the containers that
+/// keep the images in use.
+#[component]
+fn RemoveImageOptions(
+    users: Vec<String>,
+    options: ImageRemoveOptions,
+    onchange: EventHandler<ImageRemoveOptions>,
+) -> Element {
+    let joined = users.join(", ");
+
+    rsx! {
+        if !users.is_empty() {
+            div { class: "test-result error-message",
+                "⚠️ In use by {joined}. Forcing deletes the image from under stopped containers; it still fails while any of them is running."
+            }
+        }
+        label { class: "checkbox",
+            input {
+                r#type: "checkbox",
+                checked: options.force,
+                onchange: move |event| onchange.call(ImageRemoveOptions { force: event.checked(), ..options })
+            }
+            "Force (remove even when tagged more than once or used by stopped containers)"
+        }
+        label { class: "checkbox",
+            input {
+                r#type: "checkbox",
+                checked: options.no_prune,
+                onchange: move |event| onchange.call(ImageRemoveOptions { no_prune: event.checked(), ..options })
+            }
+            "Keep untagged parent images"
+        }
+    }
+}
+
+/// Modal adding a tag to `image`.
+#[component]
+fn TagImageDialog(image: ImageInfo, onclose: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut reference = use_signal(String::new);
+    let mut problem = use_signal(|| None::<String>);
+    let short_id = image.short_id().to_string();
+
+    let submit = move |_| match app_state.tag_image(&image.engine, &image.id, &reference.peek()) {
+        Ok(()) => onclose.call(()),
+        Err(e) => problem.set(Some(format!("{e:#}"))),
+    };
+
+    rsx! {
+        div { class: "modal-backdrop", onclick: move |_| onclose.call(()),
+            div {
+                class: "modal card",
+                onclick: move |event| event.stop_propagation(),
+                h3 { "Tag image {short_id}" }
+                label { class: "form-label", "New tag" }
+                input {
+                    class: "text-input",
+                    placeholder: "registry.local/app:1.0",
+                    value: "{reference}",
+                    oninput: move |event| reference.set(event.value())
+                }
+                if let Some(problem) = problem() {
+                    div { class: "test-result error-message", "⚠️ {problem}" }
+                }
+                div { class: "button-row",
+                    button { class: "button secondary", onclick: move |_| onclose.call(()), "Cancel" }
+                    button { class: "button primary", onclick: submit, "Tag" }
+                }
+            }
+        }
+    }
+}
+
+/// Images are removed by id, so every tag goes with them. Tags are only
+/// used to name the image in the report.
+fn image_target(image: &ImageInfo) -> BulkTarget {
+    BulkTarget {
+        engine: image.engine.clone(),
+        id: image.id.clone(),
+        name: image.name(),
     }
 }
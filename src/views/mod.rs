@@ -9,9 +9,25 @@ pub use dashboard::Dashboard;
 mod containers;
 pub use containers::Containers;
 
+mod run_container;
+pub use run_container::RunContainer;
+
+mod container_detail;
+pub use container_detail::ContainerDetail;
+
+mod container_inspect;
+
+mod container_logs;
+
+mod container_stats;
+
+mod container_terminal;
+
 mod images;
 pub use images::Images;
 
+mod pull_image;
+
 mod volumes;
 pub use volumes::Volumes;
 
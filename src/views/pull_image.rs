@@ -0,0 +1,188 @@
+use dioxus::core::Task;
+use dioxus::prelude::*;
+use futures_util::StreamExt;
+
+use crate::services::{PullOptions, PullState, describe_error};
+use crate::utils::{AppState, PRIMARY_ENGINE};
+
+/// Platforms offered besides the engine's own.
+const PLATFORMS: [&str; 4] = [
+    "linux/amd64",
+    "linux/arm64",
+    "linux/arm/v7",
+    "windows/amd64",
+];
+
+/// Outcome of the latest pull started from the dialog.
+#[derive(Clone, Debug, PartialEq)]
+enum PullOutcome {
+    Done,
+    Cancelled,
+    Failed(String),
+}
+
+/// Modal that pulls an image and shows each layer's progress. Closing it
+/// cancels a pull still running.
+#[component]
+pub fn PullImageDialog(onclose: EventHandler<()>) -> Element {
+    let app_state = use_context::<AppState>();
+    let mut reference = use_signal(String::new);
+    let mut platform = use_signal(String::new);
+    let mut engine = use_signal(|| PRIMARY_ENGINE.to_string());
+    let mut pull_state = use_signal(|| None::<PullState>);
+    let mut outcome = use_signal(|| None::<PullOutcome>);
+    let mut problem = use_signal(|| None::<String>);
+    let mut task = use_signal(|| None::<Task>);
+    let running = task.read().is_some();
+    let engine_names: Vec<String> = app_state
+        .engines
+        .read()
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+    let selected_engine = engine();
+    let pull = pull_state();
+    let layers_summary = pull
+        .as_ref()
+        .map(|pull| format!("{} of {} layers", pull.layers_done(), pull.layers.len()))
+        .unwrap_or_default();
+
+    let start = move |_| {
+        let wanted = reference.peek().trim().to_string();
+        if wanted.is_empty() {
+            problem.set(Some("Enter an image, e.g. nginx:latest".to_string()));
+            return;
+        }
+        let engine = engine.peek().clone();
+        let Some(service) = app_state.service(&engine) else {
+            problem.set(Some(format!("Engine {engine} is not connected")));
+            return;
+        };
+        let chosen = platform.peek().clone();
+        let options = PullOptions {
+            reference: wanted.clone(),
+            platform: (!chosen.is_empty()).then_some(chosen),
+        };
+        problem.set(None);
+        outcome.set(None);
+        pull_state.set(Some(PullState::default()));
+
+        let app_state = app_state.clone();
+        let handle = spawn(async move {
+            let mut stream = service.pull_image(options);
+            let result = loop {
+                match stream.next().await {
+                    Some(Ok(step)) => pull_state.with_mut(|pull| {
+                        if let Some(pull) = pull {
+                            pull.apply(step);
+                        }
+                    }),
+                    Some(Err(e)) => break PullOutcome::Failed(describe_error(&e)),
+                    None => break PullOutcome::Done,
+                }
+            };
+            if result == PullOutcome::Done {
+                app_state.record_action(format!("Pulled {wanted}"));
+                app_state.refresh_engine_images(engine, service);
+            }
+            outcome.set(Some(result));
+            task.set(None);
+        });
+        task.set(Some(handle));
+    };
+    let cancel = move |_| {
+        if let Some(handle) = task.take() {
+            // Dropping the progress stream makes the engine stop the pull
+            handle.cancel();
+            outcome.set(Some(PullOutcome::Cancelled));
+        }
+    };
+
+    rsx! {
+        div { class: "modal-backdrop",
+            div { class: "modal card pull-dialog",
+                h3 { "Pull image" }
+
+                if engine_names.len() > 1 {
+                    label { class: "form-label", "Engine" }
+                    select {
+                        class: "text-input",
+                        disabled: running,
+                        onchange: move |event| engine.set(event.value()),
+                        for name in engine_names {
+                            option { value: "{name}", selected: name == selected_engine, "{name}" }
+                        }
+                    }
+                }
+
+                label { class: "form-label", "Image" }
+                input {
+                    class: "text-input",
+                    placeholder: "nginx:latest",
+                    disabled: running,
+                    value: "{reference}",
+                    oninput: move |event| reference.set(event.value())
+                }
+
+                label { class: "form-label", "Platform" }
+                select {
+                    class: "text-input",
+                    disabled: running,
+                    onchange: move |event| platform.set(event.value()),
+                    option { value: "", selected: platform().is_empty(), "Engine default" }
+                    for name in PLATFORMS {
+                        option { value: "{name}", selected: platform() == name, "{name}" }
+                    }
+                }
+
+                if let Some(problem) = problem() {
+                    div { class: "test-result error-message", "⚠️ {problem}" }
+                }
+
+                if let Some(pull) = pull {
+                    div { class: "pull-progress",
+                        p { class: "card-hint",
+                            "{layers_summary}"
+                            if let Some(status) = &pull.status {
+                                " · {status}"
+                            }
+                        }
+                        for layer in pull.layers.iter() {
+                            div { class: "pull-layer", key: "{layer.id}",
+                                span { class: "pull-layer-id", "{layer.id}" }
+                                progress {
+                                    class: "pull-layer-bar",
+                                    max: 100,
+                                    value: layer.percent() as i64,
+                                }
+                                span { class: "card-hint", {layer.phase.label()} }
+                            }
+                        }
+                    }
+                }
+
+                match outcome() {
+                    Some(PullOutcome::Done) => rsx! {
+                        div { class: "test-result success", "Image pulled" }
+                    },
+                    Some(PullOutcome::Cancelled) => rsx! {
+                        div { class: "test-result", "Pull cancelled" }
+                    },
+                    Some(PullOutcome::Failed(message)) => rsx! {
+                        div { class: "test-result error-message", "⚠️ Pull failed: {message}" }
+                    },
+                    None => rsx! {},
+                }
+
+                div { class: "button-row",
+                    if running {
+                        button { class: "button secondary", onclick: cancel, "Cancel pull" }
+                    } else {
+                        button { class: "button secondary", onclick: move |_| onclose.call(()), "Close" }
+                        button { class: "button primary", onclick: start, "Pull" }
+                    }
+                }
+            }
+        }
+    }
+}
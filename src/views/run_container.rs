@@ -0,0 +1,401 @@
+use dioxus::prelude::*;
+
+use crate::Route;
+use crate::components::SectionHeader;
+use crate::services::{
+    ExecOptions, PortMapping, RestartPolicy, RunSpec, describe_error, parse_cpus, parse_memory,
+    parse_mount, split_command,
+};
+use crate::utils::{AppState, PRIMARY_ENGINE};
+
+/// A mount being edited: a volume name or host path, and where it goes.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct MountRow {
+    source: String,
+    target: String,
+    read_only: bool,
+}
+
+/// The form's raw text, turned into a `RunSpec` on submit.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct RunForm {
+    image: String,
+    name: String,
+    command: String,
+    /// One `HOST:CONTAINER[/udp]` mapping per line.
+    ports: String,
+    mounts: Vec<MountRow>,
+    /// One `KEY=VALUE` entry per line.
+    env: String,
+    /// Comma separated.
+    networks: String,
+    restart: RestartPolicy,
+    max_retries: String,
+    cpus: String,
+    memory: String,
+    /// One `KEY=VALUE` entry per line.
+    labels: String,
+}
+
+impl RunForm {
+    /// Parses every field, collecting all problems instead of stopping at the
+    /// first.
+    fn spec(&self) -> Result<RunSpec, Vec<String>> {
+        let mut problems = Vec::new();
+        let mut check = |result: anyhow::Result<()>| {
+            if let Err(e) = result {
+                problems.push(format!("{e:#}"));
+            }
+        };
+        let mut spec = RunSpec {
+            image: self.image.trim().to_string(),
+            name: Some(self.name.trim().to_string()).filter(|name| !name.is_empty()),
+            networks: self
+                .networks
+                .split(',')
+                .map(str::trim)
+                .filter(|network| !network.is_empty())
+                .map(str::to_string)
+                .collect(),
+            ..Default::default()
+        };
+
+        check(split_command(&self.command).map(|command| spec.command = command));
+        for line in non_empty_lines(&self.ports) {
+            check(PortMapping::parse(line).map(|port| spec.ports.push(port)));
+        }
+        for row in &self.mounts {
+            if row.source.trim().is_empty() && row.target.trim().is_empty() {
+                continue;
+            }
+            let text = format!("{}:{}", row.source.trim(), row.target.trim());
+            check(parse_mount(&text).map(|mut mount| {
+                mount.read_only = row.read_only;
+                spec.mounts.push(mount);
+            }));
+        }
+        check(ExecOptions::parse_env(&self.env).map(|env| spec.env = env));
+        check(ExecOptions::parse_env(&self.labels).map(|labels| {
+            spec.labels = labels
+                .iter()
+                .filter_map(|label| label.split_once('='))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+        }));
+        spec.restart = self.restart;
+        if let RestartPolicy::OnFailure { .. } = self.restart {
+            let retries = match self.max_retries.trim() {
+                "" => Ok(0),
+                value => value
+                    .parse::<u32>()
+                    .map_err(|_| anyhow::anyhow!("Max retries must be a whole number")),
+            };
+            check(retries.map(|max_retries| {
+                spec.restart = RestartPolicy::OnFailure { max_retries };
+            }));
+        }
+        check(parse_cpus(&self.cpus).map(|cpus| spec.cpus = cpus));
+        check(parse_memory(&self.memory).map(|memory| spec.memory = memory));
+
+        if problems.is_empty() {
+            Ok(spec)
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+fn non_empty_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim).filter(|line| !line.is_empty())
+}
+
+/// Form that creates and starts a new container, like `docker run -d`.
+#[component]
+pub fn RunContainer() -> Element {
+    let app_state = use_context::<AppState>();
+    let navigator = use_navigator();
+    let mut form = use_signal(RunForm::default);
+    let mut engine = use_signal(|| PRIMARY_ENGINE.to_string());
+    let mut problems = use_signal(Vec::<String>::new);
+    let mut submitting = use_signal(|| false);
+
+    let engine_names: Vec<String> = app_state
+        .engines
+        .read()
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+    let selected_engine = engine();
+    let image_options: Vec<String> = app_state
+        .images
+        .read()
+        .iter()
+        .filter(|image| image.engine == selected_engine)
+        .flat_map(|image| image.tags.iter().map(|tag| tag.to_string()))
+        .collect();
+    let volume_options: Vec<String> = app_state
+        .volumes
+        .read()
+        .iter()
+        .filter(|volume| volume.engine == selected_engine)
+        .map(|volume| volume.name.clone())
+        .collect();
+    let current = form();
+    let on_failure = matches!(current.restart, RestartPolicy::OnFailure { .. });
+
+    let app_state_for_submit = app_state.clone();
+    let submit = move |_| {
+        // A second click while the first run is still being created would
+        // start a duplicate
+        if *submitting.peek() {
+            return;
+        }
+        let spec = match form.peek().spec() {
+            Ok(spec) => spec,
+            Err(found) => {
+                problems.set(found);
+                return;
+            }
+        };
+        let engine = engine.peek().clone();
+        let existing: Vec<_> = app_state_for_submit
+            .containers
+            .read()
+            .iter()
+            .filter(|c| c.engine == engine)
+            .cloned()
+            .collect();
+        let found = spec.conflicts(&existing);
+        if !found.is_empty() {
+            problems.set(found);
+            return;
+        }
+        let Some(service) = app_state_for_submit.service(&engine) else {
+            problems.set(vec![format!("Engine {engine} is not connected")]);
+            return;
+        };
+        problems.set(Vec::new());
+        submitting.set(true);
+
+        let app_state = app_state_for_submit.clone();
+        spawn(async move {
+            let label = spec.name.clone().unwrap_or_else(|| spec.image.clone());
+            match service.run_container(spec).await {
+                Ok(id) => {
+                    app_state.record_action(format!("Started container {label}"));
+                    app_state.refresh_containers();
+                    navigator.push(Route::ContainerDetail { engine, id });
+                }
+                Err(e) => problems.set(vec![format!(
+                    "Failed to run container: {}",
+                    describe_error(&e)
+                )]),
+            }
+            submitting.set(false);
+        });
+    };
+
+    rsx! {
+        SectionHeader {
+            title: "Run container".to_string(),
+            subtitle: Some("Create and start a new container".to_string())
+        }
+
+        div { class: "action-bar",
+            Link { to: Route::Containers {}, class: "button secondary", "← Containers" }
+        }
+
+        div { class: "card run-form",
+            if engine_names.len() > 1 {
+                label { class: "form-label", "Engine" }
+                select {
+                    class: "text-input",
+                    onchange: move |event| engine.set(event.value()),
+                    for name in engine_names {
+                        option { value: "{name}", selected: name == selected_engine, "{name}" }
+                    }
+                }
+            }
+
+            label { class: "form-label", "Image" }
+            input {
+                class: "text-input",
+                list: "run-image-options",
+                placeholder: "nginx:latest",
+                value: "{current.image}",
+                oninput: move |event| form.write().image = event.value()
+            }
+            datalist { id: "run-image-options",
+                for image in image_options {
+                    option { value: "{image}" }
+                }
+            }
+
+            div { class: "form-columns",
+                div {
+                    label { class: "form-label", "Name" }
+                    input {
+                        class: "text-input",
+                        placeholder: "generated",
+                        value: "{current.name}",
+                        oninput: move |event| form.write().name = event.value()
+                    }
+                }
+                div {
+                    label { class: "form-label", "Command" }
+                    input {
+                        class: "text-input",
+                        placeholder: "image default",
+                        value: "{current.command}",
+                        oninput: move |event| form.write().command = event.value()
+                    }
+                }
+            }
+
+            label { class: "form-label", "Ports (one HOST:CONTAINER per line, add /udp for UDP)" }
+            textarea {
+                class: "text-input",
+                rows: 3,
+                placeholder: "8080:80",
+                value: "{current.ports}",
+                oninput: move |event| form.write().ports = event.value()
+            }
+
+            label { class: "form-label", "Mounts" }
+            datalist { id: "run-volume-options",
+                for volume in volume_options {
+                    option { value: "{volume}" }
+                }
+            }
+            for (index, row) in current.mounts.iter().enumerate() {
+                div { class: "mount-row",
+                    input {
+                        class: "text-input",
+                        list: "run-volume-options",
+                        placeholder: "volume or /host/path",
+                        value: "{row.source}",
+                        oninput: move |event| form.write().mounts[index].source = event.value()
+                    }
+                    input {
+                        class: "text-input",
+                        placeholder: "/path/in/container",
+                        value: "{row.target}",
+                        oninput: move |event| form.write().mounts[index].target = event.value()
+                    }
+                    label { class: "checkbox",
+                        input {
+                            r#type: "checkbox",
+                            checked: row.read_only,
+                            onchange: move |event| {
+                                form.write().mounts[index].read_only = event.checked();
+                            }
+                        }
+                        "Read-only"
+                    }
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| {
+                            form.write().mounts.remove(index);
+                        },
+                        "Remove"
+                    }
+                }
+            }
+            button {
+                class: "button secondary",
+                onclick: move |_| form.write().mounts.push(MountRow::default()),
+                "Add mount"
+            }
+
+            label { class: "form-label", "Environment (one KEY=VALUE per line)" }
+            textarea {
+                class: "text-input",
+                rows: 3,
+                value: "{current.env}",
+                oninput: move |event| form.write().env = event.value()
+            }
+
+            label { class: "form-label", "Networks (comma separated, first one joined on creation)" }
+            input {
+                class: "text-input",
+                placeholder: "bridge",
+                value: "{current.networks}",
+                oninput: move |event| form.write().networks = event.value()
+            }
+
+            div { class: "form-columns",
+                div {
+                    label { class: "form-label", "Restart policy" }
+                    select {
+                        class: "text-input",
+                        onchange: move |event| {
+                            if let Some(policy) = RestartPolicy::ALL
+                                .into_iter()
+                                .find(|policy| policy.name() == event.value())
+                            {
+                                form.write().restart = policy;
+                            }
+                        },
+                        for policy in RestartPolicy::ALL {
+                            option {
+                                value: policy.name(),
+                                selected: policy.name() == current.restart.name(),
+                                {policy.name()}
+                            }
+                        }
+                    }
+                }
+                if on_failure {
+                    div {
+                        label { class: "form-label", "Max retries" }
+                        input {
+                            class: "text-input",
+                            placeholder: "unlimited",
+                            value: "{current.max_retries}",
+                            oninput: move |event| form.write().max_retries = event.value()
+                        }
+                    }
+                }
+                div {
+                    label { class: "form-label", "CPUs" }
+                    input {
+                        class: "text-input",
+                        placeholder: "unlimited",
+                        value: "{current.cpus}",
+                        oninput: move |event| form.write().cpus = event.value()
+                    }
+                }
+                div {
+                    label { class: "form-label", "Memory" }
+                    input {
+                        class: "text-input",
+                        placeholder: "e.g. 512m",
+                        value: "{current.memory}",
+                        oninput: move |event| form.write().memory = event.value()
+                    }
+                }
+            }
+
+            label { class: "form-label", "Labels (one KEY=VALUE per line)" }
+            textarea {
+                class: "text-input",
+                rows: 2,
+                value: "{current.labels}",
+                oninput: move |event| form.write().labels = event.value()
+            }
+
+            for problem in problems() {
+                div { class: "test-result error-message", "⚠️ {problem}" }
+            }
+
+            div { class: "button-row",
+                button {
+                    class: "button primary",
+                    disabled: submitting(),
+                    onclick: submit,
+                    if submitting() { "Starting..." } else { "Create and start" }
+                }
+            }
+        }
+    }
+}
@@ -1,41 +1,336 @@
 use dioxus::prelude::*;
 
 use crate::components::SectionHeader;
-use crate::utils::AppState;
+use crate::services::{Endpoint, EngineReport, TlsOptions, describe_error};
+use crate::utils::{AppConfig, AppState, EngineConfig, StartPage, Theme};
 
 #[component]
 pub fn Settings() -> Element {
     let app_state = use_context::<AppState>();
-    let mut docker_host = app_state.docker_host.clone();
+    // Edit a draft so the active connection and the config file only change on save
+    let mut draft = use_signal(|| app_state.config.peek().clone());
+    let mut test_result = use_signal(|| None::<Result<EngineReport, String>>);
+    let mut is_testing = use_signal(|| false);
+    let mut save_errors = use_signal(Vec::<String>::new);
+    let mut new_engine_name = use_signal(String::new);
+    let mut new_engine_host = use_signal(String::new);
+    let config_warnings = (app_state.config_warnings)();
     let app_state_for_test = app_state.clone();
     let app_state_for_save = app_state.clone();
 
+    let current = draft();
+    let columns = current.columns.clone();
+    let tls_paths = current.tls.as_ref().map(|tls| {
+        (
+            tls.ca.display().to_string(),
+            tls.cert.display().to_string(),
+            tls.key.display().to_string(),
+        )
+    });
+    let tls_enabled = tls_paths.is_some();
+
     rsx! {
         SectionHeader {
             title: "Settings".to_string(),
             subtitle: Some("Connection and preferences".to_string())
         }
 
-        div { class: "card",
+        for warning in config_warnings {
+            div { class: "error-message", "⚠️ {warning}" }
+        }
+
+        div { class: "card settings-card",
+            label { class: "checkbox",
+                input {
+                    r#type: "checkbox",
+                    checked: current.use_docker_context,
+                    onchange: move |event| draft.write().use_docker_context = event.checked()
+                }
+                "Follow the Docker CLI's current context (docker context use)"
+            }
+
             label { class: "form-label", "Docker host" }
             input {
                 class: "text-input",
-                value: docker_host,
-                oninput: move |event| docker_host.set(event.value()),
+                value: "{current.docker_host}",
+                oninput: move |event| draft.write().docker_host = event.value(),
                 placeholder: "unix:///var/run/docker.sock"
             }
+
+            label { class: "checkbox tls-toggle",
+                input {
+                    r#type: "checkbox",
+                    checked: tls_enabled,
+                    onchange: move |event| {
+                        draft.write().tls = if event.checked() {
+                            // Start from the Docker CLI's environment, or ~/.docker
+                            TlsOptions::from_env().or_else(|| {
+                                dirs::home_dir().map(|home| TlsOptions::from_cert_dir(home.join(".docker")))
+                            })
+                        } else {
+                            None
+                        };
+                    }
+                }
+                "Use TLS (tcp:// hosts)"
+            }
+
+            if let Some((ca, cert, key)) = tls_paths {
+                div { class: "tls-fields",
+                    label { class: "form-label", "CA certificate" }
+                    input {
+                        class: "text-input",
+                        value: "{ca}",
+                        oninput: move |event| {
+                            if let Some(tls) = draft.write().tls.as_mut() {
+                                tls.ca = event.value().into();
+                            }
+                        }
+                    }
+                    label { class: "form-label", "Client certificate" }
+                    input {
+                        class: "text-input",
+                        value: "{cert}",
+                        oninput: move |event| {
+                            if let Some(tls) = draft.write().tls.as_mut() {
+                                tls.cert = event.value().into();
+                            }
+                        }
+                    }
+                    label { class: "form-label", "Client key" }
+                    input {
+                        class: "text-input",
+                        value: "{key}",
+                        oninput: move |event| {
+                            if let Some(tls) = draft.write().tls.as_mut() {
+                                tls.key = event.value().into();
+                            }
+                        }
+                    }
+                }
+            }
+
             div { class: "button-row",
                 button {
                     class: "button",
-                    onclick: move |_| app_state_for_test.record_action("Tested Docker connection"),
-                    "Test connection"
+                    disabled: is_testing(),
+                    onclick: move |_| {
+                        let app_state = app_state_for_test.clone();
+                        let endpoint = draft.peek().endpoint();
+                        spawn(async move {
+                            is_testing.set(true);
+                            let result = app_state
+                                .test_connection(endpoint)
+                                .await
+                                .map_err(|e| describe_error(&e));
+                            test_result.set(Some(result));
+                            is_testing.set(false);
+                        });
+                    },
+                    if is_testing() { "Testing..." } else { "Test connection" }
+                }
+            }
+
+            match test_result() {
+                Some(Ok(report)) => {
+                    let latency_ms = report.latency.as_millis();
+                    let runtime = report.kind.label();
+                    rsx! {
+                        div { class: "test-result success",
+                            p { class: "engine-row", "Engine: {runtime} {report.engine_version} (API {report.api_version})" }
+                            p { class: "engine-row", "Platform: {report.os} / {report.arch}" }
+                            p { class: "engine-row", "Latency: {latency_ms} ms" }
+                        }
+                    }
+                }
+                Some(Err(error)) => rsx! {
+                    div { class: "test-result error-message", "⚠️ {error}" }
+                },
+                None => rsx! {},
+            }
+        }
+
+        div { class: "card settings-card",
+            h3 { "Engines" }
+            p { class: "card-hint",
+                "Additional engines are listed next to the primary connection. Changes apply on save."
+            }
+            for (index, engine) in current.engines.iter().enumerate() {
+                div { class: "engine-entry",
+                    div {
+                        p { class: "row-title", "{engine.name}" }
+                        p { class: "row-subtitle", "{engine.endpoint.host}" }
+                    }
+                    button {
+                        class: "button secondary",
+                        onclick: move |_| {
+                            draft.write().engines.remove(index);
+                        },
+                        "Remove"
+                    }
+                }
+            }
+            div { class: "engine-form",
+                input {
+                    class: "text-input",
+                    value: "{new_engine_name}",
+                    oninput: move |event| new_engine_name.set(event.value()),
+                    placeholder: "Name (e.g. build-server)"
+                }
+                input {
+                    class: "text-input",
+                    value: "{new_engine_host}",
+                    oninput: move |event| new_engine_host.set(event.value()),
+                    placeholder: "tcp://build.internal:2375"
                 }
+                button {
+                    class: "button",
+                    disabled: new_engine_name().trim().is_empty() || new_engine_host().trim().is_empty(),
+                    onclick: move |_| {
+                        draft.write().engines.push(EngineConfig {
+                            name: new_engine_name().trim().to_string(),
+                            endpoint: Endpoint {
+                                host: new_engine_host().trim().to_string(),
+                                tls: None,
+                            },
+                        });
+                        new_engine_name.set(String::new());
+                        new_engine_host.set(String::new());
+                    },
+                    "Add engine"
+                }
+            }
+        }
+
+        div { class: "card settings-card",
+            label { class: "checkbox",
+                input {
+                    r#type: "checkbox",
+                    checked: current.auto_refresh,
+                    onchange: move |event| draft.write().auto_refresh = event.checked()
+                }
+                "Refresh lists in the background (paused while the window is hidden)"
+            }
+
+            label { class: "form-label", "Refresh interval (seconds)" }
+            input {
+                class: "text-input",
+                r#type: "number",
+                min: "2",
+                value: "{current.refresh_interval_secs}",
+                oninput: move |event| {
+                    if let Ok(secs) = event.value().parse() {
+                        draft.write().refresh_interval_secs = secs;
+                    }
+                }
+            }
+
+            label { class: "form-label", "Theme" }
+            select {
+                class: "text-input",
+                value: current.theme.label(),
+                onchange: move |event| {
+                    if let Some(theme) = Theme::ALL.into_iter().find(|t| t.label() == event.value()) {
+                        draft.write().theme = theme;
+                    }
+                },
+                for theme in Theme::ALL {
+                    option { value: theme.label(), selected: theme == current.theme, {theme.label()} }
+                }
+            }
+
+            label { class: "form-label", "Start page" }
+            select {
+                class: "text-input",
+                value: current.start_page.label(),
+                onchange: move |event| {
+                    if let Some(page) = StartPage::ALL.into_iter().find(|p| p.label() == event.value()) {
+                        draft.write().start_page = page;
+                    }
+                },
+                for page in StartPage::ALL {
+                    option { value: page.label(), selected: page == current.start_page, {page.label()} }
+                }
+            }
+
+            label { class: "form-label", "Table columns" }
+            div { class: "checkbox-grid",
+                ColumnToggle {
+                    label: "Container image",
+                    checked: columns.container_image,
+                    onchange: move |on| draft.write().columns.container_image = on
+                }
+                ColumnToggle {
+                    label: "Container ports",
+                    checked: columns.container_ports,
+                    onchange: move |on| draft.write().columns.container_ports = on
+                }
+                ColumnToggle {
+                    label: "Container stats",
+                    checked: columns.container_stats,
+                    onchange: move |on| draft.write().columns.container_stats = on
+                }
+                ColumnToggle {
+                    label: "Image ID",
+                    checked: columns.image_id,
+                    onchange: move |on| draft.write().columns.image_id = on
+                }
+                ColumnToggle {
+                    label: "Image size",
+                    checked: columns.image_size,
+                    onchange: move |on| draft.write().columns.image_size = on
+                }
+                ColumnToggle {
+                    label: "Volume driver",
+                    checked: columns.volume_driver,
+                    onchange: move |on| draft.write().columns.volume_driver = on
+                }
+                ColumnToggle {
+                    label: "Volume mountpoint",
+                    checked: columns.volume_mountpoint,
+                    onchange: move |on| draft.write().columns.volume_mountpoint = on
+                }
+            }
+
+            for error in save_errors() {
+                div { class: "test-result error-message", "⚠️ {error}" }
+            }
+
+            div { class: "button-row",
                 button {
                     class: "button secondary",
-                    onclick: move |_| app_state_for_save.record_action("Saved settings"),
+                    onclick: move |_| {
+                        let (_, problems) = draft().validated();
+                        if problems.is_empty() {
+                            app_state_for_save.save_config(draft());
+                        }
+                        save_errors.set(problems);
+                    },
                     "Save"
                 }
+                button {
+                    class: "button secondary",
+                    onclick: move |_| {
+                        draft.set(AppConfig::default());
+                        save_errors.set(Vec::new());
+                    },
+                    "Reset to defaults"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ColumnToggle(label: &'static str, checked: bool, onchange: EventHandler<bool>) -> Element {
+    rsx! {
+        label { class: "checkbox",
+            input {
+                r#type: "checkbox",
+                checked,
+                onchange: move |event| onchange.call(event.checked())
             }
+            "{label}"
         }
     }
 }
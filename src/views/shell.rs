@@ -1,15 +1,48 @@
 use dioxus::prelude::*;
 
 use crate::Route;
-use crate::utils::AppState;
+use crate::components::NotificationCenter;
+use crate::utils::{AppState, StartPage};
+
+/// Reports every change of the window's visibility, e.g. when minimised.
+const WATCH_VISIBILITY: &str = r#"
+    document.addEventListener("visibilitychange", () => {
+        dioxus.send(document.visibilityState === "visible");
+    });
+"#;
 
 #[component]
 pub fn AppShell() -> Element {
     let app_state = use_context::<AppState>();
     let last_action = (app_state.last_action)();
+    let contexts = (app_state.contexts)();
+    let active_context = (app_state.active_context)();
+    let app_state_for_switch = app_state.clone();
+    let app_state_for_visibility = app_state.clone();
+    let theme_class = app_state.config.read().theme.css_class();
+
+    // Open the configured start page once, when the app launches on the dashboard
+    let navigator = use_navigator();
+    let route = use_route::<Route>();
+    use_hook(|| {
+        let start_page = app_state.config.peek().start_page;
+        if route == (Route::Dashboard {}) && start_page != StartPage::Dashboard {
+            navigator.replace(start_page.route());
+        }
+    });
+
+    // The shell lives as long as the window, so this listens for the whole session
+    use_hook(move || {
+        spawn(async move {
+            let mut visibility = document::eval(WATCH_VISIBILITY);
+            while let Ok(visible) = visibility.recv::<bool>().await {
+                app_state_for_visibility.set_window_visible(visible);
+            }
+        });
+    });
 
     rsx! {
-        div { class: "app-shell",
+        div { class: "app-shell {theme_class}",
             aside { class: "sidebar",
                 div { class: "brand", "Doctainr" }
                 nav { class: "nav-list",
@@ -26,8 +59,38 @@ pub fn AppShell() -> Element {
                         h1 { class: "app-title", "Doctainr Desktop" }
                         p { class: "app-subtitle", "Local engine workspace" }
                     }
-                    if let Some(action) = last_action {
-                        div { class: "header-action", "Last action: {action}" }
+                    div { class: "header-controls",
+                        if let Some(action) = last_action {
+                            div { class: "header-action", "Last action: {action}" }
+                        }
+                        NotificationCenter {}
+                        select {
+                            class: "context-select",
+                            title: "Docker context",
+                            onfocus: move |_| app_state.reload_contexts(),
+                            onchange: move |event| {
+                                let name = event.value();
+                                app_state_for_switch
+                                    .switch_context((!name.is_empty()).then_some(name));
+                            },
+                            option {
+                                value: "",
+                                selected: active_context.is_none(),
+                                "Settings host"
+                            }
+                            for context in contexts.contexts {
+                                option {
+                                    value: "{context.name}",
+                                    selected: active_context.as_deref() == Some(context.name.as_str()),
+                                    title: "{context.endpoint.host}",
+                                    if context.name == contexts.current {
+                                        "{context.name} (current)"
+                                    } else {
+                                        "{context.name}"
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
                 main { class: "page", Outlet::<Route> {} }
@@ -1,12 +1,50 @@
 use dioxus::prelude::*;
 
-use crate::components::SectionHeader;
-use crate::utils::AppState;
+use crate::components::{
+    BulkBar, BulkReportPanel, ConfirmDialog, LoadIndicator, SectionHeader, SortHeader, TableToolbar,
+};
+use crate::utils::{
+    AppState, BulkResource, BulkTarget, Listing, Selection, SortColumn, facet_values, grid_style,
+};
 
 #[component]
 pub fn Volumes() -> Element {
     let app_state = use_context::<AppState>();
-    let volumes = (app_state.volumes)();
+    let mut tables = app_state.tables;
+    let view = tables.read().volumes.clone();
+    let all = (app_state.volumes)();
+    let (states, labels) = facet_values(&all);
+    let volumes = view.apply(&all);
+    let mut selection = use_signal(Selection::default);
+    let mut confirming = use_signal(|| false);
+    let selected = selection.read().pick(&volumes);
+    let selected_count = selected.len();
+    let all_selected = selection.read().all_selected(&volumes);
+    let visible = volumes.clone();
+    let mut bulk_report = app_state.bulk_report;
+    let report = bulk_report().filter(|report| report.resource == BulkResource::Volumes);
+    let bulk_busy = bulk_report
+        .read()
+        .as_ref()
+        .is_some_and(|report| report.pending() > 0);
+    let app_state_for_remove = app_state.clone();
+    let engine_names: Vec<String> = app_state
+        .engines
+        .read()
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+    let loading = app_state.loads.read().of(Listing::Volumes).is_loading();
+    let columns = app_state.config.read().columns.clone();
+    let row_style = grid_style(&[
+        ("32px", true),
+        ("2fr", true),
+        ("1fr", true),
+        ("1fr", columns.volume_driver),
+        ("1.5fr", columns.volume_mountpoint),
+        ("1fr", true),
+        ("1fr", true),
+    ]);
 
     rsx! {
         SectionHeader {
@@ -14,29 +52,116 @@ pub fn Volumes() -> Element {
             subtitle: Some("Persistent storage".to_string())
         }
 
-        div { class: "action-bar",
+        TableToolbar {
+            view: view.clone(),
+            engines: engine_names,
+            states,
+            labels,
+            shown: volumes.len(),
+            total: all.len(),
+            onchange: move |next| tables.write().volumes = next,
+            LoadIndicator { list: Listing::Volumes }
             button {
                 class: "button primary",
+                disabled: loading,
                 onclick: move |_| app_state.refresh_volumes(),
                 "Refresh"
             }
         }
 
+        if selected_count > 0 {
+            BulkBar {
+                count: selected_count,
+                busy: bulk_busy,
+                onclear: move |_| selection.write().clear(),
+                button {
+                    class: "button danger",
+                    onclick: move |_| confirming.set(true),
+                    "Remove…"
+                }
+            }
+        }
+
+        if let Some(report) = report {
+            BulkReportPanel { report, ondismiss: move |_| bulk_report.set(None) }
+        }
+
         div { class: "table",
-            div { class: "row header",
-                span { "Name" }
-                span { "Driver" }
-                span { "Mountpoint" }
+            div { class: "row header", style: "{row_style}",
+                input {
+                    r#type: "checkbox",
+                    title: "Select all shown",
+                    checked: all_selected,
+                    onchange: move |event| selection.write().set_all(&visible, event.checked())
+                }
+                SortHeader {
+                    label: "Name",
+                    column: SortColumn::Name,
+                    sort: view.sort,
+                    onsort: move |column| tables.write().volumes.toggle_sort(column)
+                }
+                span { "Engine" }
+                if columns.volume_driver {
+                    span { "Driver" }
+                }
+                if columns.volume_mountpoint {
+                    span { "Mountpoint" }
+                }
+                SortHeader {
+                    label: "Created",
+                    column: SortColumn::Created,
+                    sort: view.sort,
+                    onsort: move |column| tables.write().volumes.toggle_sort(column)
+                }
                 span { "Size" }
             }
             for volume in volumes {
-                div { class: "row item volumes-row",
+                div { class: "row item volumes-row", style: "{row_style}",
+                    input {
+                        r#type: "checkbox",
+                        checked: selection.read().contains(&volume),
+                        onchange: {
+                            let item = volume.clone();
+                            move |event: FormEvent| selection.write().set(&item, event.checked())
+                        }
+                    }
                     span { "{volume.name}" }
-                    span { "{volume.driver}" }
-                    span { "{volume.mountpoint}" }
+                    span { "{volume.engine}" }
+                    if columns.volume_driver {
+                        span { "{volume.driver}" }
+                    }
+                    if columns.volume_mountpoint {
+                        span { "{volume.mountpoint}" }
+                    }
+                    // Only the date of the RFC 3339 timestamp
+                    span {
+                        {volume.created.as_deref().and_then(|created| created.get(..10)).unwrap_or("--")}
+                    }
                     span { "{volume.size}" }
                 }
             }
         }
+
+        if confirming() {
+            ConfirmDialog {
+                title: format!("Remove {selected_count} volumes?"),
+                message: "The volumes and all data in them are deleted. Volumes used by a container are not removed. This cannot be undone.".to_string(),
+                confirm_label: "Remove".to_string(),
+                oncancel: move |_| confirming.set(false),
+                onconfirm: move |_| {
+                    let targets = selected
+                        .iter()
+                        .map(|volume| BulkTarget {
+                            engine: volume.engine.clone(),
+                            id: volume.name.clone(),
+                            name: volume.name.clone(),
+                        })
+                        .collect();
+                    app_state_for_remove.remove_volumes(targets);
+                    selection.write().clear();
+                    confirming.set(false);
+                }
+            }
+        }
     }
 }